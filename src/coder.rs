@@ -1,12 +1,135 @@
 use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::str::FromStr;
+use crate::{Json, ToJson, FromJson};
+
+/// Code formatting style
+#[derive(Debug, Clone)]
+pub struct Style {
+    /// Indentation width in spaces
+    pub indent: usize,
+    /// Use tabs for indentation
+    pub tabs: bool,
+    /// Braces placement
+    pub braces: Braces,
+    /// Maximum line length to wrap long parameters lists (0 to disable)
+    pub max_line_length: usize,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            indent: 4,
+            tabs: false,
+            braces: Braces::SameLine,
+            max_line_length: 0,
+        }
+    }
+}
+
+impl Style {
+    fn indent(&self, level: usize) -> String {
+        if self.tabs {
+            "\t".repeat(level)
+        } else {
+            " ".repeat(level * self.indent)
+        }
+    }
+
+    /// Get line length taking into account indentation
+    fn line_length(&self, level: usize, src: &str) -> usize {
+        level * if self.tabs { 1 } else { self.indent } + src.chars().count()
+    }
+}
+
+/// Opening brace placement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Braces {
+    /// Put brace at the end of line
+    SameLine,
+    /// Put brace on the next line
+    NextLine,
+}
+
+impl FromStr for Braces {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        Ok(match s {
+            "same-line" => Braces::SameLine,
+            "next-line" => Braces::NextLine,
+            _ => return Err(format!("Unknown braces placement: {} (expected one of: same-line, next-line)", s)),
+        })
+    }
+}
+
+/// Set of imported libraries
+///
+/// The libraries is deduplicated and ordered like Dart style guide recommends:
+/// `dart:` libraries first, then `package:` libraries, then relative imports.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImportSet {
+    uris: BTreeSet<(u8, String)>,
+}
+
+impl ImportSet {
+    /// Add library to set
+    pub fn insert(&mut self, uri: impl Into<String>) {
+        let uri = uri.into();
+        let group = if uri.starts_with("dart:") {
+            0
+        } else if uri.starts_with("package:") {
+            1
+        } else {
+            2
+        };
+        self.uris.insert((group, uri));
+    }
+
+    /// Iterate over libraries in order
+    pub fn iter(&self) -> impl Iterator<Item = &str> {
+        self.uris.iter().map(|(_, uri)| uri.as_str())
+    }
+}
+
+impl ToJson for ImportSet {
+    fn to_json(&self) -> Json {
+        Json::Array(self.iter().map(|uri| Json::String(uri.into())).collect())
+    }
+}
+
+impl FromJson for ImportSet {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let mut imports = Self::default();
+        imports.extend(Vec::<String>::from_json(json)?);
+        Ok(imports)
+    }
+}
+
+impl<S: Into<String>> Extend<S> for ImportSet {
+    fn extend<I: IntoIterator<Item = S>>(&mut self, uris: I) {
+        for uri in uris {
+            self.insert(uri);
+        }
+    }
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct Coder {
+    style: Style,
     units: Chunks,
 }
 
 impl Coder {
+    /// Create coder with specific style
+    pub fn with_style(style: Style) -> Self {
+        Self {
+            style,
+            units: Chunks::default(),
+        }
+    }
+
     /// Append code line
     pub fn line(&mut self, src: impl Into<String>) {
         self.units.push(Chunk::Line(src.into()));
@@ -19,15 +142,115 @@ impl Coder {
         self.units.push(Chunk::Block(src.into(), cg.units));
     }
 
+    /// Append raw multi-line code
+    ///
+    /// The relative indentation of lines will be kept.
+    pub fn raw(&mut self, src: impl AsRef<str>) {
+        let src = src.as_ref();
+        let initial_spaces = src.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.chars().take_while(|c| c.is_whitespace()).count())
+            .min().unwrap_or(0);
+        
+        for line in src.trim_matches('\n').lines() {
+            self.line(line.get(initial_spaces..).unwrap_or("").trim_end());
+        }
+    }
+
+    /// Append separated list of items
+    ///
+    /// The list will be placed in single line when it fits, otherwise each item will be placed on separate line.
+    pub fn list(&mut self, open: impl Into<String>, items: impl IntoIterator<Item = impl Into<String>>,
+                sep: impl Into<String>, close: impl Into<String>) {
+        self.units.push(Chunk::List(List {
+            open: open.into(),
+            items: items.into_iter().map(|item| item.into()).collect(),
+            sep: sep.into(),
+            close: close.into(),
+        }));
+    }
+
+    /// Append call-like arguments list
+    pub fn arg_list(&mut self, head: impl AsRef<str>, args: impl IntoIterator<Item = impl Into<String>>,
+                    tail: impl AsRef<str>) {
+        self.list(format!("{}(", head.as_ref()), args, ",", format!("){}", tail.as_ref()));
+    }
+
+    /// Append constructor with arguments and initializer list
+    ///
+    /// The constructor without initializers will be terminated by semicolon.
+    pub fn initializer_list(&mut self, head: impl AsRef<str>, args: impl IntoIterator<Item = impl Into<String>>,
+                            inits: impl IntoIterator<Item = impl Into<String>>) {
+        let inits = inits.into_iter().map(|init| init.into()).collect::<Vec<_>>();
+        
+        if inits.is_empty() {
+            self.arg_list(head, args, ";");
+        } else {
+            self.arg_list(head, args, "");
+            self.units.push(Chunk::Initializers(inits, Chunks::default()));
+        }
+    }
+
+    /// Append constructor with arguments, initializer list and body
+    ///
+    /// The constructor without body will be terminated by semicolon like initializer list.
+    pub fn constructor(&mut self, head: impl AsRef<str>, args: impl IntoIterator<Item = impl Into<String>>,
+                       inits: impl IntoIterator<Item = impl Into<String>>, blk: impl FnOnce(&mut Coder)) {
+        let mut cg = Coder::default();
+        blk(&mut cg);
+
+        if cg.units.is_empty() {
+            return self.initializer_list(head, args, inits);
+        }
+        
+        let inits = inits.into_iter().map(|init| init.into()).collect::<Vec<_>>();
+        
+        if inits.is_empty() {
+            let args = args.into_iter().map(|arg| arg.into()).collect::<Vec<_>>();
+            self.units.push(Chunk::Block(format!("{}({})", head.as_ref(), args.join(", ")), cg.units));
+        } else {
+            self.arg_list(head, args, "");
+            self.units.push(Chunk::Initializers(inits, cg.units));
+        }
+    }
+
+    /// Append code conditionally
+    pub fn when(&mut self, cond: bool, blk: impl FnOnce(&mut Coder)) {
+        if cond {
+            blk(self);
+        }
+    }
+
+    /// Append commented section of code
+    ///
+    /// Nothing will be appended when section is empty.
+    pub fn section(&mut self, title: impl AsRef<str>, blk: impl FnOnce(&mut Coder)) {
+        let mut cg = Coder::default();
+        blk(&mut cg);
+        if !cg.units.is_empty() {
+            self.comment(title);
+            self.units.extend(cg.units);
+        }
+    }
+
+    /// Append top-level code of other coder which is missing in this one
+    pub fn union(&mut self, other: &Coder) {
+        for unit in &other.units {
+            if !self.units.contains(unit) {
+                self.units.push(unit.clone());
+            }
+        }
+    }
+
     /// Append comment
     pub fn comment(&mut self, src: impl AsRef<str>) {
         self.units.push(Chunk::Comment(unroll_comment(src.as_ref()).into()));
     }
 
     /// Format output
-    pub fn format(&self, f: &mut Formatter, l: usize) -> FmtResult {
+    pub fn format(&self, f: &mut Formatter, style: &Style, l: usize) -> FmtResult {
         for src in &self.units {
-            src.format(f, l)?;
+            src.format(f, style, l)?;
         }
         Ok(())
     }
@@ -35,53 +258,185 @@ impl Coder {
 
 impl Display for Coder {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        self.format(f, 0)
+        self.format(f, &self.style, 0)
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 enum Chunk {
     Line(String),
     Block(String, Chunks),
     Comment(String),
+    List(List),
+    Initializers(Vec<String>, Chunks),
+}
+
+/// Separated list of items
+#[derive(Debug, Clone, PartialEq)]
+struct List {
+    open: String,
+    items: Vec<String>,
+    sep: String,
+    close: String,
+}
+
+impl List {
+    fn format(&self, f: &mut Formatter, style: &Style, l: usize) -> FmtResult {
+        let indent = style.indent(l);
+        let inline = format!("{}{}{}", self.open, self.items.join(&format!("{} ", self.sep)), self.close);
+        
+        if self.items.is_empty() || style.max_line_length == 0 ||
+            style.line_length(l, &inline) <= style.max_line_length {
+            return writeln!(f, "{}{}", indent, inline);
+        }
+
+        let item_indent = style.indent(l + 1);
+        
+        writeln!(f, "{}{}", indent, self.open)?;
+        for (index, item) in self.items.iter().enumerate() {
+            let sep = if index + 1 < self.items.len() { self.sep.as_str() } else { "" };
+            writeln!(f, "{}{}{}", item_indent, item, sep)?;
+        }
+        writeln!(f, "{}{}", indent, self.close)
+    }
 }
 
 impl Chunk {
-    pub fn format(&self, f: &mut Formatter, l: usize) -> FmtResult {
+    pub fn format(&self, f: &mut Formatter, style: &Style, l: usize) -> FmtResult {
         use Chunk::*;
         
-        let indent = l * 4;
+        let indent = style.indent(l);
         match self {
-            Line(src) => writeln!(f, "{:indent$}{}", "", src, indent = indent),
+            Line(src) => format_line(f, style, l, src, ""),
             Block(src, units) => if units.is_empty() {
-                writeln!(f, "{:indent$}{} {{}}", "", src, indent = indent)
+                format_line(f, style, l, src, " {}")
             } else {
-                writeln!(f, "{:indent$}{} {{", "", src, indent = indent)?;
+                match style.braces {
+                    Braces::SameLine => format_line(f, style, l, src, " {")?,
+                    Braces::NextLine => {
+                        format_line(f, style, l, src, "")?;
+                        writeln!(f, "{}{{", indent)?;
+                    }
+                }
                 for src in units {
-                    src.format(f, l + 1)?;
+                    src.format(f, style, l + 1)?;
                 }
-                writeln!(f, "{:indent$}}}", "", indent = indent)
+                writeln!(f, "{}}}", indent)
             },
             Comment(src) => {
-                write!(f, "{:indent$}/*", "", indent = indent)?;
+                write!(f, "{}/*", indent)?;
                 let mut lines = src.lines();
                 if let Some(line) = lines.next() {
                     writeln!(f, "{}", line)?;
                     for line in lines {
-                        writeln!(f, "{:indent$} {}", "", line, indent = indent)?;
+                        writeln!(f, "{} {}", indent, line)?;
                     }
                 }
-                writeln!(f, "{:indent$} */", "", indent = indent)
+                writeln!(f, "{} */", indent)
+            },
+            List(list) => list.format(f, style, l),
+            Initializers(inits, units) => {
+                let init_indent = style.indent(l + 1);
+                let end = match (units.is_empty(), style.braces) {
+                    (true, _) => ";",
+                    (false, Braces::SameLine) => " {",
+                    (false, Braces::NextLine) => "",
+                };
+                for (index, init) in inits.iter().enumerate() {
+                    writeln!(f, "{}{}{}{}", init_indent,
+                             if index == 0 { ": " } else { "  " }, init,
+                             if index + 1 < inits.len() { "," } else { end })?;
+                }
+                if !units.is_empty() {
+                    if style.braces == Braces::NextLine {
+                        writeln!(f, "{}{{", indent)?;
+                    }
+                    for src in units {
+                        src.format(f, style, l + 1)?;
+                    }
+                    writeln!(f, "{}}}", indent)?;
+                }
+                Ok(())
             },
         }
     }
 }
 
+/// Format line wrapping long parameters list when it is required
+fn format_line(f: &mut Formatter, style: &Style, l: usize, src: &str, end: &str) -> FmtResult {
+    let indent = style.indent(l);
+    
+    if style.max_line_length > 0 && style.line_length(l, src) + end.len() > style.max_line_length {
+        if let Some((head, params, tail)) = split_params(src) {
+            let param_indent = style.indent(l + 2);
+            writeln!(f, "{}{}(", indent, head)?;
+            for (index, param) in params.iter().enumerate() {
+                if index + 1 < params.len() {
+                    writeln!(f, "{}{},", param_indent, param)?;
+                } else {
+                    writeln!(f, "{}{}){}{}", param_indent, param, tail, end)?;
+                }
+            }
+            return Ok(());
+        }
+    }
+    
+    writeln!(f, "{}{}{}", indent, src, end)
+}
+
+/// Split line by first parenthesized list with several items
+fn split_params(src: &str) -> Option<(&str, Vec<&str>, &str)> {
+    let mut depth = 0;
+    let mut quote = None;
+    let mut open = None;
+    let mut items = Vec::new();
+    let mut start = 0;
+
+    for (index, chr) in src.char_indices() {
+        if let Some(quote_chr) = quote {
+            if chr == quote_chr {
+                quote = None;
+            }
+            continue;
+        }
+        match chr {
+            '\'' | '"' => quote = Some(chr),
+            '(' | '[' | '{' => {
+                if depth == 0 && chr == '(' {
+                    open = Some(index);
+                    start = index + 1;
+                    items.clear();
+                }
+                depth += 1;
+            }
+            ')' | ']' | '}' => {
+                depth -= 1;
+                if depth == 0 && chr == ')' {
+                    if let Some(open) = open {
+                        if !items.is_empty() {
+                            items.push(src[start..index].trim());
+                            return Some((&src[..open], items, &src[index + 1..]));
+                        }
+                    }
+                    open = None;
+                }
+            }
+            ',' if depth == 1 && open.is_some() => {
+                items.push(src[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
 type Chunks = Vec<Chunk>;
 
 impl Display for Chunk {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
-        self.format(f, 0)
+        self.format(f, &Style::default(), 0)
     }
 }
 
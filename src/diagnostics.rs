@@ -0,0 +1,184 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    str::FromStr,
+};
+use clang::Entity;
+use log::*;
+use crate::{Stats, Timings, Requirements};
+
+/// Translation report
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub stats: Stats,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Signatures of function-like macros which cannot be bound
+    pub function_macros: Vec<String>,
+    /// Names of translated declarations
+    pub symbols: Vec<String>,
+    /// Durations of translation phases
+    pub timings: Timings,
+    /// Number of errors reported by parser of headers
+    pub parse_errors: usize,
+    /// Requirements of generated code to Dart package
+    pub requirements: Requirements,
+}
+
+impl Report {
+    /// Number of warnings issued by translator
+    pub fn warnings(&self) -> usize {
+        self.diagnostics.iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Warning)
+            .count()
+    }
+}
+
+/// Diagnostic severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// Translation diagnostic with entity context
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Entity name
+    pub name: Option<String>,
+    /// Entity kind
+    pub kind: Option<String>,
+    /// Source file path
+    pub file: Option<String>,
+    /// Source line number
+    pub line: Option<u32>,
+    /// Diagnostic message
+    pub reason: String,
+}
+
+impl Diagnostic {
+    /// Make diagnostic without entity context
+    pub fn new(severity: Severity, reason: impl Into<String>) -> Self {
+        Self {
+            severity,
+            name: None,
+            kind: None,
+            file: None,
+            line: None,
+            reason: reason.into(),
+        }
+    }
+
+    /// Make diagnostic for entity
+    pub fn with_entity(severity: Severity, entity: Entity, reason: impl Into<String>) -> Self {
+        let location = entity.get_location().map(|location| location.get_file_location());
+
+        Self {
+            name: entity.get_name(),
+            kind: Some(format!("{:?}", entity.get_kind())),
+            file: location.as_ref()
+                .and_then(|location| location.file)
+                .map(|file| file.get_path().display().to_string()),
+            line: location.map(|location| location.line),
+            ..Self::new(severity, reason)
+        }
+    }
+
+    /// Format diagnostic as JSON object
+    pub fn to_json(&self) -> String {
+        let mut fields = vec![
+            format!("\"severity\":{}", json_string(self.severity.name())),
+        ];
+        if let Some(name) = &self.name {
+            fields.push(format!("\"name\":{}", json_string(name)));
+        }
+        if let Some(kind) = &self.kind {
+            fields.push(format!("\"kind\":{}", json_string(kind)));
+        }
+        if let Some(file) = &self.file {
+            fields.push(format!("\"file\":{}", json_string(file)));
+        }
+        if let Some(line) = self.line {
+            fields.push(format!("\"line\":{}", line));
+        }
+        fields.push(format!("\"reason\":{}", json_string(&self.reason)));
+
+        format!("{{{}}}", fields.join(","))
+    }
+
+    /// Log diagnostic
+    pub fn log(&self) {
+        match self.severity {
+            Severity::Warning => warn!("{}", self),
+            Severity::Error => error!("{}", self),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file)?;
+            if let Some(line) = self.line {
+                write!(f, "{}:", line)?;
+            }
+            write!(f, " ")?;
+        }
+        if let Some(name) = &self.name {
+            write!(f, "`{}`: ", name)?;
+        }
+        write!(f, "{}", self.reason)
+    }
+}
+
+/// Diagnostics output format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticsFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for DiagnosticsFormat {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(match src {
+            "text" => DiagnosticsFormat::Text,
+            "json" => DiagnosticsFormat::Json,
+            _ => return Err(format!("Unknown diagnostics format: `{}` (expected text or json)", src)),
+        })
+    }
+}
+
+/// Format log record as JSON object
+pub fn json_log_record(level: &str, target: &str, message: &str) -> String {
+    format!("{{\"level\":{},\"target\":{},\"message\":{}}}",
+            json_string(level), json_string(target), json_string(message))
+}
+
+/// Quote string as JSON string literal
+pub(crate) fn json_string(src: &str) -> String {
+    let mut out = String::with_capacity(src.len() + 2);
+    out.push('"');
+    for chr in src.chars() {
+        match chr {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            chr if (chr as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", chr as u32)),
+            chr => out.push(chr),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,78 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use crate::Model;
+
+/// Kind of API change
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// API change between two models
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// Kind of declaration (function, struct, enum or typedef)
+    pub item: &'static str,
+    /// C name of declaration
+    pub name: String,
+    /// Old declaration
+    pub old: Option<String>,
+    /// New declaration
+    pub new: Option<String>,
+}
+
+/// Compare two models to find added, removed and changed declarations
+pub fn diff(old: &Model, new: &Model) -> Vec<Change> {
+    let mut changes = Vec::new();
+
+    diff_items(&mut changes, "function", &old.functions, &new.functions, |function| &function.name);
+    diff_items(&mut changes, "struct", &old.structs, &new.structs, |struct_| &struct_.name);
+    diff_items(&mut changes, "enum", &old.enums, &new.enums, |enum_| &enum_.name);
+    diff_items(&mut changes, "typedef", &old.typedefs, &new.typedefs, |typedef| &typedef.name);
+
+    changes
+}
+
+fn diff_items<T: Display>(changes: &mut Vec<Change>, item: &'static str, old: &[T], new: &[T],
+                          name: impl Fn(&T) -> &String) {
+    for old_item in old {
+        let new_item = new.iter().find(|new_item| name(new_item) == name(old_item));
+        let old_decl = old_item.to_string();
+        let new_decl = new_item.map(|new_item| new_item.to_string());
+
+        if new_decl.as_ref() != Some(&old_decl) {
+            changes.push(Change {
+                kind: if new_decl.is_some() { ChangeKind::Changed } else { ChangeKind::Removed },
+                item,
+                name: name(old_item).clone(),
+                old: Some(old_decl),
+                new: new_decl,
+            });
+        }
+    }
+
+    for new_item in new {
+        if !old.iter().any(|old_item| name(old_item) == name(new_item)) {
+            changes.push(Change {
+                kind: ChangeKind::Added,
+                item,
+                name: name(new_item).clone(),
+                old: None,
+                new: Some(new_item.to_string()),
+            });
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match (&self.old, &self.new) {
+            (Some(old), Some(new)) => write!(f, "~ {} `{}`: {} -> {}", self.item, self.name, old, new),
+            (Some(old), None) => write!(f, "- {} `{}`: {}", self.item, self.name, old),
+            (None, Some(new)) => write!(f, "+ {} `{}`: {}", self.item, self.name, new),
+            (None, None) => write!(f, "{} `{}`", self.item, self.name),
+        }
+    }
+}
@@ -0,0 +1,734 @@
+use crate::{Coder, BindingShape, BEGIN_MANUAL, END_MANUAL, Decl, ImportsDef, FuncDef, StructDef, FieldDef, EnumDef, ConstDef, VersionDef, LibraryDef, deprecated_annotation, quote_string, nullable_type};
+
+impl Decl {
+    /// Emit Dart code of declaration
+    pub fn emit(&self, coder: &mut Coder) {
+        use Decl::*;
+
+        match self {
+            Imports(imports) => imports.emit(coder),
+            Struct(struct_) => struct_.emit(coder),
+            Enum(enum_) => enum_.emit(coder),
+            Const(const_) => const_.emit(coder),
+            Version(version) => version.emit(coder),
+            FunctionMacros(list) => if !list.is_empty() {
+                coder.comment(format!("Function-like macros (cannot be bound directly):\n{}", list.join("\n")));
+            },
+            Opaques(names) => coder.section("Opaque types", |coder| {
+                for name in names {
+                    coder.line(format!("class {name} extends Opaque {{}}",
+                                       name = name));
+                }
+            }),
+            FuncTypes(functypes) => coder.section("Native function types", |coder| {
+                for (name, func) in functypes {
+                    if let Some(cmt) = &func.cmt {
+                        coder.comment(cmt);
+                    }
+                    if !func.notes.is_empty() {
+                        coder.comment(func.notes.join("\n"));
+                    }
+                    coder.line(format!("typedef {name} = {type};",
+                                       name = name,
+                                       type = func.cffi));
+                }
+            }),
+            TypeAliases(aliases) => coder.section("Type aliases", |coder| {
+                for (name, type_) in aliases {
+                    coder.line(format!("typedef {name} = {type};",
+                                       name = name,
+                                       type = type_));
+                }
+            }),
+            Library(library) => library.emit(coder),
+            StringArrayHelpers => emit_string_array_helpers(coder),
+            LayoutHelpers => emit_layout_helpers(coder),
+            ComplexHelpers(names) => for name in names {
+                emit_complex_helpers(coder, name);
+            },
+        }
+    }
+}
+
+impl ImportsDef {
+    fn emit(&self, coder: &mut Coder) {
+        if let Some(version) = &self.language_version {
+            coder.line(format!("// @dart={}", version));
+        }
+        if !self.ignore_lints.is_empty() {
+            coder.line(format!("// ignore_for_file: {}", self.ignore_lints.join(", ")));
+        }
+        if let Some(library) = &self.part_of {
+            coder.line(format!("part of {};", quote_string(library)));
+        } else {
+            for library in self.libraries.iter() {
+                coder.line(format!("import {};", quote_string(library)));
+            }
+        }
+        coder.line("");
+    }
+}
+
+fn emit_deprecation(coder: &mut Coder, deprecated: &Option<String>) {
+    if let Some(msg) = deprecated {
+        coder.line(deprecated_annotation(msg));
+    }
+}
+
+impl StructDef {
+    fn emit(&self, coder: &mut Coder) {
+        if let Some(cmt) = &self.cmt {
+            coder.comment(cmt);
+        }
+        emit_deprecation(coder, &self.deprecated);
+
+        coder.block(format!("class {name} extends Struct",
+                            name = self.name), |coder| {
+            for field in &self.fields {
+                field.emit(coder, self.external);
+            }
+        });
+
+        if self.pointer_ext {
+            self.emit_pointer_ext(coder);
+        }
+        if self.debug_string {
+            self.emit_debug_string(coder);
+        }
+        if let Some((size, offsets)) = &self.layout {
+            self.emit_layout_check(coder, *size, offsets);
+        }
+    }
+
+    /// Emit extension with debug function which asserts native layout
+    fn emit_layout_check(&self, coder: &mut Coder, size: usize, offsets: &[(String, usize)]) {
+        let checks = offsets.iter()
+            .filter_map(|(name, offset)| {
+                let field = self.fields.iter().find(|field| &field.name == name)?;
+                Some((name, offset, field.layout_sentinel()?))
+            })
+            .collect::<Vec<_>>();
+
+        coder.block(format!("extension {name}Layout on {name}",
+                            name = self.name), |coder| {
+            coder.comment("Check that layout of struct matches native one (asserted in debug mode only)");
+            coder.block("static void validateLayout(Allocator allocator)", |coder| {
+                coder.line(format!("assert(sizeOf<{name}>() == {size}, 'Size of `{name}` is ${{sizeOf<{name}>()}} but {size} expected');",
+                                   name = self.name,
+                                   size = size));
+                coder.when(!checks.is_empty(), |coder| {
+                    coder.line(format!("final bytes = allocator<Uint8>(sizeOf<{name}>());",
+                                       name = self.name));
+                    coder.line(format!("final value = bytes.cast<{name}>().ref;",
+                                       name = self.name));
+                    for (field, offset, sentinel) in &checks {
+                        coder.line(format!("assert(_fieldOffset(bytes, sizeOf<{name}>(), () => value.{field} = {sentinel}) == {offset}, 'Offset of `{name}.{field}` differs from {offset}');",
+                                           name = self.name,
+                                           field = field,
+                                           sentinel = sentinel,
+                                           offset = offset));
+                    }
+                    coder.line("allocator.free(bytes);");
+                });
+            });
+        });
+    }
+
+    /// Emit extension which lists fields with values
+    fn emit_debug_string(&self, coder: &mut Coder) {
+        let fields = self.fields.iter()
+            .filter(|field| !field.padding)
+            .map(|field| if field.is_struct() {
+                // nested structs has own debug dump
+                format!("{name}: ${{{name}.debugString()}}", name = field.name)
+            } else {
+                format!("{name}: ${name}", name = field.name)
+            })
+            .collect::<Vec<_>>();
+        
+        coder.block(format!("extension {name}Debug on {name}",
+                            name = self.name), |coder| {
+            coder.comment("Dump fields for debugging");
+            coder.line(format!("String debugString() => '{name}({fields})';",
+                               name = self.name,
+                               fields = fields.join(", ")));
+        });
+    }
+
+    /// Emit extension to access fields through pointer without dereferencing
+    fn emit_pointer_ext(&self, coder: &mut Coder) {
+        // the members of pointer itself cannot be shadowed
+        const RESERVED: &[&str] = &["address", "allocate", "cast", "elementAt", "hashCode",
+                                    "noSuchMethod", "ref", "runtimeType", "toString"];
+        
+        coder.block(format!("extension {name}PointerExt on Pointer<{name}>",
+                            name = self.name), |coder| {
+            // the allocators of package:ffi ignores alignment, so over-aligned structs is allocated by user
+            coder.when(self.alignment.is_none(), |coder| {
+                coder.comment("Allocate struct using allocator");
+                coder.line(format!("static Pointer<{name}> allocate(Allocator allocator) => allocator<{name}>();",
+                                   name = self.name));
+            });
+            for field in &self.fields {
+                if field.padding || RESERVED.contains(&field.name.as_str()) {
+                    continue;
+                }
+                coder.line(format!("{type} get {name} => ref.{name};",
+                                   type = field.type_,
+                                   name = field.name));
+                // arrays and nested structs is accessed in-place
+                if field.is_assignable() {
+                    coder.line(format!("set {name}({type} value) => ref.{name} = value;",
+                                       type = field.type_,
+                                       name = field.name));
+                }
+            }
+        });
+    }
+}
+
+impl FieldDef {
+    /// Check that field value can be replaced as whole
+    fn is_assignable(&self) -> bool {
+        match &self.annotation {
+            Some(annotation) => !annotation.starts_with("@Array"),
+            None => self.type_.starts_with("Pointer<"),
+        }
+    }
+    
+    /// Check that field is nested struct
+    fn is_struct(&self) -> bool {
+        self.annotation.is_none() && !self.type_.starts_with("Pointer<")
+    }
+
+    /// Value which first byte is non-zero in any byte order (used to find offset of field)
+    fn layout_sentinel(&self) -> Option<&'static str> {
+        if !self.is_assignable() {
+            return None;
+        }
+        Some(match (self.annotation.as_deref(), self.type_.as_str()) {
+            (Some("@Float()"), _) => "-1.0000001192092896",
+            (Some("@Double()"), _) => "-1.0000000000000002",
+            (_, "int") => "-1",
+            (None, _) => "Pointer.fromAddress(-1)",
+            _ => return None,
+        })
+    }
+
+    fn emit(&self, coder: &mut Coder, external: bool) {
+        if let Some(cmt) = &self.cmt {
+            coder.comment(cmt);
+        }
+        emit_deprecation(coder, &self.deprecated);
+        let external = if external { "external " } else { "" };
+        coder.line(if let Some(annotation) = &self.annotation {
+            format!("{annotation} {external}{type} {name};",
+                    annotation = annotation,
+                    external = external,
+                    type = self.type_,
+                    name = self.name)
+        } else {
+            // pointers and nested structs has no annotations
+            format!("{external}{type} {name};",
+                    external = external,
+                    type = self.type_,
+                    name = self.name)
+        });
+    }
+}
+
+impl EnumDef {
+    fn emit(&self, coder: &mut Coder) {
+        if let Some(cmt) = &self.cmt {
+            coder.comment(cmt);
+        }
+        emit_deprecation(coder, &self.deprecated);
+
+        coder.block(format!("class {name}",
+                            name = self.name), |coder| {
+            for constant in &self.constants {
+                if let Some(cmt) = &constant.cmt {
+                    coder.comment(cmt);
+                }
+                emit_deprecation(coder, &constant.deprecated);
+                coder.line(format!("static const {name} = {value};",
+                                   name = constant.name,
+                                   value = constant.value));
+            }
+        });
+    }
+}
+
+impl ConstDef {
+    fn emit(&self, coder: &mut Coder) {
+        if let Some(cmt) = &self.cmt {
+            coder.comment(cmt);
+        }
+        emit_deprecation(coder, &self.deprecated);
+        coder.line(if let Some(type_) = &self.type_ {
+            format!("const {type} {name} = {value};",
+                    type = type_,
+                    name = self.name,
+                    value = self.value)
+        } else {
+            format!("const {name} = {value};",
+                    name = self.name,
+                    value = self.value)
+        });
+    }
+}
+
+impl VersionDef {
+    fn emit(&self, coder: &mut Coder) {
+        coder.comment("Library version");
+        coder.block(format!("class {name}", name = self.name), |coder| {
+            for (field, value) in &self.fields {
+                coder.line(format!("static const {field} = {value};",
+                                   field = field,
+                                   value = value));
+            }
+        });
+    }
+}
+
+impl LibraryDef {
+    fn emit(&self, coder: &mut Coder) {
+        match self.shape {
+            BindingShape::Class => {
+                coder.comment("Library class");
+                coder.block(format!("class {name}", name = self.name), |coder| {
+                    self.emit_members(coder);
+                });
+            },
+            BindingShape::Mixin => {
+                coder.comment("Library mixin");
+                coder.block(format!("mixin {name}", name = self.name), |coder| {
+                    coder.comment("Library which functions is looked up in");
+                    coder.line(format!("DynamicLibrary get {};", self.dylib_name()));
+                    self.emit_members(coder);
+                });
+            },
+            _ => {
+                coder.comment("Library bindings");
+                self.emit_members(coder);
+            },
+        }
+    }
+
+    /// Name of library variable to look up functions
+    ///
+    /// The mixins is composed into single class, so it should have unique names of library getters.
+    fn dylib_name(&self) -> String {
+        if self.shape == BindingShape::Mixin {
+            format!("{}_dylib", self.name)
+        } else {
+            "dylib".into()
+        }
+    }
+
+    /// Name of function which checks ABI
+    fn check_abi_name(&self) -> String {
+        if self.shape == BindingShape::Mixin {
+            // it should be called by class which uses mixin
+            format!("{}_checkAbi", self.name)
+        } else {
+            "_checkAbi".into()
+        }
+    }
+
+    /// Expression to call function field
+    ///
+    /// The functions which is missing on some platforms is nullable (legacy code has no null assertions).
+    fn call_expr(&self, name: &str) -> String {
+        match self.calls.iter().find(|(call_name, _)| call_name == name) {
+            Some((_, func)) if !func.platforms.is_empty() && !self.legacy &&
+                self.shape != BindingShape::NativeAnnotations => format!("{}!", name),
+            _ => name.into(),
+        }
+    }
+
+    /// Section of function field
+    fn call_group(&self, name: &str) -> Option<&str> {
+        self.calls.iter()
+            .find(|(call_name, _)| call_name == name)
+            .and_then(|(_, func)| func.group.as_deref())
+    }
+
+    /// Emit members of library class or top-level declarations
+    fn emit_members(&self, coder: &mut Coder) {
+        let class = &self.name;
+        let (calls, callbacks, ports, asyncs) = (&self.calls, &self.callbacks, &self.ports, &self.asyncs);
+        let abi_checks = &self.abi_checks;
+        let dylib = self.dylib_name();
+        let check_abi = self.check_abi_name();
+
+        let mut inits = Vec::new();
+
+        // annotated functions is resolved by runtime
+        if self.shape != BindingShape::NativeAnnotations {
+            for (name, func) in calls {
+                let ffi_name = func.name.as_ref().unwrap();
+                let lookup = format!("{dylib}.lookup<NativeFunction<{type}>>('{ffi_name}').asFunction()",
+                                     dylib = dylib,
+                                     type = func.cffi,
+                                     ffi_name = ffi_name);
+                inits.push((name.clone(), if func.platforms.is_empty() {
+                    lookup
+                } else {
+                    // the function is missing on some platforms
+                    format!("{dylib}.providesSymbol('{ffi_name}') ? {lookup} : null",
+                            dylib = dylib,
+                            ffi_name = ffi_name,
+                            lookup = lookup)
+                }));
+            }
+        }
+
+        if let Some(init_name) = &self.init_dart_api {
+            inits.push(("init_dart_api_dl".into(), format!("{dylib}.lookup<NativeFunction<IntPtr Function(Pointer<Void>)>>('{init_name}').asFunction()",
+                                                           dylib = dylib,
+                                                           init_name = init_name)));
+        }
+
+        for (name, shim_name, type_) in ports {
+            inits.push((name.clone(), format!("{dylib}.lookup<NativeFunction<{type}>>('{shim_name}')",
+                                              dylib = dylib,
+                                              type = type_,
+                                              shim_name = shim_name)));
+            inits.push((format!("{}_set_port", name), format!("{dylib}.lookup<NativeFunction<Void Function(Int64)>>('{shim_name}_set_port').asFunction()",
+                                                              dylib = dylib,
+                                                              shim_name = shim_name)));
+        }
+
+        for (name, class, symbols) in &self.features {
+            let provided = symbols.iter()
+                .map(|symbol| format!("{}.providesSymbol('{}')", dylib, symbol))
+                .collect::<Vec<_>>()
+                .join(" && ");
+            inits.push((name.clone(), if self.shape == BindingShape::Class {
+                format!("{provided} ? {class}({dylib}) : null",
+                        provided = provided,
+                        class = class,
+                        dylib = dylib)
+            } else {
+                provided
+            }));
+        }
+
+        for (_name, ffi_name, async_) in asyncs {
+            inits.push((async_.ptr_name.clone(), format!("{dylib}.lookup<NativeFunction<{type}>>('{ffi_name}')",
+                                                         dylib = dylib,
+                                                         type = async_.cffi,
+                                                         ffi_name = ffi_name)));
+        }
+
+        // the fields of mixin is initialized lazily
+        let field = |type_: &str, name: &str| match self.shape {
+            BindingShape::Class => format!("final {type} {name};",
+                                           type = type_,
+                                           name = name),
+            BindingShape::Mixin => match inits.iter().find(|(field, _)| field == name) {
+                Some((_, init)) => format!("late final {type} {name} = {init};",
+                                           type = type_,
+                                           name = name,
+                                           init = init),
+                None => format!("late final {type} {name};",
+                                type = type_,
+                                name = name),
+            },
+            // the legacy top-level variables is null until initialization
+            _ if self.legacy => format!("{type} {name};",
+                                        type = type_,
+                                        name = name),
+            _ => format!("late final {type} {name};",
+                         type = type_,
+                         name = name),
+        };
+
+        coder.section("Callbacks", |coder| {
+            for (name, type_) in callbacks {
+                coder.line(field(&format!("Pointer<NativeFunction<{}>>", type_), name));
+            }
+        });
+
+        coder.section("Functions", |coder| {
+            if self.shape == BindingShape::NativeAnnotations {
+                emit_native_functions(coder, calls, &self.skipped);
+            } else {
+                emit_functions(coder, &field, calls, &self.skipped, self.legacy);
+            }
+        });
+
+        coder.section("Native ports", |coder| coder.when(!ports.is_empty(), |coder| {
+            coder.line(field("int Function(Pointer<Void>)", "init_dart_api_dl"));
+
+            for (name, _shim_name, type_) in ports {
+                coder.line(field(&format!("Pointer<NativeFunction<{}>>", type_), name));
+                coder.line(field("void Function(int)", &format!("{}_set_port", name)));
+            }
+        }));
+
+        coder.section("Optional features", |coder| {
+            for (name, class, _) in &self.features {
+                // the functions of feature is bound by top-level init function when it is available
+                coder.line(if self.shape == BindingShape::Class {
+                    field(&nullable_type(class, !self.legacy), name)
+                } else {
+                    field("bool", name)
+                });
+            }
+        });
+
+        coder.section("Asynchronous functions", |coder| {
+            for (_name, _ffi_name, async_) in asyncs {
+                coder.line(field(&format!("Pointer<NativeFunction<{}>>", async_.cffi), &async_.ptr_name));
+            }
+        });
+
+        let inits = inits.into_iter()
+            .map(|(name, init)| format!("{} = {}", name, init))
+            .collect::<Vec<_>>();
+
+        match self.shape {
+            BindingShape::Class => {
+                coder.comment("Constructor");
+                coder.constructor(class,
+                                  std::iter::once("DynamicLibrary dylib".to_string())
+                                  .chain(callbacks.iter().map(|(name, _)| format!("this.{name}", name = name))),
+                                  inits, |coder| coder.when(!abi_checks.is_empty(), |coder| {
+                                      coder.line("_checkAbi();");
+                                  }));
+            },
+            // the fields is initialized on first access
+            BindingShape::Mixin => {},
+            _ => if !inits.is_empty() || !callbacks.is_empty() || !abi_checks.is_empty() {
+                // the params can't be named as variables which it initializes
+                let params = std::iter::once("DynamicLibrary dylib".to_string())
+                    .chain(callbacks.iter().map(|(name, type_)| format!("Pointer<NativeFunction<{type}>> {name}_",
+                                                                        type = type_,
+                                                                        name = name)))
+                    .collect::<Vec<_>>();
+
+                coder.comment("Initialize bindings");
+                coder.block(format!("void {name}({params})",
+                                    name = self.init_name,
+                                    params = params.join(", ")), |coder| {
+                    for (name, _) in callbacks {
+                        coder.line(format!("{name} = {name}_;", name = name));
+                    }
+                    for init in inits {
+                        coder.line(format!("{};", init));
+                    }
+                    coder.when(!abi_checks.is_empty(), |coder| {
+                        coder.line("_checkAbi();");
+                    });
+                });
+            },
+        }
+
+        coder.when(!abi_checks.is_empty(), |coder| {
+            coder.comment("Check that loaded library is compatible");
+            coder.block(format!("void {}()", check_abi), |coder| {
+                for (actual, expected) in abi_checks {
+                    coder.block(format!("if ({actual} != {expected})",
+                                        actual = actual,
+                                        expected = expected), |coder| {
+                        coder.line(format!("throw StateError('ABI mismatch: {actual} is ${{{actual}}} but ${{{expected}}} expected');",
+                                           actual = actual,
+                                           expected = expected));
+                    });
+                }
+            });
+        });
+
+        coder.section("Wrappers", |coder| {
+            emit_grouped(coder, &self.wrappers, |(_, raw_name, _)| self.call_group(raw_name), |coder, (name, raw_name, wrap)| {
+                wrap.translate(coder, name, &self.call_expr(raw_name));
+            });
+        });
+
+        coder.section("Forwarding functions", |coder| {
+            emit_grouped(coder, &self.forwarders, |(_, field_name)| self.call_group(field_name), |coder, (name, field_name)| {
+                if let Some((_, func)) = calls.iter().find(|(call_name, _)| call_name == field_name) {
+                    emit_function_doc(coder, func);
+                    coder.line(format!("{res} {name}({params}) => {field_name}({args});",
+                                       res = func.res,
+                                       name = name,
+                                       params = func.params,
+                                       field_name = self.call_expr(field_name),
+                                       args = func.args.join(", ")));
+                }
+            });
+        });
+
+        coder.when(!ports.is_empty(), |coder| {
+            coder.comment("Initialize Dart API for native ports\n\n\
+                           It should be called once before receiving callbacks.");
+            coder.block("void initDartApi()", |coder| {
+                coder.block("if (init_dart_api_dl(NativeApi.initializeApiDLData) != 0)", |coder| {
+                    coder.line("throw StateError('Unable to initialize Dart API');");
+                });
+            });
+
+            for (name, _shim_name, _type) in ports {
+                coder.comment(format!("Receive `{name}` callbacks from any thread\n\n\
+                                       Each message is a list of callback arguments where pointers passed as addresses.\n\
+                                       The `{name}` should be passed to native code as callback.",
+                                      name = name));
+                coder.block(format!("ReceivePort {name}_port()", name = name), |coder| {
+                    coder.line("final port = ReceivePort();");
+                    coder.line(format!("{name}_set_port(port.sendPort.nativePort);", name = name));
+                    coder.line("return port;");
+                });
+            }
+        });
+
+        coder.section("Asynchronous wrappers", |coder| {
+            for (name, _ffi_name, async_) in asyncs {
+                async_.translate(coder, name);
+            }
+        });
+
+        coder.when(self.manual_region, |coder| {
+            coder.line(format!("{} {}", BEGIN_MANUAL, class));
+            coder.line(END_MANUAL);
+        });
+    }
+}
+
+/// Emit documentation and annotations of function
+fn emit_function_doc(coder: &mut Coder, func: &FuncDef) {
+    if let Some(cmt) = &func.cmt {
+        coder.comment(cmt);
+    }
+    if !func.notes.is_empty() {
+        coder.comment(func.notes.join("\n"));
+    }
+    emit_deprecation(coder, &func.deprecated);
+    if func.use_result {
+        coder.line("@useResult");
+    }
+}
+
+fn emit_functions(coder: &mut Coder, field: &dyn Fn(&str, &str) -> String,
+                  calls: &[(String, FuncDef)], skipped: &[(String, String)], legacy: bool) {
+    emit_grouped(coder, calls, |(_, func)| func.group.as_deref(), |coder, (name, func)| {
+        emit_function_doc(coder, func);
+        coder.line(if func.platforms.is_empty() {
+            field(&func.dart, name)
+        } else {
+            field(&nullable_type(&func.dart, !legacy), name)
+        });
+    });
+
+    emit_skipped(coder, skipped);
+}
+
+fn emit_native_functions(coder: &mut Coder, calls: &[(String, FuncDef)], skipped: &[(String, String)]) {
+    emit_grouped(coder, calls, |(_, func)| func.group.as_deref(), |coder, (name, func)| {
+        emit_function_doc(coder, func);
+        coder.line(format!("@Native<{type}>(symbol: '{ffi_name}')",
+                           type = func.cffi,
+                           ffi_name = func.name.as_ref().unwrap()));
+        coder.line(format!("external {res} {name}({params});",
+                           res = func.res,
+                           name = name,
+                           params = func.params));
+    });
+
+    emit_skipped(coder, skipped);
+}
+
+/// Emit items grouped by sections
+///
+/// The ungrouped items goes first and the sections is ordered by first appearance.
+fn emit_grouped<'a, T>(coder: &mut Coder, items: &'a [T], group: impl Fn(&'a T) -> Option<&'a str>,
+                       mut emit: impl FnMut(&mut Coder, &'a T)) {
+    let mut groups = vec![None];
+    for item in items {
+        if !groups.contains(&group(item)) {
+            groups.push(group(item));
+        }
+    }
+
+    for title in groups {
+        let mut items = items.iter().filter(|item| group(item) == title).peekable();
+        if items.peek().is_none() {
+            continue;
+        }
+        if let Some(title) = title {
+            coder.line(format!("// ── {} ──", title));
+        }
+        for item in items {
+            emit(coder, item);
+        }
+    }
+}
+
+fn emit_skipped(coder: &mut Coder, skipped: &[(String, String)]) {
+    for (name, reason) in skipped {
+        // the line comment is recognized as TODO by Dart analyzer
+        coder.line(format!("// TODO(c4dart): could not bind `{name}`: {reason}",
+                           name = name,
+                           reason = reason));
+    }
+}
+
+fn emit_complex_helpers(coder: &mut Coder, name: &str) {
+    coder.comment("Conversion helpers of complex number");
+    coder.block(format!("extension {name}Helpers on {name}", name = name), |coder| {
+        coder.comment("Set real and imaginary parts");
+        coder.block("void assign(double re, double im)", |coder| {
+            coder.line("this.re = re;");
+            coder.line("this.im = im;");
+        });
+        coder.comment("Get real and imaginary parts as list");
+        coder.line("List<double> toList() => [re, im];");
+    });
+}
+
+fn emit_layout_helpers(coder: &mut Coder) {
+    coder.comment("Find offset of struct field by writing it into zeroed bytes");
+    coder.block("int _fieldOffset(Pointer<Uint8> bytes, int size, void Function() write)", |coder| {
+        coder.block("for (var i = 0; i < size; i++)", |coder| {
+            coder.line("bytes[i] = 0;");
+        });
+        coder.line("write();");
+        coder.block("for (var i = 0; i < size; i++)", |coder| {
+            coder.block("if (bytes[i] != 0)", |coder| {
+                coder.line("return i;");
+            });
+        });
+        coder.line("return -1;");
+    });
+}
+
+fn emit_string_array_helpers(coder: &mut Coder) {
+    coder.comment("Convert list of strings to native null-terminated array of C strings");
+    coder.block("Pointer<Pointer<Int8>> toNativeStringArray(List<String> list, {Allocator allocator = malloc})", |coder| {
+        coder.line("final array = allocator<Pointer<Int8>>(list.length + 1);");
+        coder.block("for (var i = 0; i < list.length; i++)", |coder| {
+            coder.line("array[i] = list[i].toNativeUtf8(allocator: allocator).cast<Int8>();");
+        });
+        coder.line("array[list.length] = nullptr;");
+        coder.line("return array;");
+    });
+
+    coder.comment("Free native null-terminated array of C strings");
+    coder.block("void freeNativeStringArray(Pointer<Pointer<Int8>> array, {Allocator allocator = malloc})", |coder| {
+        coder.block("for (var i = 0; array[i] != nullptr; i++)", |coder| {
+            coder.line("allocator.free(array[i]);");
+        });
+        coder.line("allocator.free(array);");
+    });
+
+    coder.comment("Convert native array of C strings to list of strings\n\n\
+                   The array should be null-terminated when length is negative.");
+    coder.block("List<String> fromNativeStringArray(Pointer<Pointer<Int8>> array, [int length = -1])", |coder| {
+        coder.line("final list = <String>[];");
+        coder.block("for (var i = 0; length < 0 ? array[i] != nullptr : i < length; i++)", |coder| {
+            coder.line("list.add(array[i].cast<Utf8>().toDartString());");
+        });
+        coder.line("return list;");
+    });
+}
@@ -0,0 +1,95 @@
+use std::{collections::HashMap, sync::OnceLock};
+use regex::{Regex, Captures};
+
+/// Extract code examples from documentation comment
+///
+/// The examples is taken from `@code`/`\code` blocks and fenced (```) blocks.
+pub fn comment_examples(cmt: &str) -> Vec<String> {
+    let mut examples = Vec::new();
+    // the code lines with flag of fenced block
+    let mut example: Option<(bool, Vec<&str>)> = None;
+
+    for line in cmt.lines().map(strip_comment_marker) {
+        let text = line.trim();
+
+        match &mut example {
+            None => if text.starts_with("```") {
+                example = Some((true, Vec::new()));
+            } else if is_command(text, "code") {
+                example = Some((false, Vec::new()));
+            },
+            Some((fenced, lines)) => if (*fenced && text.starts_with("```")) ||
+                (!*fenced && is_command(text, "endcode")) {
+                    examples.push(dedent(lines));
+                    example = None;
+                } else {
+                    lines.push(line);
+                },
+        }
+    }
+
+    examples.retain(|code| !code.is_empty());
+    examples
+}
+
+fn is_command(text: &str, name: &str) -> bool {
+    (text.starts_with('@') || text.starts_with('\\')) && text[1..].starts_with(name) &&
+        !text[1 + name.len()..].starts_with(|chr: char| chr.is_alphanumeric())
+}
+
+fn strip_comment_marker(line: &str) -> &str {
+    let line = line.trim_start();
+    let line = ["/**", "/*!", "/*", "///", "//!", "//"].iter()
+        .find_map(|marker| line.strip_prefix(marker))
+        .unwrap_or(line);
+    let line = line.trim_end().strip_suffix("*/").unwrap_or(line);
+    let line = line.strip_prefix('*').unwrap_or(line);
+    line.strip_prefix(' ').unwrap_or(line)
+}
+
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines.iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min().unwrap_or(0);
+
+    lines.iter()
+        .map(|line| line.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>().join("\n")
+        .trim_matches('\n').into()
+}
+
+/// Translate C code example to Dart usage snippet
+///
+/// The statements which calls bound functions only is translated using callees (Dart expressions by C names),
+/// another lines is kept commented.
+pub fn translate_example(code: &str, callees: &HashMap<String, String>) -> String {
+    // the patterns is compiled once for all examples
+    static PATTERNS: OnceLock<(Regex, Regex, Regex)> = OnceLock::new();
+    let (call, null, declaration) = PATTERNS.get_or_init(|| (
+        Regex::new(r"\b([A-Za-z_]\w*)\s*\(").unwrap(),
+        Regex::new(r"\bNULL\b").unwrap(),
+        Regex::new(r"^(\s*)[\w\s*]*?\w[\s*]+(\w+)\s*=([^=].*)$").unwrap(),
+    ));
+
+    code.lines().map(|line| {
+        let text = line.trim();
+
+        if text.is_empty() || text.starts_with("//") {
+            return line.into();
+        }
+
+        let mut calls = call.captures_iter(text).map(|captures| captures[1].to_string()).peekable();
+
+        // the address and member operators has no direct equivalents
+        if !text.ends_with(';') || text.contains('&') || text.contains("->") || calls.peek().is_none() ||
+            !calls.all(|name| callees.contains_key(&name)) {
+                return format!("// {}", line);
+            }
+
+        let line = call.replace_all(line, |captures: &Captures| format!("{}(", callees[&captures[1]]));
+        let line = null.replace_all(&line, "nullptr");
+
+        declaration.replace(&line, "${1}final ${2} =${3}").into()
+    }).collect::<Vec<_>>().join("\n")
+}
@@ -0,0 +1,248 @@
+use crate::{WrapDef, AsyncDef, BindingShape, ImportSet, Json, ToJson, FromJson};
+
+/// Top-level declaration of generated bindings
+///
+/// The translator builds declarations from clang entities with already resolved Dart names and types,
+/// so the code can be emitted without access to translation unit.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Decl {
+    /// Imports (or `part of` directive)
+    Imports(ImportsDef),
+    Struct(StructDef),
+    Enum(EnumDef),
+    Const(ConstDef),
+    /// Library version class
+    Version(VersionDef),
+    /// Signatures of function-like macros which cannot be bound directly
+    FunctionMacros(Vec<String>),
+    /// Names of opaque types
+    Opaques(Vec<String>),
+    /// Native function typedefs
+    FuncTypes(Vec<(String, FuncDef)>),
+    /// Typedef aliases of frequently used types (names with types)
+    TypeAliases(Vec<(String, String)>),
+    /// Library class
+    Library(LibraryDef),
+    /// Helpers to convert arrays of strings
+    StringArrayHelpers,
+    /// Function to find offsets of struct fields at runtime
+    LayoutHelpers,
+    /// Helpers of complex number structs (names of structs)
+    ComplexHelpers(Vec<String>),
+}
+
+/// Imports of generated file
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImportsDef {
+    /// Imported libraries URIs
+    pub libraries: ImportSet,
+    /// Library which the file is part of
+    pub part_of: Option<String>,
+    /// Language version of legacy (not null-safe) code
+    pub language_version: Option<String>,
+    /// Analyzer lints which is ignored for file
+    pub ignore_lints: Vec<String>,
+}
+
+/// Native function definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuncDef {
+    /// Native symbol name
+    pub name: Option<String>,
+    /// Documentation comment
+    pub cmt: Option<String>,
+    /// Deprecation message
+    pub deprecated: Option<String>,
+    /// Extra documentation notes
+    pub notes: Vec<String>,
+    /// Result should be used
+    pub use_result: bool,
+    /// Native function type
+    pub cffi: String,
+    /// Dart function type
+    pub dart: String,
+    /// Dart result type
+    pub res: String,
+    /// Dart params with types
+    pub params: String,
+    /// Dart argument names
+    pub args: Vec<String>,
+    /// Platforms which provides function (all when empty)
+    ///
+    /// The function which is not provided by all platforms is looked up only when library has it.
+    pub platforms: Vec<String>,
+    /// Section title of library members
+    pub group: Option<String>,
+}
+
+/// Struct or union definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructDef {
+    pub name: String,
+    pub cmt: Option<String>,
+    pub deprecated: Option<String>,
+    pub fields: Vec<FieldDef>,
+    /// Fields is declared as `external` (required by null-safe code)
+    pub external: bool,
+    /// Emit extension on pointer with field accessors
+    pub pointer_ext: bool,
+    /// Emit extension with debug dump of fields
+    pub debug_string: bool,
+    /// Native alignment when it is stricter than Dart derives from fields
+    pub alignment: Option<usize>,
+    /// Native size with offsets of fields to check at runtime
+    pub layout: Option<(usize, Vec<(String, usize)>)>,
+}
+
+/// Struct field definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDef {
+    pub name: String,
+    pub cmt: Option<String>,
+    pub deprecated: Option<String>,
+    /// Native type annotation (like `@Int32()` or `@Array(4)`)
+    pub annotation: Option<String>,
+    /// Dart type
+    pub type_: String,
+    /// Unnamed bytes which keeps native offsets of next fields
+    pub padding: bool,
+}
+
+/// Enum definition
+///
+/// The enums is emitted as classes with integer constants.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumDef {
+    pub name: String,
+    pub cmt: Option<String>,
+    pub deprecated: Option<String>,
+    pub constants: Vec<ConstDef>,
+}
+
+/// Constant definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstDef {
+    pub name: String,
+    pub cmt: Option<String>,
+    pub deprecated: Option<String>,
+    /// Explicit Dart type (inferred from value when not set)
+    pub type_: Option<String>,
+    /// Dart expression
+    pub value: String,
+}
+
+/// Library version class definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionDef {
+    pub name: String,
+    /// Fields with values
+    pub fields: Vec<(String, String)>,
+}
+
+/// Library class definition
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LibraryDef {
+    pub name: String,
+    /// Shape of bindings
+    pub shape: BindingShape,
+    /// Name of initialization function (when bindings is not shaped as class)
+    pub init_name: String,
+    /// Functions with names of fields
+    pub calls: Vec<(String, FuncDef)>,
+    /// Callbacks with names of native function types
+    pub callbacks: Vec<(String, String)>,
+    /// Wrapper methods with names of raw functions
+    pub wrappers: Vec<(String, String, WrapDef)>,
+    /// Public forwarding functions with names of private functions
+    pub forwarders: Vec<(String, String)>,
+    /// Asynchronous wrapper methods with native function names
+    pub asyncs: Vec<(String, String, AsyncDef)>,
+    /// Callbacks delivered via native ports with shim function names and types
+    pub ports: Vec<(String, String, String)>,
+    /// Native function which initializes Dart API for ports
+    pub init_dart_api: Option<String>,
+    /// Skipped functions with reasons
+    pub skipped: Vec<(String, String)>,
+    /// Runtime ABI checks as pairs of actual and expected values
+    pub abi_checks: Vec<(String, String)>,
+    /// Optional features with names of classes and functions which is required by it
+    pub features: Vec<(String, String, Vec<String>)>,
+    /// Emit markers of hand-written region
+    pub manual_region: bool,
+    /// Emit code opted out of null safety
+    pub legacy: bool,
+}
+
+impl ToJson for Decl {
+    fn to_json(&self) -> Json {
+        use Decl::*;
+
+        let (kind, value) = match self {
+            Imports(imports) => ("imports", imports.to_json()),
+            Struct(struct_) => ("struct", struct_.to_json()),
+            Enum(enum_) => ("enum", enum_.to_json()),
+            Const(const_) => ("const", const_.to_json()),
+            Version(version) => ("version", version.to_json()),
+            FunctionMacros(list) => ("function_macros", list.to_json()),
+            Opaques(names) => ("opaques", names.to_json()),
+            FuncTypes(functypes) => ("functypes", functypes.to_json()),
+            TypeAliases(aliases) => ("type_aliases", aliases.to_json()),
+            Library(library) => ("library", library.to_json()),
+            StringArrayHelpers => ("string_array_helpers", Json::Null),
+            LayoutHelpers => ("layout_helpers", Json::Null),
+            ComplexHelpers(names) => ("complex_helpers", names.to_json()),
+        };
+
+        Json::Object(vec![(kind.into(), value)])
+    }
+}
+
+impl FromJson for Decl {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        use Decl::*;
+
+        let (kind, value) = match json {
+            Json::Object(fields) if fields.len() == 1 => (fields[0].0.as_str(), &fields[0].1),
+            _ => return Err("Expected declaration object with single field".into()),
+        };
+
+        Ok(match kind {
+            "imports" => Imports(FromJson::from_json(value)?),
+            "struct" => Struct(FromJson::from_json(value)?),
+            "enum" => Enum(FromJson::from_json(value)?),
+            "const" => Const(FromJson::from_json(value)?),
+            "version" => Version(FromJson::from_json(value)?),
+            "function_macros" => FunctionMacros(FromJson::from_json(value)?),
+            "opaques" => Opaques(FromJson::from_json(value)?),
+            "functypes" => FuncTypes(FromJson::from_json(value)?),
+            "type_aliases" => TypeAliases(FromJson::from_json(value)?),
+            "library" => Library(FromJson::from_json(value)?),
+            "string_array_helpers" => StringArrayHelpers,
+            "layout_helpers" => LayoutHelpers,
+            "complex_helpers" => ComplexHelpers(FromJson::from_json(value)?),
+            _ => return Err(format!("Unknown declaration kind: `{}`", kind)),
+        })
+    }
+}
+
+impl ToJson for BindingShape {
+    fn to_json(&self) -> Json {
+        Json::String(self.name().into())
+    }
+}
+
+impl FromJson for BindingShape {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        String::from_json(json)?.parse()
+    }
+}
+
+json_struct!(ImportsDef { libraries, part_of, language_version, ignore_lints });
+json_struct!(FuncDef { name, cmt, deprecated, notes, use_result, cffi, dart, res, params, args, platforms, group });
+json_struct!(StructDef { name, cmt, deprecated, fields, external, pointer_ext, debug_string, alignment, layout });
+json_struct!(FieldDef { name, cmt, deprecated, annotation, type_, padding });
+json_struct!(EnumDef { name, cmt, deprecated, constants });
+json_struct!(ConstDef { name, cmt, deprecated, type_, value });
+json_struct!(VersionDef { name, fields });
+json_struct!(LibraryDef { name, shape, init_name, calls, callbacks, wrappers, forwarders, asyncs, ports,
+                          init_dart_api, skipped, abi_checks, features, manual_region, legacy });
@@ -0,0 +1,354 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use crate::json_string;
+
+/// JSON value
+///
+/// The objects keeps fields order to make output reproducible.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parse JSON text
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let mut parser = Parser { src, pos: 0 };
+        let value = parser.value()?;
+        parser.skip_spaces();
+        if parser.pos < src.len() {
+            return Err(parser.error("Unexpected trailing characters"));
+        }
+        Ok(value)
+    }
+
+    /// Get field of object (null when it is missing)
+    pub fn field(&self, key: &str) -> &Json {
+        match self {
+            Json::Object(fields) => fields.iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value)
+                .unwrap_or(&Json::Null),
+            _ => &Json::Null,
+        }
+    }
+
+    fn format(&self, f: &mut Formatter, level: usize) -> FmtResult {
+        let indent = "  ".repeat(level + 1);
+
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write!(f, "{}", json_string(value)),
+            Json::Array(items) if items.is_empty() => write!(f, "[]"),
+            Json::Array(items) => {
+                writeln!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    write!(f, "{}", indent)?;
+                    item.format(f, level + 1)?;
+                    writeln!(f, "{}", if index + 1 < items.len() { "," } else { "" })?;
+                }
+                write!(f, "{}]", "  ".repeat(level))
+            },
+            Json::Object(fields) if fields.is_empty() => write!(f, "{{}}"),
+            Json::Object(fields) => {
+                writeln!(f, "{{")?;
+                for (index, (name, value)) in fields.iter().enumerate() {
+                    write!(f, "{}{}: ", indent, json_string(name))?;
+                    value.format(f, level + 1)?;
+                    writeln!(f, "{}", if index + 1 < fields.len() { "," } else { "" })?;
+                }
+                write!(f, "{}}}", "  ".repeat(level))
+            },
+        }
+    }
+}
+
+impl Display for Json {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        self.format(f, 0)
+    }
+}
+
+/// Recursive descent JSON parser
+struct Parser<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, msg: &str) -> String {
+        let line = self.src[..self.pos].matches('\n').count() + 1;
+        format!("{} at line {}", msg, line)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn skip_spaces(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.src[self.pos..].starts_with(token) {
+            self.pos += token.len();
+            Ok(())
+        } else {
+            Err(self.error(&format!("Expected `{}`", token)))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_spaces();
+
+        Ok(match self.peek() {
+            Some('n') => { self.expect("null")?; Json::Null },
+            Some('t') => { self.expect("true")?; Json::Bool(true) },
+            Some('f') => { self.expect("false")?; Json::Bool(false) },
+            Some('"') => Json::String(self.string()?),
+            Some('[') => {
+                self.pos += 1;
+                let mut items = Vec::new();
+                self.skip_spaces();
+                if self.peek() == Some(']') {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        items.push(self.value()?);
+                        self.skip_spaces();
+                        match self.peek() {
+                            Some(',') => self.pos += 1,
+                            Some(']') => { self.pos += 1; break; },
+                            _ => return Err(self.error("Expected `,` or `]`")),
+                        }
+                    }
+                }
+                Json::Array(items)
+            },
+            Some('{') => {
+                self.pos += 1;
+                let mut fields = Vec::new();
+                self.skip_spaces();
+                if self.peek() == Some('}') {
+                    self.pos += 1;
+                } else {
+                    loop {
+                        self.skip_spaces();
+                        let name = self.string()?;
+                        self.skip_spaces();
+                        self.expect(":")?;
+                        fields.push((name, self.value()?));
+                        self.skip_spaces();
+                        match self.peek() {
+                            Some(',') => self.pos += 1,
+                            Some('}') => { self.pos += 1; break; },
+                            _ => return Err(self.error("Expected `,` or `}`")),
+                        }
+                    }
+                }
+                Json::Object(fields)
+            },
+            Some(chr) if chr == '-' || chr.is_ascii_digit() => {
+                let len = self.src[self.pos..]
+                    .find(|chr: char| !(chr.is_ascii_digit() || "+-.eE".contains(chr)))
+                    .unwrap_or(self.src.len() - self.pos);
+                let number = self.src[self.pos..self.pos + len].parse()
+                    .map_err(|_| self.error("Invalid number"))?;
+                self.pos += len;
+                Json::Number(number)
+            },
+            _ => return Err(self.error("Expected value")),
+        })
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect("\"")?;
+
+        let mut out = String::new();
+        let mut chars = self.src[self.pos..].char_indices();
+
+        while let Some((index, chr)) = chars.next() {
+            match chr {
+                '"' => {
+                    self.pos += index + 1;
+                    return Ok(out);
+                },
+                '\\' => match chars.next().map(|(_, chr)| chr) {
+                    Some('n') => out.push('\n'),
+                    Some('r') => out.push('\r'),
+                    Some('t') => out.push('\t'),
+                    Some('b') => out.push('\u{8}'),
+                    Some('f') => out.push('\u{c}'),
+                    Some('u') => {
+                        let code = (0..4).filter_map(|_| chars.next().map(|(_, chr)| chr)).collect::<String>();
+                        let code = u32::from_str_radix(&code, 16).ok()
+                            .and_then(std::char::from_u32)
+                            .ok_or_else(|| self.error("Invalid unicode escape"))?;
+                        out.push(code);
+                    },
+                    Some(chr) => out.push(chr),
+                    None => break,
+                },
+                chr => out.push(chr),
+            }
+        }
+
+        Err(self.error("Unterminated string"))
+    }
+}
+
+/// Conversion to JSON
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+/// Conversion from JSON
+pub trait FromJson: Sized {
+    fn from_json(json: &Json) -> Result<Self, String>;
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Json {
+        Json::String(self.clone())
+    }
+}
+
+impl FromJson for String {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match json {
+            Json::String(value) => Ok(value.clone()),
+            _ => Err("Expected string".into()),
+        }
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Json {
+        Json::Bool(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match json {
+            Json::Bool(value) => Ok(*value),
+            // missing flags is unset
+            Json::Null => Ok(false),
+            _ => Err("Expected boolean".into()),
+        }
+    }
+}
+
+impl ToJson for usize {
+    fn to_json(&self) -> Json {
+        Json::Number(*self as f64)
+    }
+}
+
+impl FromJson for usize {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match json {
+            Json::Number(value) if *value >= 0.0 && value.fract() == 0.0 => Ok(*value as usize),
+            _ => Err("Expected unsigned integer".into()),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Json {
+        self.as_ref().map(ToJson::to_json).unwrap_or(Json::Null)
+    }
+}
+
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match json {
+            Json::Null => Ok(None),
+            json => T::from_json(json).map(Some),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Json {
+        Json::Array(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match json {
+            Json::Array(items) => items.iter().map(T::from_json).collect(),
+            // missing lists is empty
+            Json::Null => Ok(Vec::new()),
+            _ => Err("Expected array".into()),
+        }
+    }
+}
+
+impl<A: ToJson, B: ToJson> ToJson for (A, B) {
+    fn to_json(&self) -> Json {
+        Json::Array(vec![self.0.to_json(), self.1.to_json()])
+    }
+}
+
+impl<A: FromJson, B: FromJson> FromJson for (A, B) {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match json {
+            Json::Array(items) if items.len() == 2 =>
+                Ok((A::from_json(&items[0])?, B::from_json(&items[1])?)),
+            _ => Err("Expected array of 2 items".into()),
+        }
+    }
+}
+
+impl<A: ToJson, B: ToJson, C: ToJson> ToJson for (A, B, C) {
+    fn to_json(&self) -> Json {
+        Json::Array(vec![self.0.to_json(), self.1.to_json(), self.2.to_json()])
+    }
+}
+
+impl<A: FromJson, B: FromJson, C: FromJson> FromJson for (A, B, C) {
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match json {
+            Json::Array(items) if items.len() == 3 =>
+                Ok((A::from_json(&items[0])?, B::from_json(&items[1])?, C::from_json(&items[2])?)),
+            _ => Err("Expected array of 3 items".into()),
+        }
+    }
+}
+
+/// Implement JSON conversions for struct as object with same fields
+///
+/// The trailing underscores is stripped from names of fields (`type_` becomes `type`).
+macro_rules! json_struct {
+    ($type:ident { $($field:ident),* $(,)? }) => {
+        impl $crate::ToJson for $type {
+            fn to_json(&self) -> $crate::Json {
+                $crate::Json::Object(vec![
+                    $((stringify!($field).trim_end_matches('_').into(),
+                       $crate::ToJson::to_json(&self.$field)),)*
+                ])
+            }
+        }
+
+        impl $crate::FromJson for $type {
+            fn from_json(json: &$crate::Json) -> Result<Self, String> {
+                Ok(Self {
+                    $($field: {
+                        let name = stringify!($field).trim_end_matches('_');
+                        $crate::FromJson::from_json(json.field(name))
+                            .map_err(|error| format!("{}.{}: {}", stringify!($type), name, error))?
+                    },)*
+                })
+            }
+        }
+    };
+}
@@ -1,56 +1,357 @@
+#[macro_use]
+mod json;
 mod options;
 mod result;
 mod coder;
+mod names;
 mod translator;
+mod ir;
+mod emitter;
+mod target;
 mod utils;
+mod wrapper;
+mod pragma;
+mod stats;
+mod diagnostics;
+mod model;
+mod diff;
+mod merge;
+mod testing;
+mod preset;
+mod manual;
+mod symbols;
+mod response;
+mod package;
+mod examples;
 
 use std::{
     path::Path,
     io::Write,
+    fs::File,
+    time::Instant,
 };
-use clang::{Clang, Index};
+use clang::{Clang, Entity, Index, TranslationUnit, diagnostic::Severity as ClangSeverity};
+use log::*;
 
 pub use options::*;
 pub use result::*;
+pub use target::*;
+pub use stats::*;
+pub use diagnostics::*;
+pub use model::*;
+pub use diff::*;
+pub use merge::*;
+pub use testing::*;
+pub use preset::*;
+pub use manual::*;
+pub use symbols::*;
+pub use response::*;
+pub use package::*;
+pub use coder::{Style, Braces};
 pub(crate) use coder::*;
+pub(crate) use names::*;
 pub(crate) use translator::*;
+pub(crate) use ir::*;
+pub(crate) use json::*;
 pub(crate) use utils::*;
+pub(crate) use wrapper::*;
+pub(crate) use pragma::*;
+pub(crate) use examples::*;
 
-pub fn translate(options: Options, input: &Path, output: &mut impl Write) -> Result<()> {
-    let clang = Clang::new().unwrap();
+pub fn translate(options: Options, input: &Path, output: &mut impl Write) -> Result<Report> {
+    if !options.define_sets.is_empty() {
+        return translate_define_sets(options, input, output);
+    }
+    
+    let mut start = Instant::now();
+    
+    with_unit(&options, input, |entity, parse_errors| {
+        let mut translator = Translator::new(options.clone());
+        translator.add_timing("parse headers", &mut start);
+
+        if options.streaming {
+            writeln!(output, "{}", generated_header())?;
+            translator.translate_to(entity, Some(output))?;
+        } else {
+            translator.translate(entity)?;
+            writeln!(output, "{}", generated_header())?;
+        }
+    
+        writeln!(output, "{}", translator.coder())?;
+
+        if options.manual_regions {
+            writeln!(output, "{} file", BEGIN_MANUAL)?;
+            writeln!(output, "{}", END_MANUAL)?;
+        }
+
+        if let Some(path) = &options.ir_output {
+            let mut ir = File::create(path)?;
+
+            writeln!(ir, "{}", translator.ir())?;
+        }
+
+        if let Some(path) = &options.shim_output {
+            write_shim(path, input, translator.shim())?;
+        }
+
+        Ok(with_parse_errors(translator.report(), parse_errors))
+    })
+}
+
+/// Translate headers once per define set and merge the results
+///
+/// The declarations which is missing under some define sets is documented
+/// and the functions is looked up only when library provides it.
+fn translate_define_sets(options: Options, input: &Path, output: &mut impl Write) -> Result<Report> {
+    let mut report = Report::default();
+    let mut sets = Vec::new();
+    // the shims of all sets is merged because functions may be declared under some sets only
+    let mut shim: Option<Coder> = None;
+    
+    for (index, set) in options.define_sets.iter().enumerate() {
+        let set_options = Options {
+            defines: options.defines.iter().chain(set).cloned().collect(),
+            define_sets: Vec::new(),
+            streaming: false,
+            ..options.clone()
+        };
+        
+        let (decls, set_shim, set_report) = with_unit(&set_options, input, |entity, parse_errors| {
+            let mut translator = Translator::new(set_options.clone());
+            translator.translate(entity)?;
+
+            Ok((translator.decls().to_vec(), translator.shim().clone(),
+                with_parse_errors(translator.report(), parse_errors)))
+        })?;
+
+        match &mut shim {
+            Some(shim) => shim.union(&set_shim),
+            None => shim = Some(set_shim),
+        }
+
+        if index == 0 {
+            report = set_report;
+        } else {
+            report.parse_errors += set_report.parse_errors;
+            // the declarations which is common for all sets is reported only once
+            for diagnostic in set_report.diagnostics {
+                if !report.diagnostics.contains(&diagnostic) {
+                    report.diagnostics.push(diagnostic);
+                }
+            }
+            report.timings.merge(&set_report.timings);
+            for symbol in set_report.symbols {
+                if !report.symbols.contains(&symbol) {
+                    report.symbols.push(symbol);
+                }
+            }
+        }
+        
+        sets.push((set.join(","), decls));
+    }
+
+    let sets = sets.iter()
+        .map(|(name, decls)| (name.as_str(), decls.clone()))
+        .collect::<Vec<_>>();
+    let (decls, diagnostics) = merge_decls(&sets, "with");
+    report.diagnostics.extend(diagnostics);
+    // the stats of first set is not relevant for merged declarations
+    report.stats = Stats::from_decls(&decls);
+    report.requirements = Requirements::from_decls(&decls);
+
+    if let (Some(path), Some(shim)) = (&options.shim_output, &shim) {
+        write_shim(path, input, shim)?;
+    }
+
+    write_decls(&decls, options.style, output)?;
+
+    if let Some(path) = &options.ir_output {
+        let mut ir = File::create(path)?;
+
+        writeln!(ir, "{}", decls.to_json())?;
+    }
+
+    Ok(report)
+}
+
+fn write_shim(path: &Path, input: &Path, shim: &Coder) -> Result<()> {
+    let mut file = File::create(path)?;
+
+    writeln!(file, "{}", generated_header())?;
+
+    if let Some(name) = input.file_name() {
+        writeln!(file, "#include \"{}\"", name.to_string_lossy())?;
+    }
+    
+    writeln!(file, "{}", shim)?;
+
+    Ok(())
+}
+
+/// Generate Dart bindings from saved intermediate representation
+///
+/// The IR is written by translation with `ir_output` option and doesn't requires libclang to generate code.
+/// The report has stats and requirements of declarations only.
+pub fn translate_ir(style: Style, input: &str, output: &mut impl Write) -> Result<Report> {
+    let json = Json::parse(input)
+        .map_err(|error| format!("Invalid IR: {}", error))?;
+    let decls = Vec::<Decl>::from_json(&json)
+        .map_err(|error| format!("Invalid IR: {}", error))?;
+
+    write_decls(&decls, style, output)?;
+
+    Ok(Report {
+        stats: Stats::from_decls(&decls),
+        requirements: Requirements::from_decls(&decls),
+        ..Report::default()
+    })
+}
+
+/// Write generated code of declarations
+fn write_decls(decls: &[Decl], style: Style, output: &mut impl Write) -> Result<()> {
+    let mut coder = Coder::with_style(style);
+    for decl in decls {
+        decl.emit(&mut coder);
+    }
+
+    writeln!(output, "{}", generated_header())?;
+    writeln!(output, "{}", coder)?;
+
+    if decls.iter().any(|decl| matches!(decl, Decl::Library(library) if library.manual_region)) {
+        writeln!(output, "{} file", BEGIN_MANUAL)?;
+        writeln!(output, "{}", END_MANUAL)?;
+    }
+
+    Ok(())
+}
+
+/// Check that source was generated by this tool
+pub fn is_generated(src: &str) -> bool {
+    src.lines().next().map(|line| line.starts_with(&format!("/* This file was generated using {} ", env!("CARGO_PKG_NAME"))))
+        .unwrap_or(false)
+}
+
+fn generated_header() -> String {
+    format!("/* This file was generated using {program} v{version} tool and should not be modified manually. */",
+            program = env!("CARGO_PKG_NAME"),
+            version = env!("CARGO_PKG_VERSION"))
+}
+
+/// Parse C headers to get the model of bound declarations
+pub fn parse(options: Options, input: &Path) -> Result<Model> {
+    with_unit(&options, input, |entity, _| {
+        let mut translator = Translator::new(options.clone());
+
+        translator.translate(entity)?;
+
+        Ok(translator.model().clone())
+    })
+}
+
+/// Put errors of parser before diagnostics of translation
+fn with_parse_errors(mut report: Report, parse_errors: Vec<Diagnostic>) -> Report {
+    report.parse_errors = parse_errors.len();
+    report.diagnostics.splice(0..0, parse_errors);
+    report
+}
+
+/// Collect errors which is reported by parser of headers
+fn parse_errors(tu: &TranslationUnit) -> Vec<Diagnostic> {
+    tu.get_diagnostics().into_iter()
+        .filter(|diagnostic| diagnostic.get_severity() >= ClangSeverity::Error)
+        .map(|diagnostic| {
+            let location = diagnostic.get_location().get_file_location();
+            let diagnostic = Diagnostic {
+                file: location.file.map(|file| file.get_path().display().to_string()),
+                line: Some(location.line),
+                ..Diagnostic::new(Severity::Error, diagnostic.get_text())
+            };
+            diagnostic.log();
+            diagnostic
+        })
+        .collect()
+}
+
+/// Parse translation unit and process its entity with errors of parser
+fn with_unit<R>(options: &Options, input: &Path,
+                process: impl for<'tu> FnOnce(Entity<'tu>, Vec<Diagnostic>) -> Result<R>) -> Result<R> {
+    let clang = Clang::new().map_err(|error| {
+        Error::Tool(format!("Unable to load libclang: {}. Install libclang or set LIBCLANG_PATH.", error))
+    })?;
     
     let index = Index::new(&clang, false, true);
     
     let mut args = Vec::new();
     
     args.push("-xc".into());
+    args.push(format!("-std={}", options.c_standard));
 
-    if options.detect_isystem {
-        let paths = system_includes_search_paths();
-        
-        for path in paths {
-            args.push(format!("-isystem{}", path.display()));
+    if options.msvc {
+        args.push("-fms-compatibility".into());
+        args.push("-fms-extensions".into());
+    }
+
+    let mut target_args = Vec::new();
+
+    if let Some(target) = &options.target {
+        target_args.push(format!("--target={}", target));
+    }
+
+    if let Some(path) = &options.sysroot {
+        target_args.push(format!("--sysroot={}", path.display()));
+    }
+
+    args.extend(target_args.iter().cloned());
+
+    if options.no_std_includes {
+        args.push("-nostdinc".into());
+    }
+
+    for path in &options.isystem_paths {
+        args.push(format!("-isystem{}", path.display()));
+    }
+
+    if options.detect_isystem && !options.no_std_includes {
+        match system_includes_search_paths(options.clang_path.as_deref(), options.msvc, &target_args) {
+            Ok(paths) => {
+                for path in paths.includes {
+                    args.push(format!("-isystem{}", path.display()));
+                }
+                // the system frameworks (like CoreFoundation) is searched separately
+                for path in paths.frameworks {
+                    args.push(format!("-iframework{}", path.display()));
+                }
+            },
+            Err(error) => if options.isystem_fallback {
+                warn!("{}", error);
+            } else {
+                return Err(error);
+            },
         }
     }
 
+    for define in &options.defines {
+        args.push(format!("-D{}", define));
+    }
+
     for path in &options.include_paths {
         args.push(format!("-I{}", path.display()));
     }
 
-    let tu = index.parser(&input)
-        .arguments(&args)
-        .parse().unwrap();
-
-    let mut translator = Translator::new(options);
+    for path in &options.framework_paths {
+        args.push(format!("-F{}", path.display()));
+    }
 
-    translator.translate(tu.get_entity());
+    args.extend(options.clang_args.iter().cloned());
 
-    writeln!(output,
-             "/* This file was generated using {program} v{version} tool and should not be modified manually. */",
-             program = env!("CARGO_PKG_NAME"),
-             version = env!("CARGO_PKG_VERSION"))?;
-    
-    writeln!(output, "{}", translator.coder())?;
+    let tu = index.parser(&input)
+        .arguments(&args)
+        // macro definitions is required only to translate macros
+        .detailed_preprocessing_record(options.parse_macros())
+        .skip_function_bodies(options.skip_function_bodies)
+        .keep_going(options.keep_going)
+        .parse()
+        .map_err(|error| Error::Parse(format!("Unable to parse `{}`: {}", input.display(), error)))?;
 
-    Ok(())
+    process(tu.get_entity(), parse_errors(&tu))
 }
@@ -1,15 +1,24 @@
 use std::{
-    path::PathBuf,
-    fs::File,
+    path::{Path, PathBuf},
+    fs::{File, read_to_string, create_dir_all, rename, remove_file},
+    io::{Write, IsTerminal},
+    str::FromStr,
+    fmt::Display,
+    collections::HashSet,
+    sync::{Mutex, atomic::{AtomicBool, AtomicUsize, Ordering}},
 };
 use regex::Regex;
-use log::LevelFilter;
+use log::{Log, Metadata, Record};
+use pretty_env_logger::env_logger::filter::{Builder as FilterBuilder, Filter};
 
-pub use c4dart::{Options, translate};
+pub use c4dart::{Options, Style, Braces, BindingShape, MemberVisibility, MemberOrder, AndroidArch, AppleSdk, Diagnostic, DiagnosticsFormat, Severity, Progress, Report, Error, translate, translate_ir, merge_ir, parse, diff, json_log_record, merge_manual_regions, is_generated,
+                 read_symbol_map, write_symbol_map, expand_response_files, package_name, package_bindings_path, write_package};
+use structopt::StructOpt;
 
 /// Command-line arguments
 #[derive(Debug, structopt::StructOpt)]
-#[structopt(about)]
+#[structopt(about, after_help = "EXIT CODES:\n    0    Bindings generated\n    1    Generation error\n    \
+                                 2    Errors in parsed headers\n    3    Nothing matched by patterns")]
 struct Args {
     /// Print version number
     #[structopt(short = "V", long)]
@@ -22,6 +31,14 @@ struct Args {
     /// Dart source output
     #[structopt(short, long, parse(from_os_str))]
     output: Option<PathBuf>,
+
+    /// Write bindings as Dart package to directory (pubspec.yaml, library in lib/ which exports bindings in lib/src/)
+    #[structopt(long, parse(from_os_str), conflicts_with = "output")]
+    package_out: Option<PathBuf>,
+
+    /// Overwrite output file even when it was not generated by c4dart
+    #[structopt(long)]
+    force: bool,
     
     /// Library class name
     #[structopt(short, long)]
@@ -31,10 +48,90 @@ struct Args {
     #[structopt(short = "I", long, parse(from_os_str))]
     include_paths: Vec<PathBuf>,
     
+    /// Extra framework paths
+    #[structopt(short = "F", long = "framework-path", parse(from_os_str), number_of_values = 1)]
+    framework_paths: Vec<PathBuf>,
+
+    /// Extra system include paths
+    #[structopt(long = "isystem", parse(from_os_str), number_of_values = 1)]
+    isystem_paths: Vec<PathBuf>,
+
+    /// Target triple
+    #[structopt(long)]
+    target: Option<String>,
+
+    /// Android NDK path (configures target, sysroot and compiler)
+    #[structopt(long, parse(from_os_str))]
+    android_ndk: Option<PathBuf>,
+
+    /// Android API level
+    #[structopt(long, default_value = "24")]
+    android_api: u32,
+
+    /// Android architecture (arm, arm64, x86, x86_64)
+    #[structopt(long, default_value = "arm64")]
+    android_arch: AndroidArch,
+
+    /// Built-in options preset for popular library (sqlite3, libcurl)
+    #[structopt(long)]
+    preset: Option<String>,
+
+    /// Apple SDK (macosx, iphoneos, iphonesimulator)
+    #[structopt(long)]
+    apple_sdk: Option<AppleSdk>,
+
+    /// Target system root
+    #[structopt(long, parse(from_os_str))]
+    sysroot: Option<PathBuf>,
+
+    /// Preprocessor definition (NAME or NAME=VALUE)
+    #[structopt(long = "define", number_of_values = 1)]
+    defines: Vec<String>,
+
+    /// Extra argument of clang parser (like `-fblocks`)
+    #[structopt(long = "clang-arg", number_of_values = 1, allow_hyphen_values = true)]
+    clang_args: Vec<String>,
+
+    /// Do not merge parse configuration from environment (C4DART_INCLUDE_PATHS, C4DART_DEFINES, C4DART_CLANG_ARGS)
+    #[structopt(long)]
+    no_env: bool,
+
+    /// Set of comma-separated preprocessor definitions (the headers is translated once per set and merged)
+    #[structopt(long = "define-set", parse(from_str = parse_define_set), number_of_values = 1)]
+    define_sets: Vec<Vec<String>>,
+
+    /// Do not use standard system include paths
+    #[structopt(long)]
+    nostdinc: bool,
+
     /// Skip system include paths detection
     #[structopt(short = "D", long)]
     no_system_includes: bool,
 
+    /// Continue without system include paths when detection fails
+    #[structopt(long)]
+    no_system_includes_fallback: bool,
+
+    /// Compiler to detect system include paths (clang, cc or gcc by default)
+    #[structopt(long, parse(from_os_str))]
+    clang_path: Option<PathBuf>,
+
+    /// Parse headers in MSVC compatibility mode
+    #[structopt(long)]
+    msvc: bool,
+
+    /// Language standard of headers (like c99 or gnu11)
+    #[structopt(long = "std", default_value = "gnu17")]
+    c_standard: String,
+
+    /// Parse function bodies (skipped by default to speed up parsing)
+    #[structopt(long)]
+    parse_function_bodies: bool,
+
+    /// Continue parsing after fatal errors
+    #[structopt(long)]
+    keep_going: bool,
+
     /// Name match pattern
     #[structopt(short = "m", long = "match", env, parse(try_from_str = Regex::new), default_value = ".*")]
     names_match: Regex,
@@ -43,40 +140,923 @@ struct Args {
     #[structopt(short = "r", long = "replace", env, default_value = "$0")]
     names_replace: String,
 
-    /// Log level
-    #[structopt(short, long, env, parse(try_from_str), default_value = "off")]
-    log_level: LevelFilter,
+    /// Function name match pattern (overrides --match)
+    #[structopt(long, env, parse(try_from_str = Regex::new))]
+    function_match: Option<Regex>,
+
+    /// Skip function leaving commented stub with reason in output (name or name=reason, like `log_printf=variadic`)
+    #[structopt(long = "skip", parse(from_str = parse_skip), number_of_values = 1)]
+    skip_functions: Vec<(String, String)>,
+
+    /// Read functions to skip from file (one per line like --skip values, lines starting with `#` is ignored)
+    #[structopt(long, parse(from_os_str))]
+    skip_file: Option<PathBuf>,
+
+    /// Bind matched functions by additional library class which shares types with main one (Class=pattern)
+    #[structopt(long = "library", parse(try_from_str = parse_library), number_of_values = 1)]
+    libraries: Vec<(String, Regex)>,
+
+    /// Bind matched functions by optional feature class which is created only when library provides it (feature=pattern)
+    #[structopt(long = "feature", parse(try_from_str = parse_library), number_of_values = 1)]
+    features: Vec<(String, Regex)>,
+
+    /// Group matched functions under section region comment (Section=pattern)
+    #[structopt(long = "group", parse(try_from_str = parse_library), number_of_values = 1)]
+    groups: Vec<(String, Regex)>,
+
+    /// Group functions under sections by banner comments of headers
+    #[structopt(long)]
+    group_banners: bool,
+
+    /// Struct and typedef name match pattern (translate matched types unconditionally)
+    #[structopt(long, env, parse(try_from_str = Regex::new))]
+    type_match: Option<Regex>,
+
+    /// Enum name match pattern (overrides --match)
+    #[structopt(long, env, parse(try_from_str = Regex::new))]
+    enum_match: Option<Regex>,
+
+    /// Strip prefix from constants of matched enums (enum=PREFIX)
+    #[structopt(long = "enum-prefix", parse(try_from_str = parse_enum_prefix), number_of_values = 1)]
+    enum_prefixes: Vec<(Regex, String)>,
+
+    /// Macro name match pattern (translate macro constants)
+    #[structopt(long, env, parse(try_from_str = Regex::new))]
+    macro_match: Option<Regex>,
+
+    /// Generate shim function for matched function-like macro (macro=signature, like `int(int, int)`, requires --shim)
+    #[structopt(long = "macro-shim", parse(try_from_str = parse_macro_shim), number_of_values = 1)]
+    macro_shims: Vec<(Regex, String)>,
+
+    /// Embed library version macro into version class (field=MACRO, like `major=FOO_VERSION_MAJOR`)
+    #[structopt(long = "version-macro", parse(try_from_str = parse_version_macro), number_of_values = 1)]
+    version_macros: Vec<(String, String)>,
+
+    /// Check size of matched structs when library is loaded
+    #[structopt(long = "abi-struct", parse(try_from_str = Regex::new), number_of_values = 1)]
+    abi_structs: Vec<Regex>,
+
+    /// Check result of version function against version class field when library is loaded
+    /// (function=field, like `foo_version=number`, requires --version-macro)
+    #[structopt(long, parse(try_from_str = parse_abi_version))]
+    abi_version: Option<(String, String)>,
+
+    /// Emit debug functions which asserts sizes of structs and offsets of fields computed for the target
+    #[structopt(long)]
+    layout_checks: bool,
+
+    /// Translate code examples (`@code` or fenced blocks) of functions comments to Dart usage snippets
+    #[structopt(long)]
+    examples: bool,
+
+    /// Annotate functions marked `warn_unused_result` by `@useResult` (requires package:meta dependency)
+    #[structopt(long)]
+    use_result: bool,
+
+    /// Exclude declarations from headers which paths matches pattern
+    #[structopt(short = "X", long = "exclude-header", parse(try_from_str = Regex::new), number_of_values = 1)]
+    exclude_headers: Vec<Regex>,
+
+    /// Generate C shim source for static inline functions
+    #[structopt(long, parse(from_os_str))]
+    shim: Option<PathBuf>,
+
+    /// Write intermediate representation (JSON) of translated declarations
+    #[structopt(long, parse(from_os_str))]
+    emit_ir: Option<PathBuf>,
+
+    /// Shim functions names prefix (<class_name>_shim_ by default)
+    #[structopt(long)]
+    shim_prefix: Option<String>,
+
+    /// Map matched `void*` params to opaque types (function.param=Type)
+    #[structopt(long = "void-opaque", parse(try_from_str = parse_void_opaque), number_of_values = 1)]
+    void_opaques: Vec<(Regex, String)>,
+
+    /// Treat matched structs or typedefs as opaque types
+    #[structopt(long = "opaque-type", parse(try_from_str = Regex::new), number_of_values = 1)]
+    opaque_types: Vec<Regex>,
+
+    /// Do not treat common system types (FILE, DIR, ...) as opaque
+    #[structopt(long)]
+    no_system_opaques: bool,
+
+    /// Skip functions declared without prototypes (like `int foo();`)
+    #[structopt(long)]
+    strict_prototypes: bool,
+
+    /// Fail when less functions bound (0 to allow empty bindings)
+    #[structopt(long, default_value = "1")]
+    min_matches: usize,
+
+    /// Indentation width
+    #[structopt(long, default_value = "4")]
+    indent: usize,
+
+    /// Use tabs for indentation
+    #[structopt(long)]
+    tabs: bool,
+
+    /// Opening braces placement (same-line, next-line)
+    #[structopt(long, default_value = "same-line")]
+    braces: Braces,
+
+    /// Wrap parameters lists of longer lines (0 to disable)
+    #[structopt(long, default_value = "0")]
+    max_line_length: usize,
+
+    /// Shape of bindings (class, top-level functions with init call, native with @Native annotations, mixin)
+    #[structopt(long, default_value = "class")]
+    binding_shape: BindingShape,
+
+    /// Visibility of bound functions (public, private with public forwarding functions)
+    #[structopt(long, default_value = "public")]
+    member_visibility: MemberVisibility,
+
+    /// Order of functions, types and enum constants (source, alphabetical, grouped by sections)
+    #[structopt(long, default_value = "grouped")]
+    member_order: MemberOrder,
+
+    /// Write declarations to output as soon as it translated (reduces memory usage)
+    #[structopt(long)]
+    streaming: bool,
+
+    /// Emit markers of hand-written regions and keep its content from existing output
+    #[structopt(long)]
+    manual_regions: bool,
+
+    /// Symbol map sidecar with names of translated declarations (it will be updated)
+    #[structopt(long, parse(from_os_str))]
+    symbol_map: Option<PathBuf>,
+
+    /// Generate `part of` file with declarations added since previous run (requires --symbol-map)
+    #[structopt(long)]
+    part_of: Option<String>,
+
+    /// Generate convenience wrappers and helpers (requires package:ffi)
+    #[structopt(short, long)]
+    wrappers: bool,
+
+    /// Length param name pattern of (pointer, length) buffer params pairs in wrappers
+    #[structopt(long, env, parse(try_from_str = Regex::new), default_value = "^(n|len|length|size)$|_(len|length|size)$")]
+    buffer_length_match: Regex,
+
+    /// Free strings returned by matched functions in wrappers (function=free_function, malloc.free by default)
+    #[structopt(long = "owned-string", parse(try_from_str = parse_owned_string), number_of_values = 1)]
+    owned_strings: Vec<(Regex, String)>,
+
+    /// Make matched trailing params (function.param) optional named in wrappers (with `=default` value, null for pointers by default)
+    #[structopt(long = "optional-param", parse(try_from_str = parse_optional_param), number_of_values = 1)]
+    optional_params: Vec<(Regex, Option<String>)>,
+
+    /// Mark matched pointer params (function.param) as accepting NULL (null is allowed in wrappers)
+    #[structopt(long = "nullable-param", parse(try_from_str = Regex::new), number_of_values = 1)]
+    nullable_params: Vec<Regex>,
+
+    /// Extra library to import in generated file (like package:meta/meta.dart)
+    #[structopt(long = "import", number_of_values = 1)]
+    imports: Vec<String>,
+
+    /// Analyzer lint to ignore in generated file using `// ignore_for_file:` directive
+    #[structopt(long = "ignore-lint", number_of_values = 1)]
+    ignore_lints: Vec<String>,
+
+    /// Generate legacy code without null safety (for Dart SDK before 2.12)
+    #[structopt(long = "no-null-safety")]
+    no_null_safety: bool,
+
+    /// Generate extensions on struct pointers with field accessors (`ptr.field` instead of `ptr.ref.field`)
+    #[structopt(long = "pointer-extensions")]
+    pointer_extensions: bool,
+
+    /// Generate `debugString()` extensions which lists struct fields with values
+    #[structopt(long = "debug-strings")]
+    debug_strings: bool,
+
+    /// Alias nested pointer types used in signatures at least that many times by typedefs (0 to disable)
+    #[structopt(long, default_value = "0")]
+    pointer_aliases: usize,
+
+    /// Bind complex numbers as structs with real and imaginary parts (functions which uses it is skipped otherwise)
+    #[structopt(long)]
+    complex_structs: bool,
+
+    /// Bind vector (SIMD) types in struct fields and pointers as structs of bytes (such structs is opaque otherwise)
+    #[structopt(long)]
+    vector_structs: bool,
+
+    /// Function name match pattern for asynchronous wrappers (runs calls via Isolate.run)
+    #[structopt(long, env, parse(try_from_str = Regex::new))]
+    async_match: Option<Regex>,
+
+    /// Deliver matched callbacks (function.param) from any thread via native ports (requires --shim)
+    #[structopt(long = "port-callback", parse(try_from_str = Regex::new), number_of_values = 1)]
+    port_callbacks: Vec<Regex>,
+
+    /// Mark matched functions as not thread-safe
+    #[structopt(long = "not-thread-safe", parse(try_from_str = Regex::new), number_of_values = 1)]
+    not_thread_safe: Vec<Regex>,
+
+    /// Mark matched functions as must be called on the main thread
+    #[structopt(long = "main-thread", parse(try_from_str = Regex::new), number_of_values = 1)]
+    main_thread: Vec<Regex>,
+
+    /// Assert that main thread functions are called on the main isolate in wrappers
+    #[structopt(long)]
+    thread_asserts: bool,
+
+    /// Map typedef to native type (typedef=Type)
+    #[structopt(long = "typedef-type", parse(try_from_str = parse_typedef_type), number_of_values = 1)]
+    typedef_types: Vec<(String, String)>,
+
+    /// Diagnostics format (text, json)
+    #[structopt(long, default_value = "text")]
+    diagnostics_format: DiagnosticsFormat,
+
+    /// Write diagnostics to file instead of stderr
+    #[structopt(long, parse(from_os_str))]
+    diagnostics_output: Option<PathBuf>,
+
+    /// Fail when any warnings occurred
+    #[structopt(long)]
+    deny_warnings: bool,
+
+    /// Do not show progress (it is shown only when stderr is a terminal)
+    #[structopt(long)]
+    no_progress: bool,
+
+    /// Print durations of translation phases
+    #[structopt(long)]
+    timings: bool,
+
+    /// Print only machine-parsable summary to stdout (`key value` lines like `status ok` or `functions 12`)
+    #[structopt(long)]
+    porcelain: bool,
+
+    /// Log level or per-module filters (like `warn,c4dart::translator=debug`)
+    #[structopt(short, long, env, default_value = "off")]
+    log_level: String,
+
+    /// Write log to file instead of stderr
+    #[structopt(long, parse(from_os_str))]
+    log_file: Option<PathBuf>,
+
+    /// Log format (text, json)
+    #[structopt(long, default_value = "text")]
+    log_format: LogFormat,
+
+    #[structopt(subcommand)]
+    command: Option<Command>,
+}
+
+/// Subcommands
+#[derive(Debug, structopt::StructOpt)]
+enum Command {
+    /// Report API changes between two versions of C headers
+    Diff {
+        /// Old C headers
+        #[structopt(parse(from_os_str))]
+        old: PathBuf,
+        /// New C headers
+        #[structopt(parse(from_os_str))]
+        new: PathBuf,
+    },
+    /// Merge intermediate representations produced for several platforms into superset IR
+    MergeIr {
+        /// IR files written using --emit-ir (as platform=path or path which stem is platform name)
+        #[structopt(parse(try_from_str = parse_platform_ir), required = true)]
+        irs: Vec<(String, PathBuf)>,
+    },
+    /// Generate Dart bindings from saved intermediate representation (no libclang required)
+    FromIr {
+        /// IR file written using --emit-ir
+        #[structopt(parse(from_os_str))]
+        ir: PathBuf,
+    },
+}
+
+fn parse_platform_ir(src: &str) -> Result<(String, PathBuf), String> {
+    let mut parts = src.splitn(2, '=');
+    
+    let first = parts.next().unwrap();
+    
+    Ok(if let Some(path) = parts.next() {
+        (first.into(), path.into())
+    } else {
+        let path = PathBuf::from(first);
+        let platform = path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .ok_or_else(|| format!("Missing platform name in `{}`", src))?
+            .into();
+        (platform, path)
+    })
+}
+
+fn parse_define_set(src: &str) -> Vec<String> {
+    src.split(',')
+        .map(|define| define.trim())
+        .filter(|define| !define.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn parse_void_opaque(src: &str) -> Result<(Regex, String), String> {
+    let mut parts = src.splitn(2, '=');
+    
+    let pattern = parts.next().unwrap();
+    let type_name = parts.next()
+        .ok_or_else(|| format!("Missing opaque type name in `{}`", src))?;
+    
+    let regex = Regex::new(pattern).map_err(|error| error.to_string())?;
+    
+    Ok((regex, type_name.into()))
+}
+
+fn parse_library(src: &str) -> Result<(String, Regex), String> {
+    let mut parts = src.splitn(2, '=');
+    
+    let class_name = parts.next().unwrap();
+    let pattern = parts.next()
+        .ok_or_else(|| format!("Missing function name pattern in `{}`", src))?;
+    
+    let regex = Regex::new(pattern).map_err(|error| error.to_string())?;
+    
+    Ok((class_name.into(), regex))
+}
+
+fn parse_skip(src: &str) -> (String, String) {
+    let mut parts = src.splitn(2, '=');
+
+    let name = parts.next().unwrap().trim();
+    let reason = parts.next()
+        .map(|reason| reason.trim())
+        .filter(|reason| !reason.is_empty())
+        .unwrap_or("excluded by configuration");
+
+    (name.into(), reason.into())
+}
+
+fn parse_owned_string(src: &str) -> Result<(Regex, String), String> {
+    let mut parts = src.splitn(2, '=');
+    
+    let pattern = parts.next().unwrap();
+    let free = parts.next().unwrap_or("malloc.free");
+    
+    let regex = Regex::new(pattern).map_err(|error| error.to_string())?;
+    
+    Ok((regex, free.into()))
+}
+
+fn parse_optional_param(src: &str) -> Result<(Regex, Option<String>), String> {
+    let mut parts = src.splitn(2, '=');
+    
+    let pattern = parts.next().unwrap();
+    let default = parts.next();
+    
+    let regex = Regex::new(pattern).map_err(|error| error.to_string())?;
+    
+    Ok((regex, default.map(String::from)))
+}
+
+fn parse_macro_shim(src: &str) -> Result<(Regex, String), String> {
+    let mut parts = src.splitn(2, '=');
+    
+    let pattern = parts.next().unwrap();
+    let signature = parts.next()
+        .ok_or_else(|| format!("Missing shim signature in `{}`", src))?;
+    
+    let regex = Regex::new(pattern).map_err(|error| error.to_string())?;
+    
+    Ok((regex, signature.into()))
+}
+
+fn parse_enum_prefix(src: &str) -> Result<(Regex, String), String> {
+    let mut parts = src.splitn(2, '=');
+    
+    let pattern = parts.next().unwrap();
+    let prefix = parts.next()
+        .ok_or_else(|| format!("Missing constants prefix in `{}`", src))?;
+    
+    let regex = Regex::new(pattern).map_err(|error| error.to_string())?;
+    
+    Ok((regex, prefix.into()))
+}
+
+fn parse_typedef_type(src: &str) -> Result<(String, String), String> {
+    let mut parts = src.splitn(2, '=');
+    
+    let name = parts.next().unwrap();
+    let type_name = parts.next()
+        .ok_or_else(|| format!("Missing native type name in `{}`", src))?;
+    
+    Ok((name.into(), type_name.into()))
+}
+
+fn parse_version_macro(src: &str) -> Result<(String, String), String> {
+    let mut parts = src.splitn(2, '=');
+    
+    let field = parts.next().unwrap();
+    let name = parts.next()
+        .ok_or_else(|| format!("Missing version macro name in `{}`", src))?;
+    
+    Ok((field.into(), name.into()))
+}
+
+fn parse_abi_version(src: &str) -> Result<(String, String), String> {
+    let mut parts = src.splitn(2, '=');
+    
+    let function = parts.next().unwrap();
+    let field = parts.next()
+        .ok_or_else(|| format!("Missing version class field in `{}`", src))?;
+    
+    Ok((function.into(), field.into()))
+}
+
+/// Machine-parsable summary is printed instead of human-readable one
+static PORCELAIN: AtomicBool = AtomicBool::new(false);
+
+/// Exit code when bindings cannot be generated (or warnings is denied)
+const EXIT_ERROR: i32 = 1;
+/// Exit code when parser reports errors in headers
+const EXIT_PARSE: i32 = 2;
+/// Exit code when too few functions matched by patterns
+const EXIT_UNMATCHED: i32 = 3;
+
+/// Get exit code of translation error
+fn error_code(error: &Error) -> i32 {
+    match error {
+        Error::Parse(_) => EXIT_PARSE,
+        Error::Unmatched(_) => EXIT_UNMATCHED,
+        _ => EXIT_ERROR,
+    }
+}
+
+/// Report failure (with porcelain status when requested) and exit with code
+fn fail(code: i32, message: impl Display) -> ! {
+    let status = match code {
+        EXIT_PARSE => "parse-error",
+        EXIT_UNMATCHED => "unmatched",
+        _ => "error",
+    };
+    if PORCELAIN.load(Ordering::Relaxed) {
+        print_porcelain(status, None, &Report::default());
+    } else {
+        eprintln!("{}", message);
+    }
+    std::process::exit(code);
+}
+
+/// Print machine-parsable summary of translation
+fn print_porcelain(status: &str, output: Option<&Path>, report: &Report) {
+    println!("status {}", status);
+    if let Some(path) = output {
+        println!("output {}", path.display());
+    }
+    let stats = &report.stats;
+    for (key, value) in &[("functions", stats.functions), ("structs", stats.structs), ("enums", stats.enums),
+                          ("macros", stats.macros), ("function_macros", stats.function_macros),
+                          ("skipped", stats.skipped), ("warnings", report.warnings()),
+                          ("parse_errors", report.parse_errors)] {
+        println!("{} {}", key, value);
+    }
+}
+
+/// Log records format
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LogFormat {
+    Text,
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(match src {
+            "text" => LogFormat::Text,
+            "json" => LogFormat::Json,
+            _ => return Err(format!("Unknown log format: `{}` (expected text or json)", src)),
+        })
+    }
+}
+
+/// Logger which writes plain or JSON records to any output
+struct WriteLogger {
+    filter: Filter,
+    format: LogFormat,
+    output: Mutex<Box<dyn Write + Send>>,
+}
+
+impl Log for WriteLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.filter.matches(record) {
+            return;
+        }
+        
+        let mut output = self.output.lock().unwrap_or_else(|error| error.into_inner());
+        
+        let _ = match self.format {
+            LogFormat::Text => writeln!(output, "{:<5} {} > {}", record.level(), record.target(), record.args()),
+            LogFormat::Json => writeln!(output, "{}", json_log_record(record.level().as_str(), record.target(),
+                                                                      &record.args().to_string())),
+        };
+    }
+
+    fn flush(&self) {
+        let _ = self.output.lock().unwrap_or_else(|error| error.into_inner()).flush();
+    }
 }
 
-#[paw::main]
-fn main(args: Args) {
+fn main() {
+    // the response files is expanded to bypass command-line length limits
+    let args = Args::from_iter(expand_response_files(std::env::args_os()).unwrap_or_else(|error| {
+        fail(EXIT_ERROR, format!("Unable to expand arguments: {}", error))
+    }));
+
+    PORCELAIN.store(args.porcelain, Ordering::Relaxed);
+
     if args.version {
         println!("Version: {}", env!("CARGO_PKG_VERSION"));
         return;
     }
 
     {
-        std::env::set_var("__LOG_LEVEL_FILTER__", args.log_level.to_string());
-        pretty_env_logger::init_custom_env("__LOG_LEVEL_FILTER__");
+        let (inner, level): (Box<dyn Log>, _) = if args.log_file.is_none() && args.log_format == LogFormat::Text {
+            let inner = pretty_env_logger::formatted_builder()
+                .parse_filters(&args.log_level)
+                .build();
+            let level = inner.filter();
+            (Box::new(inner), level)
+        } else {
+            let output: Box<dyn Write + Send> = if let Some(path) = &args.log_file {
+                Box::new(File::create(path)
+                         .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to create log file: {}", error))))
+            } else {
+                Box::new(std::io::stderr())
+            };
+            let filter = FilterBuilder::new().parse(&args.log_level).build();
+            let level = filter.filter();
+            (Box::new(WriteLogger { filter, format: args.log_format, output: Mutex::new(output) }), level)
+        };
+        log::set_max_level(level);
+        log::set_boxed_logger(inner)
+            .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to setup logger: {}", error)));
     }
 
-    let input = args.input.expect("Missing input C header");
-    let output = args.output.expect("Missing output Dart source");
+    let command = args.command;
+
+    if let Some(Command::MergeIr { irs }) = &command {
+        let output = args.output.as_ref().unwrap_or_else(|| fail(EXIT_ERROR, "Missing output IR file"));
+        merge_irs(irs, output, args.diagnostics_format, args.diagnostics_output.as_ref());
+        return;
+    }
+    
+    let (input, output) = match &command {
+        Some(Command::Diff { old, .. }) => (old.clone(), None),
+        Some(Command::MergeIr { .. }) => unreachable!(),
+        Some(Command::FromIr { ir }) => (ir.clone(), args.output),
+        None => (args.input.unwrap_or_else(|| fail(EXIT_ERROR, "Missing input C header")), args.output),
+    };
 
     let class_name = args.class_name.or_else(|| {
-        input.file_stem().or_else(|| output.file_stem())
+        input.file_stem().or_else(|| output.as_ref().and_then(|output| output.file_stem()))
             .and_then(|name| name.to_str()).map(|name| name.into())
-    }).expect("Missing library class name");
+    }).unwrap_or_else(|| fail(EXIT_ERROR, "Missing library class name"));
+
+    let shim_prefix = args.shim_prefix.unwrap_or_else(|| {
+        format!("{}_shim_", class_name.to_lowercase())
+    });
 
-    let options = Options {
+    let mut options = Options {
         class_name: class_name,
         include_paths: args.include_paths,
+        framework_paths: args.framework_paths,
+        isystem_paths: args.isystem_paths,
+        target: args.target,
+        sysroot: args.sysroot,
+        defines: args.defines,
+        define_sets: args.define_sets,
+        clang_args: args.clang_args,
+        no_std_includes: args.nostdinc,
         detect_isystem: !args.no_system_includes,
+        isystem_fallback: args.no_system_includes_fallback,
+        clang_path: args.clang_path,
+        msvc: args.msvc,
+        c_standard: args.c_standard,
+        skip_function_bodies: !args.parse_function_bodies,
+        keep_going: args.keep_going,
+        progress: if !args.no_progress && !args.porcelain && std::io::stderr().is_terminal() {
+            Some(print_progress)
+        } else {
+            None
+        },
         names_match: args.names_match,
         names_replace: args.names_replace,
+        function_filter: args.function_match,
+        skip_functions: args.skip_functions,
+        libraries: args.libraries,
+        features: args.features,
+        groups: args.groups,
+        group_banners: args.group_banners,
+        type_filter: args.type_match,
+        enum_filter: args.enum_match,
+        enum_prefixes: args.enum_prefixes,
+        macro_filter: args.macro_match,
+        macro_shims: args.macro_shims,
+        version_macros: args.version_macros,
+        abi_structs: args.abi_structs,
+        abi_version: args.abi_version,
+        layout_checks: args.layout_checks,
+        examples: args.examples,
+        use_result: args.use_result,
+        exclude_headers: args.exclude_headers,
+        shim_output: args.shim,
+        ir_output: args.emit_ir,
+        shim_prefix,
+        void_opaques: args.void_opaques,
+        opaque_types: args.opaque_types,
+        no_system_opaques: args.no_system_opaques,
+        strict_prototypes: args.strict_prototypes,
+        min_matches: args.min_matches,
+        style: Style {
+            indent: args.indent,
+            tabs: args.tabs,
+            braces: args.braces,
+            max_line_length: args.max_line_length,
+        },
+        binding_shape: args.binding_shape,
+        member_visibility: args.member_visibility,
+        member_order: args.member_order,
+        streaming: args.streaming,
+        manual_regions: args.manual_regions,
+        known_symbols: HashSet::new(),
+        part_of: args.part_of,
+        wrappers: args.wrappers,
+        buffer_length_match: args.buffer_length_match,
+        owned_strings: args.owned_strings,
+        optional_params: args.optional_params,
+        nullable_params: args.nullable_params,
+        imports: args.imports,
+        ignore_lints: args.ignore_lints,
+        null_safety: !args.no_null_safety,
+        pointer_extensions: args.pointer_extensions,
+        pointer_aliases: args.pointer_aliases,
+        complex_structs: args.complex_structs,
+        vector_structs: args.vector_structs,
+        debug_strings: args.debug_strings,
+        async_filter: args.async_match,
+        port_callbacks: args.port_callbacks,
+        not_thread_safe: args.not_thread_safe,
+        main_thread: args.main_thread,
+        thread_asserts: args.thread_asserts,
+        typedef_types: args.typedef_types,
+    };
+
+    if let Some(ndk) = &args.android_ndk {
+        options.android_ndk(ndk, args.android_api, args.android_arch)
+            .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to configure Android NDK: {}", error)));
+    }
+
+    if let Some(sdk) = args.apple_sdk {
+        options.apple_sdk(sdk)
+            .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to configure Apple SDK: {}", error)));
+    }
+
+    if !args.no_env {
+        options.merge_env();
+    }
+
+    if let Some(path) = &args.skip_file {
+        let src = read_to_string(path)
+            .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to read skip list: {}", error)));
+        options.skip_functions.extend(src.lines()
+                                      .map(|line| line.trim())
+                                      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                                      .map(parse_skip));
+    }
+
+    if let Some(preset) = &args.preset {
+        options.preset(preset).unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to apply preset: {}", error)));
+    }
+
+    // the lazy fields and native annotations has no legacy equivalents
+    if !options.null_safety {
+        let shape = match options.binding_shape {
+            BindingShape::Mixin => Some("mixin"),
+            BindingShape::NativeAnnotations => Some("native"),
+            _ => None,
+        };
+        if let Some(shape) = shape {
+            fail(EXIT_ERROR, format!("The --binding-shape {} requires null-safe code", shape));
+        }
+    }
+
+    if options.part_of.is_some() {
+        let path = args.symbol_map.as_ref()
+            .unwrap_or_else(|| fail(EXIT_ERROR, "The --part-of requires --symbol-map"));
+        options.known_symbols = read_symbol_map(path)
+            .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to read symbol map: {}", error)));
+        // all functions may be already bound
+        options.min_matches = 0;
+    }
+
+    if let Some(Command::Diff { old, new }) = command {
+        diff_headers(options, &old, &new);
+        return;
+    }
+
+    // the package name is derived from class name
+    let package = args.package_out.map(|dir| (dir, package_name(&options.class_name)));
+
+    let output = output.or_else(|| package.as_ref().map(|(dir, name)| package_bindings_path(dir, name)))
+        .unwrap_or_else(|| fail(EXIT_ERROR, "Missing output Dart source"));
+
+    // hand-written regions should be read before output will be truncated
+    let previous = if options.manual_regions {
+        read_to_string(&output).ok()
+    } else {
+        None
+    };
+    
+    if !args.force && output.exists() &&
+        !read_to_string(&output).map(|src| is_generated(&src)).unwrap_or(false) {
+            fail(EXIT_ERROR, format!("Refusing to overwrite `{}` which was not generated by c4dart (use --force to overwrite)",
+                                     output.display()));
+        }
+
+    if let Some(dir) = output.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        create_dir_all(dir)
+            .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to create output directory: {}", error)));
+    }
+
+    // the output will be replaced by temporary file when translation succeeded
+    let file_name = output.file_name()
+        .unwrap_or_else(|| fail(EXIT_ERROR, format!("Invalid output Dart source: `{}`", output.display())));
+    let temp_output = output.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+    
+    let mut output_file = File::create(&temp_output)
+        .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to create output file: {}", error)));
+    
+    let progress = options.progress.is_some();
+    let known_symbols = options.known_symbols.clone();
+    
+    let from_ir = matches!(command, Some(Command::FromIr { .. }));
+
+    let generate = |options: Options, mut output: &mut dyn Write| if from_ir {
+        // the diagnostics is not available for saved IR
+        read_to_string(&input).map_err(Error::from)
+            .and_then(|src| translate_ir(options.style, &src, &mut output))
+    } else {
+        translate(options, &input, &mut output)
+    };
+    
+    let report = if let Some(previous) = previous {
+        let mut generated = Vec::new();
+        let report = generate(options, &mut generated);
+        
+        output_file.write_all(merge_manual_regions(&previous, &String::from_utf8_lossy(&generated)).as_bytes())
+            .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to write output file: {}", error)));
+        
+        report
+    } else {
+        generate(options, &mut output_file)
+    };
+
+    drop(output_file);
+
+    if let Ok(report) = &report {
+        rename(&temp_output, &output)
+            .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to replace output file: {}", error)));
+
+        if let Some((dir, name)) = &package {
+            write_package(dir, name, &report.requirements)
+                .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to write package files: {}", error)));
+        }
+    } else {
+        let _ = remove_file(&temp_output);
+    }
+
+    if progress {
+        // clear progress line
+        eprint!("\r\x1b[K");
+    }
+
+    let diagnostics = match &report {
+        Ok(report) => report.diagnostics.clone(),
+        Err(error) => vec![Diagnostic::new(Severity::Error, error.to_string())],
+    };
+
+    write_diagnostics(&diagnostics, args.diagnostics_format, args.diagnostics_output.as_ref());
+
+    let report = match report {
+        Ok(report) => report,
+        Err(error) => fail(error_code(&error), format!("Unable to translate declarations: {}", error)),
+    };
+
+    if let Some(path) = &args.symbol_map {
+        write_symbol_map(path, known_symbols.iter().chain(&report.symbols))
+            .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to write symbol map: {}", error)));
+    }
+
+    let warnings = report.warnings();
+
+    // the output is written anyway but it may be incomplete when headers has errors
+    let (code, status) = if report.parse_errors > 0 {
+        (EXIT_PARSE, "parse-error")
+    } else if args.deny_warnings && warnings > 0 {
+        (EXIT_ERROR, "warnings-denied")
+    } else {
+        (0, "ok")
     };
 
-    let mut output_file = File::create(&output).expect("Unable to create output file");
+    if args.porcelain {
+        print_porcelain(status, Some(&output), &report);
+    } else {
+        eprintln!("Translated: {}, {} warnings", report.stats, warnings);
+
+        if args.timings {
+            eprintln!("Timings:\n{}", report.timings);
+        }
+
+        if report.parse_errors > 0 {
+            eprintln!("Headers has {} errors", report.parse_errors);
+        } else if code != 0 {
+            eprintln!("Warnings are denied");
+        }
+    }
+
+    if code != 0 {
+        std::process::exit(code);
+    }
+}
+
+/// Print progress when percentage changes
+fn print_progress(progress: &Progress) {
+    static PERCENT: AtomicUsize = AtomicUsize::new(usize::MAX);
+    
+    let percent = (progress.done * 100).checked_div(progress.total).unwrap_or(100);
+    
+    if PERCENT.swap(percent, Ordering::Relaxed) != percent {
+        eprint!("\r\x1b[K{}: {}/{} ({}%)", progress.phase, progress.done, progress.total, percent);
+    }
+}
+
+fn diff_headers(options: Options, old: &Path, new: &Path) {
+    let parse_header = |options, input: &Path| parse(options, input).unwrap_or_else(|error| {
+        fail(error_code(&error), format!("Unable to parse `{}`: {}", input.display(), error))
+    });
     
-    translate(options, &input, &mut output_file).expect("Unable to translate declarations");
+    let old = parse_header(options.clone(), old);
+    let new = parse_header(options, new);
+
+    let changes = diff(&old, &new);
+
+    for change in &changes {
+        println!("{}", change);
+    }
+
+    eprintln!("API changes: {}", changes.len());
+}
+
+fn merge_irs(irs: &[(String, PathBuf)], output: &Path, format: DiagnosticsFormat, diagnostics_output: Option<&PathBuf>) {
+    let inputs = irs.iter()
+        .map(|(platform, path)| (platform.clone(), read_to_string(path).unwrap_or_else(|error| {
+            fail(EXIT_ERROR, format!("Unable to read `{}`: {}", path.display(), error))
+        })))
+        .collect::<Vec<_>>();
+
+    let (ir, diagnostics) = merge_ir(&inputs)
+        .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to merge IR: {}", error)));
+
+    let mut output = File::create(output)
+        .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to create output IR file: {}", error)));
+    writeln!(output, "{}", ir)
+        .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to write output IR file: {}", error)));
+
+    write_diagnostics(&diagnostics, format, diagnostics_output);
+
+    eprintln!("Merged: {} platforms, {} differences", inputs.len(), diagnostics.len());
+}
+
+fn write_diagnostics(diagnostics: &[Diagnostic], format: DiagnosticsFormat, path: Option<&PathBuf>) {
+    let mut output: Box<dyn Write> = if let Some(path) = path {
+        Box::new(File::create(path)
+                 .unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to create diagnostics file: {}", error))))
+    } else if format == DiagnosticsFormat::Text {
+        // text diagnostics is already logged
+        return;
+    } else {
+        Box::new(std::io::stderr())
+    };
+
+    for diagnostic in diagnostics {
+        match format {
+            DiagnosticsFormat::Text => writeln!(output, "{}: {}", diagnostic.severity.name(), diagnostic),
+            DiagnosticsFormat::Json => writeln!(output, "{}", diagnostic.to_json()),
+        }.unwrap_or_else(|error| fail(EXIT_ERROR, format!("Unable to write diagnostics: {}", error)));
+    }
 }
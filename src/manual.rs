@@ -0,0 +1,71 @@
+use log::*;
+
+/// Marker of hand-written region beginning followed by region name
+pub const BEGIN_MANUAL: &str = "// c4dart:begin-manual";
+
+/// Marker of hand-written region end
+pub const END_MANUAL: &str = "// c4dart:end-manual";
+
+/// Hand-written region of source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManualRegion<'a> {
+    pub name: &'a str,
+    /// Lines between markers
+    pub lines: Vec<&'a str>,
+}
+
+/// Find hand-written regions in source
+pub fn manual_regions(src: &str) -> Vec<ManualRegion<'_>> {
+    let mut regions = Vec::new();
+    let mut current: Option<ManualRegion> = None;
+
+    for line in src.lines() {
+        let marker = line.trim();
+        
+        if let Some(name) = marker.strip_prefix(BEGIN_MANUAL) {
+            current = Some(ManualRegion { name: name.trim(), lines: Vec::new() });
+        } else if marker == END_MANUAL {
+            regions.extend(current.take());
+        } else if let Some(region) = &mut current {
+            region.lines.push(line);
+        }
+    }
+
+    regions
+}
+
+/// Carry hand-written regions of previous source into generated one
+///
+/// The regions is placed between the markers with same names. The regions which
+/// markers is missing in generated source will be appended to the end.
+pub fn merge_manual_regions(previous: &str, generated: &str) -> String {
+    let mut regions = manual_regions(previous);
+    let mut out = String::with_capacity(previous.len().max(generated.len()));
+
+    for line in generated.lines() {
+        out.push_str(line);
+        out.push('\n');
+        
+        if let Some(name) = line.trim().strip_prefix(BEGIN_MANUAL) {
+            if let Some(index) = regions.iter().position(|region| region.name == name.trim()) {
+                for line in regions.remove(index).lines {
+                    out.push_str(line);
+                    out.push('\n');
+                }
+            }
+        }
+    }
+
+    for region in regions {
+        warn!("Manual region `{}` has no place in generated source so it is appended to the end", region.name);
+        out.push_str(&format!("{} {}\n", BEGIN_MANUAL, region.name));
+        for line in region.lines {
+            out.push_str(line);
+            out.push('\n');
+        }
+        out.push_str(END_MANUAL);
+        out.push('\n');
+    }
+
+    out
+}
@@ -0,0 +1,321 @@
+use crate::{Result, Diagnostic, Severity, Decl, FieldDef, LibraryDef, Json, ToJson, FromJson};
+
+/// Merge intermediate representations produced for several platforms
+///
+/// The inputs is pairs of platform names and IR sources. The result is superset IR
+/// where declarations which is missing on some platforms is documented and functions
+/// is looked up only when library provides it. The definitions which differs between
+/// platforms (like widths of types) is reported and taken from the first platform.
+pub fn merge_ir(inputs: &[(String, String)]) -> Result<(String, Vec<Diagnostic>)> {
+    let mut platforms = Vec::new();
+
+    for (platform, src) in inputs {
+        let json = Json::parse(src)
+            .map_err(|error| format!("Invalid IR of `{}`: {}", platform, error))?;
+        let decls = Vec::<Decl>::from_json(&json)
+            .map_err(|error| format!("Invalid IR of `{}`: {}", platform, error))?;
+        platforms.push((platform.as_str(), decls));
+    }
+
+    let (decls, diagnostics) = merge_decls(&platforms, "on");
+
+    Ok((decls.to_json().to_string(), diagnostics))
+}
+
+/// Merge declarations translated under several conditions (platforms or define sets)
+///
+/// The condition preposition is used in notes like `Only available on: linux.`
+pub(crate) fn merge_decls(variants: &[(&str, Vec<Decl>)], preposition: &'static str) -> (Vec<Decl>, Vec<Diagnostic>) {
+    let mut merger = Merger { platforms: variants, preposition, diagnostics: Vec::new() };
+    let decls = merger.merge();
+
+    (decls, merger.diagnostics)
+}
+
+struct Merger<'a> {
+    platforms: &'a [(&'a str, Vec<Decl>)],
+    preposition: &'static str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Merger<'a> {
+    fn warn(&mut self, name: &str, reason: String) {
+        let diagnostic = Diagnostic {
+            name: Some(name.into()),
+            ..Diagnostic::new(Severity::Warning, reason)
+        };
+        diagnostic.log();
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Note about platforms which provides declaration (nothing when it is provided by all)
+    fn availability_note(&self, platforms: &[&str]) -> Option<String> {
+        if platforms.len() < self.platforms.len() {
+            Some(format!("Only available {}: {}.", self.preposition, platforms.join(", ")))
+        } else {
+            None
+        }
+    }
+
+    fn merge(&mut self) -> Vec<Decl> {
+        // the order of first platform is kept and the missing declarations is appended
+        let mut keys = Vec::new();
+        for (_, decls) in self.platforms {
+            for decl in decls {
+                let key = decl_key(decl);
+                if !keys.contains(&key) {
+                    keys.push(key);
+                }
+            }
+        }
+
+        keys.iter().map(|key| {
+            let variants = self.platforms.iter()
+                .filter_map(|(platform, decls)| decls.iter()
+                            .find(|decl| &decl_key(decl) == key)
+                            .map(|decl| (*platform, decl)))
+                .collect::<Vec<_>>();
+            self.merge_decl(&variants)
+        }).collect()
+    }
+
+    fn merge_decl(&mut self, variants: &[(&str, &Decl)]) -> Decl {
+        use Decl::*;
+
+        let platforms = variants.iter().map(|(platform, _)| *platform).collect::<Vec<_>>();
+        let note = self.availability_note(&platforms);
+        let mut merged = variants[0].1.clone();
+
+        match &mut merged {
+            Imports(imports) => for (_, decl) in &variants[1..] {
+                if let Imports(other) = decl {
+                    imports.libraries.extend(other.libraries.iter().map(String::from));
+                }
+            },
+            FunctionMacros(list) => for (_, decl) in &variants[1..] {
+                if let FunctionMacros(other) = decl {
+                    union(list, other, |item| item.clone());
+                }
+            },
+            ComplexHelpers(names) => for (_, decl) in &variants[1..] {
+                if let ComplexHelpers(other) = decl {
+                    union(names, other, |name| name.clone());
+                }
+            },
+            Opaques(names) => {
+                for (_, decl) in &variants[1..] {
+                    if let Opaques(other) = decl {
+                        union(names, other, |name| name.clone());
+                    }
+                }
+                names.sort();
+            },
+            FuncTypes(functypes) => for (platform, decl) in &variants[1..] {
+                if let FuncTypes(other) = decl {
+                    for (name, func) in other {
+                        match functypes.iter().find(|(other_name, _)| other_name == name) {
+                            Some((_, first)) if first.cffi != func.cffi => {
+                                let reason = format!("Function type differs: `{}` on {} but `{}` on {}",
+                                                     first.cffi, platforms[0], func.cffi, platform);
+                                self.warn(name, reason);
+                            },
+                            Some(_) => {},
+                            None => functypes.push((name.clone(), func.clone())),
+                        }
+                    }
+                }
+            },
+            TypeAliases(aliases) => for (platform, decl) in &variants[1..] {
+                if let TypeAliases(other) = decl {
+                    for (name, type_) in other {
+                        match aliases.iter().find(|(other_name, _)| other_name == name) {
+                            Some((_, first)) if first != type_ => {
+                                let reason = format!("Type alias differs: `{}` on {} but `{}` on {}",
+                                                     first, platforms[0], type_, platform);
+                                self.warn(name, reason);
+                            },
+                            Some(_) => {},
+                            None => aliases.push((name.clone(), type_.clone())),
+                        }
+                    }
+                }
+            },
+            Library(library) => self.merge_library(library, variants),
+            Struct(struct_) => {
+                for (platform, decl) in &variants[1..] {
+                    if let Struct(other) = decl {
+                        for field in &struct_.fields {
+                            let other_field = other.fields.iter().find(|other_field| other_field.name == field.name);
+                            let spelling = |field: &FieldDef| match &field.annotation {
+                                Some(annotation) => format!("{} {}", annotation, field.type_),
+                                None => field.type_.clone(),
+                            };
+                            match other_field {
+                                Some(other_field) if spelling(other_field) != spelling(field) => {
+                                    let reason = format!("Field `{}` differs: `{}` on {} but `{}` on {}",
+                                                         field.name, spelling(field), platforms[0],
+                                                         spelling(other_field), platform);
+                                    self.warn(&struct_.name, reason);
+                                },
+                                Some(_) => {},
+                                None => self.warn(&struct_.name, format!("Field `{}` is missing on {}",
+                                                                         field.name, platform)),
+                            }
+                        }
+                        for other_field in &other.fields {
+                            if !struct_.fields.iter().any(|field| field.name == other_field.name) {
+                                self.warn(&struct_.name, format!("Field `{}` is only declared on {}",
+                                                                 other_field.name, platform));
+                            }
+                        }
+                    }
+                }
+                add_note(&mut struct_.cmt, note);
+            },
+            Enum(enum_) => {
+                // the constants which is missing under some conditions is documented
+                for (platform, decl) in &variants[1..] {
+                    if let Enum(other) = decl {
+                        for constant in &other.constants {
+                            match enum_.constants.iter().find(|first| first.name == constant.name) {
+                                Some(first) if first.value != constant.value => {
+                                    let reason = format!("Constant `{}` differs: `{}` on {} but `{}` on {}",
+                                                         constant.name, first.value, platforms[0],
+                                                         constant.value, platform);
+                                    self.warn(&enum_.name, reason);
+                                },
+                                Some(_) => {},
+                                None => enum_.constants.push(constant.clone()),
+                            }
+                        }
+                    }
+                }
+                for constant in &mut enum_.constants {
+                    let platforms = variants.iter()
+                        .filter(|(_, decl)| matches!(decl, Enum(other)
+                                                     if other.constants.iter().any(|other| other.name == constant.name)))
+                        .map(|(platform, _)| *platform)
+                        .collect::<Vec<_>>();
+                    add_note(&mut constant.cmt, self.availability_note(&platforms));
+                }
+                add_note(&mut enum_.cmt, note);
+            },
+            Const(const_) => {
+                self.check_same(&const_.name, variants);
+                add_note(&mut const_.cmt, note);
+            },
+            Version(version) => self.check_same(&version.name, variants),
+            StringArrayHelpers | LayoutHelpers => {},
+        }
+
+        merged
+    }
+
+    /// Report platforms which definitions differs from the first one
+    fn check_same(&mut self, name: &str, variants: &[(&str, &Decl)]) {
+        let (first_platform, first) = variants[0];
+        let others = variants[1..].iter()
+            .filter(|(_, decl)| decl != &first)
+            .map(|(platform, _)| *platform)
+            .collect::<Vec<_>>();
+
+        if !others.is_empty() {
+            self.warn(name, format!("Definition on {} differs from {}", others.join(", "), first_platform));
+        }
+    }
+
+    fn merge_library(&mut self, library: &mut LibraryDef, variants: &[(&str, &Decl)]) {
+        let libraries = variants.iter()
+            .filter_map(|(platform, decl)| match decl {
+                Decl::Library(library) => Some((*platform, library)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        // functions which is missing on some platforms is looked up conditionally
+        for (_, other) in &libraries[1..] {
+            union(&mut library.calls, &other.calls, |(name, _)| name.clone());
+        }
+
+        for (name, func) in &mut library.calls {
+            let mut platforms = Vec::new();
+
+            for (platform, other) in &libraries {
+                if let Some((_, other_func)) = other.calls.iter().find(|(other_name, _)| other_name == name) {
+                    if other_func.cffi != func.cffi {
+                        let reason = format!("Signature differs: `{}` on {} but `{}` on {}",
+                                             func.cffi, libraries[0].0, other_func.cffi, platform);
+                        self.warn(name, reason);
+                    }
+                    platforms.push(*platform);
+                }
+            }
+
+            if let Some(note) = self.availability_note(&platforms) {
+                func.notes.push(note);
+                func.platforms = platforms.iter().map(|platform| platform.to_string()).collect();
+            }
+        }
+
+        for (_, other) in &libraries[1..] {
+            union(&mut library.callbacks, &other.callbacks, |(name, _)| name.clone());
+            union(&mut library.wrappers, &other.wrappers, |(name, _, _)| name.clone());
+            union(&mut library.forwarders, &other.forwarders, |(name, _)| name.clone());
+            union(&mut library.asyncs, &other.asyncs, |(name, _, _)| name.clone());
+            union(&mut library.ports, &other.ports, |(name, _, _)| name.clone());
+            union(&mut library.abi_checks, &other.abi_checks, |check| check.clone());
+            union(&mut library.features, &other.features, |(name, _, _)| name.clone());
+            if library.init_dart_api.is_none() {
+                library.init_dart_api = other.init_dart_api.clone();
+            }
+        }
+
+        // functions which is bound on any platform is not skipped
+        let mut skipped = Vec::new();
+        for (_, other) in &libraries {
+            union(&mut skipped, &other.skipped, |(name, _)| name.clone());
+        }
+        skipped.retain(|(name, _)| !library.calls.iter().any(|(_, func)| func.name.as_ref() == Some(name)));
+        library.skipped = skipped;
+    }
+}
+
+/// Key which identifies declaration between platforms
+fn decl_key(decl: &Decl) -> String {
+    use Decl::*;
+
+    match decl {
+        Imports(_) => "imports".into(),
+        Struct(struct_) => format!("struct {}", struct_.name),
+        Enum(enum_) => format!("enum {}", enum_.name),
+        Const(const_) => format!("const {}", const_.name),
+        Version(version) => format!("version {}", version.name),
+        FunctionMacros(_) => "function macros".into(),
+        Opaques(_) => "opaques".into(),
+        FuncTypes(_) => "functypes".into(),
+        TypeAliases(_) => "type aliases".into(),
+        Library(library) => format!("library {}", library.name),
+        StringArrayHelpers => "string array helpers".into(),
+        LayoutHelpers => "layout helpers".into(),
+        ComplexHelpers(_) => "complex helpers".into(),
+    }
+}
+
+/// Append items which is missing in list
+fn union<T: Clone, K: PartialEq>(list: &mut Vec<T>, other: &[T], key: impl Fn(&T) -> K) {
+    for item in other {
+        if !list.iter().any(|list_item| key(list_item) == key(item)) {
+            list.push(item.clone());
+        }
+    }
+}
+
+/// Append note to documentation comment
+fn add_note(cmt: &mut Option<String>, note: Option<String>) {
+    if let Some(note) = note {
+        *cmt = Some(match cmt.take() {
+            Some(cmt) => format!("{}\n\n{}", cmt, note),
+            None => note,
+        });
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use clang::{Entity, EntityKind};
+
+/// Parsed C API
+///
+/// The model contains declarations which was bound by translator with C types as spelled in headers.
+#[derive(Debug, Clone, Default)]
+pub struct Model {
+    pub functions: Vec<Function>,
+    pub structs: Vec<Struct>,
+    pub enums: Vec<Enum>,
+    pub typedefs: Vec<Typedef>,
+}
+
+/// Function declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Function {
+    /// C name
+    pub name: String,
+    /// Dart name
+    pub dart_name: String,
+    /// Documentation comment
+    pub comment: Option<String>,
+    /// Deprecation message
+    pub deprecated: Option<String>,
+    /// Result type
+    pub result: String,
+    pub params: Vec<Param>,
+    /// Function takes variable number of arguments
+    pub variadic: bool,
+}
+
+/// Function parameter
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Param {
+    pub name: Option<String>,
+    pub type_: String,
+}
+
+/// Struct or union declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Struct {
+    /// C name
+    pub name: String,
+    /// Dart name
+    pub dart_name: String,
+    /// Documentation comment
+    pub comment: Option<String>,
+    /// Declared as union
+    pub union: bool,
+    pub fields: Vec<Field>,
+}
+
+/// Struct field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub type_: String,
+}
+
+/// Enum declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Enum {
+    /// C name
+    pub name: String,
+    /// Dart name
+    pub dart_name: String,
+    /// Documentation comment
+    pub comment: Option<String>,
+    pub variants: Vec<Variant>,
+}
+
+/// Enum constant
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Variant {
+    pub name: String,
+    /// Documentation comment
+    pub comment: Option<String>,
+    pub value: i64,
+}
+
+/// Type alias declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Typedef {
+    pub name: String,
+    /// Documentation comment
+    pub comment: Option<String>,
+    /// Underlying type
+    pub type_: String,
+}
+
+impl Model {
+    /// Find function by C name
+    pub fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.iter().find(|function| function.name == name)
+    }
+
+    /// Find struct by C name
+    pub fn struct_(&self, name: &str) -> Option<&Struct> {
+        self.structs.iter().find(|struct_| struct_.name == name)
+    }
+
+    /// Find enum by C name
+    pub fn enum_(&self, name: &str) -> Option<&Enum> {
+        self.enums.iter().find(|enum_| enum_.name == name)
+    }
+
+    /// Find typedef by name
+    pub fn typedef(&self, name: &str) -> Option<&Typedef> {
+        self.typedefs.iter().find(|typedef| typedef.name == name)
+    }
+}
+
+impl Function {
+    pub(crate) fn from_entity(dart_name: &str, deprecated: Option<String>, entity: Entity) -> Self {
+        Self {
+            name: entity.get_name().unwrap_or_default(),
+            dart_name: dart_name.into(),
+            comment: entity.get_comment(),
+            deprecated,
+            result: entity.get_result_type()
+                .map(|type_| type_.get_display_name())
+                .unwrap_or_else(|| "void".into()),
+            params: entity.get_arguments().unwrap_or_default().into_iter()
+                .map(|arg| Param {
+                    name: arg.get_name(),
+                    type_: arg.get_type().unwrap().get_display_name(),
+                })
+                .collect(),
+            variadic: entity.is_variadic(),
+        }
+    }
+}
+
+impl Struct {
+    pub(crate) fn from_entity(name: &str, dart_name: &str, comment: Option<String>, entity: Entity) -> Self {
+        Self {
+            name: name.into(),
+            dart_name: dart_name.into(),
+            comment,
+            union: entity.get_kind() == EntityKind::UnionDecl,
+            fields: entity.get_children().into_iter()
+                .filter(|field| field.get_kind() == EntityKind::FieldDecl)
+                .map(|field| Field {
+                    name: field.get_name().unwrap_or_default(),
+                    type_: field.get_type().unwrap().get_display_name(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Enum {
+    pub(crate) fn from_entity(name: &str, dart_name: &str, comment: Option<String>, entity: Entity) -> Self {
+        Self {
+            name: name.into(),
+            dart_name: dart_name.into(),
+            comment,
+            variants: entity.get_children().into_iter()
+                .filter(|variant| variant.get_kind() == EntityKind::EnumConstantDecl)
+                .map(|variant| Variant {
+                    name: variant.get_name().unwrap(),
+                    comment: variant.get_comment(),
+                    value: variant.get_enum_constant_value().unwrap().0,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Typedef {
+    pub(crate) fn from_entity(name: &str, entity: Entity) -> Self {
+        Self {
+            name: name.into(),
+            comment: entity.get_comment(),
+            type_: entity.get_typedef_underlying_type().unwrap().get_display_name(),
+        }
+    }
+}
+
+impl Display for Function {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} {}(", self.result, self.name)?;
+        for (index, param) in self.params.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", param.type_)?;
+            if let Some(name) = &param.name {
+                write!(f, " {}", name)?;
+            }
+        }
+        if self.variadic {
+            write!(f, "{}...", if self.params.is_empty() { "" } else { ", " })?;
+        }
+        write!(f, ")")
+    }
+}
+
+impl Display for Struct {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} {} {{", if self.union { "union" } else { "struct" }, self.name)?;
+        for field in &self.fields {
+            write!(f, " {} {};", field.type_, field.name)?;
+        }
+        write!(f, " }}")
+    }
+}
+
+impl Display for Enum {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "enum {} {{", self.name)?;
+        for (index, variant) in self.variants.iter().enumerate() {
+            write!(f, "{} {} = {}", if index > 0 { "," } else { "" }, variant.name, variant.value)?;
+        }
+        write!(f, " }}")
+    }
+}
+
+impl Display for Typedef {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "typedef {} {}", self.type_, self.name)
+    }
+}
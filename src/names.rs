@@ -0,0 +1,40 @@
+use std::collections::HashSet;
+
+/// Unique identifiers allocator
+#[derive(Debug, Clone, Default)]
+pub struct Names {
+    used: HashSet<String>,
+    /// Renamed names with replacements which is not reported yet
+    renamed: Vec<(String, String)>,
+}
+
+impl Names {
+    /// Allocate unique name using the given one as a base
+    ///
+    /// The numeric suffix will be added to name when it is already in use.
+    pub fn unique(&mut self, name: impl AsRef<str>) -> String {
+        let name = name.as_ref();
+        
+        if self.used.insert(name.into()) {
+            return name.into();
+        }
+
+        let mut num = 1;
+        
+        loop {
+            let alt_name = format!("{}_{}", name, num);
+            
+            if self.used.insert(alt_name.clone()) {
+                self.renamed.push((name.into(), alt_name.clone()));
+                return alt_name;
+            }
+            
+            num += 1;
+        }
+    }
+
+    /// Take renamed names to report it
+    pub fn take_renamed(&mut self) -> Vec<(String, String)> {
+        std::mem::take(&mut self.renamed)
+    }
+}
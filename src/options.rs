@@ -1,5 +1,11 @@
-use std::path::PathBuf;
+use std::{
+    env,
+    path::PathBuf,
+    collections::HashSet,
+    str::FromStr,
+};
 use regex::Regex;
+use crate::{Style, Progress};
 
 #[derive(Debug, Clone)]
 pub struct Options {
@@ -9,13 +15,513 @@ pub struct Options {
     /// Includes paths
     pub include_paths: Vec<PathBuf>,
     
+    /// Frameworks search paths
+    pub framework_paths: Vec<PathBuf>,
+
+    /// Explicit system includes paths
+    pub isystem_paths: Vec<PathBuf>,
+
+    /// Target triple
+    pub target: Option<String>,
+
+    /// Target system root
+    pub sysroot: Option<PathBuf>,
+
+    /// Preprocessor definitions (like `NAME` or `NAME=VALUE`)
+    pub defines: Vec<String>,
+
+    /// Sets of preprocessor definitions to translate headers with
+    ///
+    /// The headers is parsed once per set and the results is merged, so the declarations
+    /// which is available only under some definitions is captured and documented.
+    pub define_sets: Vec<Vec<String>>,
+
+    /// Extra arguments of parser (passed after generated ones)
+    pub clang_args: Vec<String>,
+
+    /// Do not use standard system includes paths
+    pub no_std_includes: bool,
+    
     /// Detect system includes paths
     pub detect_isystem: bool,
+
+    /// Continue without system includes paths when detection fails
+    pub isystem_fallback: bool,
+
+    /// Compiler used to detect system includes paths
+    pub clang_path: Option<PathBuf>,
+
+    /// Parse headers in MSVC compatibility mode
+    pub msvc: bool,
+
+    /// Language standard of headers
+    ///
+    /// The GNU dialect is used by default because system headers uses extensions like `typeof` and `__extension__`.
+    pub c_standard: String,
+
+    /// Skip parsing of function bodies
+    ///
+    /// The bodies is not required to translate declarations so skipping it speeds up parsing.
+    pub skip_function_bodies: bool,
+
+    /// Continue parsing after fatal errors
+    pub keep_going: bool,
+
+    /// Progress reporting function
+    pub progress: Option<fn(&Progress)>,
     
     /// Name matching regexp
     pub names_match: Regex,
 
     /// Name replace pattern
     pub names_replace: String,
+
+    /// Function names matching regexp (overrides names match)
+    pub function_filter: Option<Regex>,
+
+    /// Functions to skip (function name, reason)
+    ///
+    /// The skipped functions is listed in library class as commented stubs with reasons.
+    pub skip_functions: Vec<(String, String)>,
+
+    /// Additional library classes (class name, function names pattern)
+    ///
+    /// The matched functions will be bound by additional class instead of main one.
+    /// The types is shared between all library classes.
+    pub libraries: Vec<(String, Regex)>,
+
+    /// Optional features (feature name, function names pattern)
+    ///
+    /// The matched functions will be bound by separate class which is instantiated
+    /// only when loaded library provides all its functions.
+    pub features: Vec<(String, Regex)>,
+
+    /// Sections of library members (section title, function names pattern)
+    ///
+    /// The functions of same section is emitted together under region comment.
+    pub groups: Vec<(String, Regex)>,
+
+    /// Group functions by banner comments of headers (like `/* ---- Audio ---- */`)
+    ///
+    /// The sections from config overrides banners.
+    pub group_banners: bool,
+
+    /// Struct and typedef names matching regexp
+    ///
+    /// When set the matched types will be translated even if no function refers to it.
+    pub type_filter: Option<Regex>,
+
+    /// Enum names matching regexp (overrides names match)
+    pub enum_filter: Option<Regex>,
+
+    /// Constants prefixes of enums (enum name pattern, prefix)
+    ///
+    /// The constants is prefixed by enum name (without `_t` suffix) by default.
+    pub enum_prefixes: Vec<(Regex, String)>,
+
+    /// Macro names matching regexp
+    ///
+    /// Macro constants will be translated only when it is set.
+    pub macro_filter: Option<Regex>,
+
+    /// Function-like macros to wrap by shim functions (pattern, signature)
+    ///
+    /// The signature is spelled like `int(const char*, int)`. The macros cannot be bound directly,
+    /// so the C shim functions which calls it will be generated (requires shim output).
+    pub macro_shims: Vec<(Regex, String)>,
+
+    /// Version macros to embed (field, macro)
+    ///
+    /// The values will be emitted as constants of `<class_name>Version` class.
+    pub version_macros: Vec<(String, String)>,
+
+    /// Structs which sizes should be checked at runtime
+    ///
+    /// The regexps matches C names of structs. The expected sizes is taken for the target.
+    pub abi_structs: Vec<Regex>,
+
+    /// Version function with version class field to check at runtime (function, field)
+    pub abi_version: Option<(String, String)>,
+
+    /// Emit `validateLayout()` functions for structs
+    ///
+    /// The expected sizes and offsets of fields is computed by clang for the target and asserted against
+    /// runtime layout of Dart structs, so the mismatches (like from packing or bit-fields) is caught in debug mode.
+    pub layout_checks: bool,
+
+    /// Translate code examples of functions comments to Dart usage snippets
+    ///
+    /// The `@code` and fenced blocks is translated by calls of bound functions, another lines is kept commented.
+    pub examples: bool,
+
+    /// Annotate functions which result should be used by `@useResult`
+    ///
+    /// The annotation requires `package:meta` dependency so it is off by default.
+    pub use_result: bool,
+
+    /// Header paths matching regexps
+    ///
+    /// Declarations from matched headers will never be translated directly.
+    pub exclude_headers: Vec<Regex>,
+
+    /// C shim source output path
+    ///
+    /// When set the exported wrappers for `static inline` functions will be generated.
+    pub shim_output: Option<PathBuf>,
+
+    /// Intermediate representation output path
+    ///
+    /// The saved IR (JSON) can be used to generate the same code without parsing headers.
+    pub ir_output: Option<PathBuf>,
+
+    /// Shim functions names prefix
+    pub shim_prefix: String,
+
+    /// Opaque types for `void*` params
+    ///
+    /// The regexps matches `function.param` (or `function.return` for result)
+    /// and the matched untyped pointers will be mapped to pointers to opaque types.
+    pub void_opaques: Vec<(Regex, String)>,
+
+    /// Records which should be treated as opaque types
+    ///
+    /// The regexps matches the names of structs or typedefs in addition to common system types
+    /// like `FILE` or `DIR`.
+    pub opaque_types: Vec<Regex>,
+
+    /// Do not treat common system types as opaque
+    pub no_system_opaques: bool,
+
+    /// Skip functions declared without prototypes instead of binding them without arguments
+    pub strict_prototypes: bool,
+
+    /// Minimum number of functions which should be bound
+    pub min_matches: usize,
+
+    /// Generated code formatting style
+    pub style: Style,
+
+    /// Shape of generated bindings
+    pub binding_shape: BindingShape,
+
+    /// Visibility of bound functions
+    ///
+    /// The private functions is accessed via public forwarding functions or wrappers.
+    pub member_visibility: MemberVisibility,
+
+    /// Order of functions, types and enum constants in output
+    pub member_order: MemberOrder,
+
+    /// Write completed top-level declarations to output during translation
+    ///
+    /// It reduces memory usage on huge headers.
+    pub streaming: bool,
+
+    /// Emit markers of hand-written regions
+    ///
+    /// The regions is placed at the end of library classes and the end of file.
+    pub manual_regions: bool,
+
+    /// Names of declarations which is already translated
+    ///
+    /// The declarations can be referenced but will not be emitted,
+    /// so only the new ones will be translated (extend mode).
+    pub known_symbols: HashSet<String>,
+
+    /// Library file which the output is part of
+    ///
+    /// The `part of` directive will be emitted instead of imports.
+    pub part_of: Option<String>,
+
+    /// Generate convenience wrappers and helpers
+    pub wrappers: bool,
+
+    /// Length param name pattern for buffer params
+    ///
+    /// The byte pointer param followed by integer param which name matches
+    /// will be wrapped as `Uint8List`.
+    pub buffer_length_match: Regex,
+
+    /// Functions which returns owned strings with functions to free it
+    ///
+    /// The regexps matches function names. The returned strings will be converted and freed by wrappers.
+    /// The native free functions (like `sqlite3_free`) is called via its bindings, the Dart ones should be qualified
+    /// (like `malloc.free`).
+    pub owned_strings: Vec<(Regex, String)>,
+
+    /// Optional trailing params of wrappers (`function.param` pattern, default value)
+    ///
+    /// The matched params will be optional named params. The pointers without default value
+    /// is nullable and `nullptr` will be passed instead of null.
+    pub optional_params: Vec<(Regex, Option<String>)>,
+
+    /// Pointer params which accepts NULL (`function.param` patterns)
+    ///
+    /// The nullable pointers is documented and wrappers accepts null instead of `nullptr`.
+    pub nullable_params: Vec<Regex>,
+
+    /// Extra libraries to import (like `package:meta/meta.dart`)
+    ///
+    /// The imports is ignored when output is part of other library.
+    pub imports: Vec<String>,
+
+    /// Analyzer lints to ignore in generated file (like `non_constant_identifier_names`)
+    pub ignore_lints: Vec<String>,
+
+    /// Generate null-safe code (with `external` struct fields)
+    ///
+    /// The legacy code is opted out of null safety using language version comment.
+    pub null_safety: bool,
+
+    /// Generate extensions on struct pointers with field accessors
+    pub pointer_extensions: bool,
+
+    /// Generate extensions with `debugString()` dump of struct fields
+    pub debug_strings: bool,
+
+    /// Minimum number of uses of nested pointer type in signatures to alias it by typedef (0 to disable)
+    ///
+    /// The aliases like `typedef FooPtrPtr = Pointer<Pointer<foo>>;` requires null-safe code.
+    pub pointer_aliases: usize,
+
+    /// Bind complex numbers (`_Complex float` and `_Complex double`) as structs with real and imaginary parts
+    ///
+    /// The functions which uses complex numbers is skipped otherwise.
+    pub complex_structs: bool,
+
+    /// Bind vector (SIMD) types as structs of bytes with same size
+    ///
+    /// The vectors is passed in vector registers, so functions which takes or returns it by value is skipped anyway.
+    /// The structs with vector fields is bound as opaque otherwise.
+    pub vector_structs: bool,
+
+    /// Function name match pattern for asynchronous wrappers
+    ///
+    /// The matched functions will get `Future`-returning wrappers which runs call in separate isolate.
+    pub async_filter: Option<Regex>,
+
+    /// Callback params which should be delivered via native ports
+    ///
+    /// The regexps matches `function.param`. The C shim functions which posts callback arguments
+    /// to Dart port will be generated, so it requires shim output and `dart_api_dl.h` to compile.
+    pub port_callbacks: Vec<Regex>,
+
+    /// Function name patterns of not thread-safe functions
+    pub not_thread_safe: Vec<Regex>,
+
+    /// Function name patterns of functions which must be called on the main thread
+    pub main_thread: Vec<Regex>,
+
+    /// Generate runtime assertions for main thread functions in wrappers
+    pub thread_asserts: bool,
+
+    /// Native types for typedefs (name, type)
+    ///
+    /// Overrides the built-in mapping of system typedefs like `time_t` or `size_t`.
+    pub typedef_types: Vec<(String, String)>,
 }
 
+/// Shape of generated bindings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BindingShape {
+    /// Library class which looks up functions in constructor
+    #[default]
+    Class,
+    /// Top-level `late final` functions which is looked up by `init` function
+    TopLevel,
+    /// Top-level external functions with `@Native` annotations
+    ///
+    /// The `init` function is required only for callbacks, native ports and asynchronous functions.
+    NativeAnnotations,
+    /// Mixin which lazily looks up functions in library provided by getter
+    ///
+    /// The several mixins can be composed into single facade class.
+    Mixin,
+}
+
+impl BindingShape {
+    /// Bindings is declared at top level instead of class members
+    pub fn is_top_level(&self) -> bool {
+        matches!(self, BindingShape::TopLevel | BindingShape::NativeAnnotations)
+    }
+
+    /// Name of shape as accepted by parser
+    pub fn name(&self) -> &'static str {
+        match self {
+            BindingShape::Class => "class",
+            BindingShape::TopLevel => "top-level",
+            BindingShape::NativeAnnotations => "native",
+            BindingShape::Mixin => "mixin",
+        }
+    }
+}
+
+impl FromStr for BindingShape {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(match src {
+            "class" => BindingShape::Class,
+            "top-level" => BindingShape::TopLevel,
+            "native" => BindingShape::NativeAnnotations,
+            "mixin" => BindingShape::Mixin,
+            _ => return Err(format!("Unknown binding shape: `{}` (expected one of: class, top-level, native, mixin)", src)),
+        })
+    }
+}
+
+/// Visibility of bound functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemberVisibility {
+    /// Functions is accessible directly
+    #[default]
+    Public,
+    /// Functions is private (prefixed by `_`)
+    Private,
+}
+
+impl FromStr for MemberVisibility {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(match src {
+            "public" => MemberVisibility::Public,
+            "private" => MemberVisibility::Private,
+            _ => return Err(format!("Unknown member visibility: `{}` (expected public or private)", src)),
+        })
+    }
+}
+
+/// Order of generated members
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MemberOrder {
+    /// Order of declarations in headers
+    Source,
+    /// Sorted by names
+    Alphabetical,
+    /// Functions is grouped by sections and other members is in order of declarations
+    #[default]
+    Grouped,
+}
+
+impl FromStr for MemberOrder {
+    type Err = String;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        Ok(match src {
+            "source" => MemberOrder::Source,
+            "alphabetical" => MemberOrder::Alphabetical,
+            "grouped" => MemberOrder::Grouped,
+            _ => return Err(format!("Unknown member order: `{}` (expected one of: source, alphabetical, grouped)", src)),
+        })
+    }
+}
+
+impl Options {
+    /// Make options with defaults for library class
+    pub fn new(class_name: impl Into<String>) -> Self {
+        let class_name = class_name.into();
+        let shim_prefix = format!("{}_shim_", class_name.to_lowercase());
+        
+        Self {
+            class_name,
+            include_paths: Vec::new(),
+            framework_paths: Vec::new(),
+            isystem_paths: Vec::new(),
+            target: None,
+            sysroot: None,
+            defines: Vec::new(),
+            define_sets: Vec::new(),
+            clang_args: Vec::new(),
+            no_std_includes: false,
+            detect_isystem: true,
+            isystem_fallback: false,
+            clang_path: None,
+            msvc: false,
+            c_standard: "gnu17".into(),
+            skip_function_bodies: true,
+            keep_going: false,
+            progress: None,
+            names_match: Regex::new(".*").unwrap(),
+            names_replace: "$0".into(),
+            function_filter: None,
+            skip_functions: Vec::new(),
+            libraries: Vec::new(),
+            features: Vec::new(),
+            groups: Vec::new(),
+            group_banners: false,
+            type_filter: None,
+            enum_filter: None,
+            enum_prefixes: Vec::new(),
+            macro_filter: None,
+            macro_shims: Vec::new(),
+            version_macros: Vec::new(),
+            abi_structs: Vec::new(),
+            abi_version: None,
+            layout_checks: false,
+            examples: false,
+            use_result: false,
+            exclude_headers: Vec::new(),
+            shim_output: None,
+            ir_output: None,
+            shim_prefix,
+            void_opaques: Vec::new(),
+            opaque_types: Vec::new(),
+            no_system_opaques: false,
+            strict_prototypes: false,
+            min_matches: 1,
+            style: Style::default(),
+            binding_shape: BindingShape::default(),
+            member_visibility: MemberVisibility::default(),
+            member_order: MemberOrder::default(),
+            streaming: false,
+            manual_regions: false,
+            known_symbols: HashSet::new(),
+            part_of: None,
+            wrappers: false,
+            buffer_length_match: Regex::new("^(n|len|length|size)$|_(len|length|size)$").unwrap(),
+            owned_strings: Vec::new(),
+            optional_params: Vec::new(),
+            nullable_params: Vec::new(),
+            imports: Vec::new(),
+            ignore_lints: Vec::new(),
+            null_safety: true,
+            pointer_extensions: false,
+            pointer_aliases: 0,
+            complex_structs: false,
+            vector_structs: false,
+            debug_strings: false,
+            async_filter: None,
+            port_callbacks: Vec::new(),
+            not_thread_safe: Vec::new(),
+            main_thread: Vec::new(),
+            thread_asserts: false,
+            typedef_types: Vec::new(),
+        }
+    }
+
+    /// Merge parse configuration from environment variables
+    ///
+    /// The `C4DART_INCLUDE_PATHS` is list of paths separated like in `PATH`, the `C4DART_DEFINES`
+    /// and `C4DART_CLANG_ARGS` is whitespace-separated lists. The include paths is searched after
+    /// configured ones and the definitions and arguments is passed before configured ones to be overridden.
+    pub fn merge_env(&mut self) {
+        if let Some(paths) = env::var_os("C4DART_INCLUDE_PATHS") {
+            self.include_paths.extend(env::split_paths(&paths)
+                                      .filter(|path| !path.as_os_str().is_empty()));
+        }
+
+        if let Ok(defines) = env::var("C4DART_DEFINES") {
+            self.defines.splice(0..0, defines.split_whitespace().map(String::from));
+        }
+
+        if let Ok(args) = env::var("C4DART_CLANG_ARGS") {
+            self.clang_args.splice(0..0, args.split_whitespace().map(String::from));
+        }
+    }
+
+    /// Macros definitions is required
+    pub(crate) fn parse_macros(&self) -> bool {
+        self.macro_filter.is_some() || !self.macro_shims.is_empty() || !self.version_macros.is_empty()
+    }
+}
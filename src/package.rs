@@ -0,0 +1,157 @@
+use std::{
+    path::{Path, PathBuf},
+    fs::{read_to_string, write, create_dir_all},
+    collections::BTreeSet,
+};
+use crate::{Result, Decl, BindingShape, is_generated, generated_header};
+
+/// Requirements of generated bindings to Dart package
+#[derive(Debug, Clone, PartialEq)]
+pub struct Requirements {
+    /// Minimal version of Dart SDK (major and minor)
+    pub sdk: (u32, u32),
+    /// Names of imported packages (except `ffi`)
+    pub packages: BTreeSet<String>,
+    /// Bindings is opted out of null safety
+    pub legacy: bool,
+}
+
+impl Default for Requirements {
+    fn default() -> Self {
+        // the ffi package with allocators requires null-safe SDK even for opted out code
+        Self {
+            sdk: (2, 12),
+            packages: BTreeSet::new(),
+            legacy: false,
+        }
+    }
+}
+
+impl Requirements {
+    /// Collect requirements of declarations
+    pub(crate) fn from_decls(decls: &[Decl]) -> Self {
+        let mut requirements = Self::default();
+
+        for decl in decls {
+            match decl {
+                Decl::Imports(imports) => {
+                    // the legacy code is opted out by language version
+                    requirements.legacy |= imports.language_version.is_some();
+                    requirements.packages.extend(imports.libraries.iter()
+                                                 .filter_map(|uri| uri.strip_prefix("package:")?.split('/').next())
+                                                 .filter(|name| *name != "ffi")
+                                                 .map(String::from));
+                }
+                // the non-function typedefs is available since Dart 2.13
+                Decl::TypeAliases(aliases) if !aliases.is_empty() => requirements.require_sdk((2, 13)),
+                Decl::Library(library) => {
+                    if library.shape == BindingShape::NativeAnnotations {
+                        requirements.require_sdk((3, 0));
+                    }
+                    // the asynchronous wrappers uses `Isolate.run`
+                    if !library.asyncs.is_empty() {
+                        requirements.require_sdk((2, 19));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        requirements
+    }
+
+    /// Add requirements of other declarations
+    pub(crate) fn merge(&mut self, other: &Self) {
+        self.require_sdk(other.sdk);
+        self.packages.extend(other.packages.iter().cloned());
+        self.legacy |= other.legacy;
+    }
+
+    fn require_sdk(&mut self, version: (u32, u32)) {
+        self.sdk = self.sdk.max(version);
+    }
+}
+
+/// Get Dart package name for library class (`LibFoo` becomes `lib_foo`)
+pub fn package_name(class_name: &str) -> String {
+    let mut name = String::new();
+    let mut prev_lower = false;
+
+    for chr in class_name.chars() {
+        if chr.is_ascii_alphanumeric() {
+            if chr.is_ascii_uppercase() && prev_lower {
+                name.push('_');
+            }
+            prev_lower = chr.is_ascii_lowercase() || chr.is_ascii_digit();
+            name.push(chr.to_ascii_lowercase());
+        } else {
+            if !name.is_empty() && !name.ends_with('_') {
+                name.push('_');
+            }
+            prev_lower = false;
+        }
+    }
+
+    let name = name.trim_end_matches('_');
+
+    // the package name should be valid identifier
+    if name.starts_with(|chr: char| chr.is_ascii_digit()) {
+        format!("lib_{}", name)
+    } else if name.is_empty() {
+        "bindings".into()
+    } else {
+        name.into()
+    }
+}
+
+/// Get path of bindings source in package directory
+pub fn package_bindings_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join("lib").join("src").join(format!("{}_bindings.dart", name))
+}
+
+/// Write pubspec and library file which exports bindings of package
+///
+/// The existing pubspec is kept as is because it may be edited to publish package.
+/// The library file is overwritten only when it was generated.
+pub fn write_package(dir: &Path, name: &str, requirements: &Requirements) -> Result<()> {
+    let lib_dir = dir.join("lib");
+    create_dir_all(&lib_dir)?;
+
+    let pubspec = dir.join("pubspec.yaml");
+    if !pubspec.exists() {
+        // the code which is opted out of null safety is not supported by Dart 3
+        let sdk = format!(">={}.{}.0 <{}.0.0", requirements.sdk.0, requirements.sdk.1,
+                          if requirements.legacy { 3 } else { 4 });
+        let dependencies = requirements.packages.iter()
+            .filter(|package| *package != name)
+            .map(|package| match package.as_str() {
+                "meta" => "\n  meta: '>=1.3.0 <2.0.0'".to_string(),
+                _ => format!("\n  {}: any", package),
+            })
+            .collect::<String>();
+        write(&pubspec, format!("\
+name: {name}
+description: Dart FFI bindings to {name} library generated using {program}.
+version: 0.1.0
+
+environment:
+  sdk: '{sdk}'
+
+dependencies:
+  ffi: '>=1.0.0 <3.0.0'{dependencies}
+",
+                                name = name,
+                                program = env!("CARGO_PKG_NAME"),
+                                sdk = sdk,
+                                dependencies = dependencies))?;
+    }
+
+    let library = lib_dir.join(format!("{}.dart", name));
+    if !library.exists() || read_to_string(&library).map(|src| is_generated(&src))? {
+        write(&library, format!("{header}\n\nexport 'src/{name}_bindings.dart';\n",
+                                header = generated_header(),
+                                name = name))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use clang::Entity;
+use regex::Regex;
+use log::*;
+
+/// Comment pragmas of declaration
+///
+/// The pragmas looks like `/* c4dart: owns-return, borrows(arg), nullable(arg) */` and can be placed
+/// in documentation comment, right before declaration or inside it.
+#[derive(Debug, Clone, Default)]
+pub struct Pragmas {
+    /// The returned value is owned by caller (with optional Dart function to free it)
+    ///
+    /// The wrappers frees owned strings and buffers after conversion, another values is not attached to finalizers
+    /// so it should be freed explicitly.
+    pub owns_return: Option<Option<String>>,
+    /// The params which is borrowed by callee after call
+    pub borrows: Vec<String>,
+    /// The pointer params which accepts NULL
+    pub nullable: Vec<String>,
+}
+
+impl Pragmas {
+    /// Collect pragmas of entity
+    pub fn from_entity(entity: Entity) -> Self {
+        let mut pragmas = Self::default();
+
+        for text in entity.get_comment().into_iter().chain(declaration_text(entity)) {
+            pragmas.parse(&text);
+        }
+
+        pragmas
+    }
+
+    fn parse(&mut self, text: &str) {
+        for (index, _) in text.match_indices("c4dart:") {
+            let text = &text[index + "c4dart:".len()..];
+            let text = &text[..text.find(&['\n', '*'][..]).unwrap_or(text.len())];
+
+            for pragma in split_pragmas(text) {
+                let (name, arg) = if let Some(start) = pragma.find('(') {
+                    (pragma[..start].trim(),
+                     Some(pragma[start + 1..].trim_end_matches(')').trim()))
+                } else {
+                    (pragma, None)
+                };
+
+                match name {
+                    "owns-return" => self.owns_return = Some(arg.map(String::from)),
+                    "borrows" => if let Some(arg) = arg {
+                        self.borrows.extend(arg.split(',').map(|arg| arg.trim().into()));
+                    },
+                    "nullable" => if let Some(arg) = arg {
+                        self.nullable.extend(arg.split(',').map(|arg| arg.trim().into()));
+                    },
+                    _ => warn!("Unknown pragma `{}`", name),
+                }
+            }
+        }
+    }
+
+    /// Check that param is borrowed
+    pub fn is_borrowed(&self, name: &str) -> bool {
+        self.borrows.iter().any(|arg| arg == name)
+    }
+
+    /// Check that param accepts NULL
+    pub fn is_nullable(&self, name: &str) -> bool {
+        self.nullable.iter().any(|arg| arg == name)
+    }
+
+    /// Documentation notes about ownership
+    pub fn notes(&self) -> Vec<String> {
+        self.owns_return.iter()
+            .map(|free| format!("The returned value is owned by caller and should be freed using `{}`.",
+                                free.as_deref().unwrap_or("malloc.free")))
+            .chain(self.borrows.iter()
+                   .map(|arg| format!("The `{}` is borrowed and should outlive its usage by library.", arg)))
+            .collect()
+    }
+}
+
+/// Split pragmas list by commas outside of parentheses
+fn split_pragmas(text: &str) -> Vec<&str> {
+    let mut pragmas = Vec::new();
+    let mut level = 0;
+    let mut start = 0;
+
+    for (index, chr) in text.char_indices() {
+        match chr {
+            '(' => level += 1,
+            ')' => level -= 1,
+            ',' if level == 0 => {
+                pragmas.push(text[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    pragmas.push(text[start..].trim());
+
+    pragmas.into_iter().filter(|pragma| !pragma.is_empty()).collect()
+}
+
+/// Get source text of declaration including the comment right before it
+fn declaration_text(entity: Entity) -> Option<String> {
+    let range = entity.get_range()?;
+    let start = range.get_start().get_file_location();
+    let end = range.get_end().get_file_location();
+    let contents = start.file?.get_contents()?;
+
+    let start = start.offset as usize;
+    let end = (end.offset as usize).min(contents.len());
+
+    let prefix = contents.get(..start)?.trim_end();
+    let start = if prefix.ends_with("*/") {
+        prefix.rfind("/*").unwrap_or(start)
+    } else {
+        start
+    };
+
+    contents.get(start..end).map(String::from)
+}
+
+/// Find banner comments of header with offsets
+///
+/// The banner is single-line comment which title is surrounded by runs of `-`, `=`, `*`, `#` or `─`
+/// (like `/* ---- Audio ---- */` or `// ==== Audio ====`).
+pub fn banner_comments(contents: &str) -> Vec<(usize, String)> {
+    let regex = Regex::new(r"(?m)^[ \t]*(?://|/\*)[ \t]*[-=*#─]{3,}[ \t]*([^-=*#─\n]+?)[ \t]*[-=*#─]{3,}[ \t]*(?:\*/)?[ \t]*$").unwrap();
+
+    regex.captures_iter(contents)
+        .map(|captures| (captures.get(0).unwrap().start(), captures[1].into()))
+        .collect()
+}
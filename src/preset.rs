@@ -0,0 +1,82 @@
+use regex::Regex;
+use crate::{Options, Result};
+
+/// Built-in options preset for popular library
+struct Preset {
+    name: &'static str,
+    function_filter: &'static str,
+    enum_filter: Option<&'static str>,
+    macro_filter: Option<&'static str>,
+    opaque_types: &'static [&'static str],
+    /// Opaque types for `void*` params (pattern, type)
+    void_opaques: &'static [(&'static str, &'static str)],
+    /// Functions which returns owned strings (pattern, free function)
+    owned_strings: &'static [(&'static str, &'static str)],
+}
+
+const PRESETS: &[Preset] = &[
+    Preset {
+        name: "sqlite3",
+        function_filter: "^sqlite3_",
+        enum_filter: None,
+        macro_filter: Some("^SQLITE_"),
+        opaque_types: &[
+            "^sqlite3(_(stmt|blob|backup|context|value|mutex|str))?$",
+        ],
+        void_opaques: &[],
+        owned_strings: &[
+            ("^sqlite3_(expanded_sql|str_finish)$", "sqlite3_free"),
+        ],
+    },
+    Preset {
+        name: "libcurl",
+        function_filter: "^curl_",
+        enum_filter: Some("^(CURL|curl)"),
+        macro_filter: Some("^(CURL_|LIBCURL_VERSION)"),
+        opaque_types: &[],
+        void_opaques: &[
+            ("^curl_easy_(init|duphandle)\\.return$|^curl_easy_[a-z_]+\\.(curl|handle|data)$", "CURL"),
+            ("^curl_multi_init\\.return$|^curl_multi_[a-z_]+\\.multi_handle$", "CURLM"),
+            ("^curl_share_init\\.return$|^curl_share_[a-z_]+\\.share$", "CURLSH"),
+        ],
+        owned_strings: &[
+            ("^curl_(easy_)?(escape|unescape)$|^curl_getenv$", "curl_free"),
+        ],
+    },
+];
+
+/// Get names of built-in presets
+pub fn preset_names() -> Vec<&'static str> {
+    PRESETS.iter().map(|preset| preset.name).collect()
+}
+
+impl Options {
+    /// Apply built-in preset for popular library
+    ///
+    /// The preset fills filters which is not set and adds its patterns after user-defined ones,
+    /// so the user options takes precedence.
+    pub fn preset(&mut self, name: &str) -> Result<()> {
+        let preset = PRESETS.iter().find(|preset| preset.name == name)
+            .ok_or_else(|| format!("Unknown preset `{}` (available: {})", name, preset_names().join(", ")))?;
+
+        let regex = |pattern: &str| Regex::new(pattern).unwrap();
+
+        if self.function_filter.is_none() {
+            self.function_filter = Some(regex(preset.function_filter));
+        }
+        if self.enum_filter.is_none() {
+            self.enum_filter = preset.enum_filter.map(regex);
+        }
+        if self.macro_filter.is_none() {
+            self.macro_filter = preset.macro_filter.map(regex);
+        }
+
+        self.opaque_types.extend(preset.opaque_types.iter().map(|pattern| regex(pattern)));
+        self.void_opaques.extend(preset.void_opaques.iter()
+                                 .map(|(pattern, type_name)| (regex(pattern), type_name.to_string())));
+        self.owned_strings.extend(preset.owned_strings.iter()
+                                  .map(|(pattern, free)| (regex(pattern), free.to_string())));
+
+        Ok(())
+    }
+}
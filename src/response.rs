@@ -0,0 +1,88 @@
+use std::{
+    ffi::OsString,
+    fs::read_to_string,
+};
+use crate::Result;
+
+/// Maximum depth of nested response files
+const MAX_DEPTH: usize = 16;
+
+/// Expand response files (`@args.txt`) in command-line arguments
+///
+/// The arguments of files is separated by whitespaces and can be quoted. The program name is kept as is.
+/// The nested response files is expanded too.
+pub fn expand_response_files(args: impl IntoIterator<Item = OsString>) -> Result<Vec<OsString>> {
+    let mut args = args.into_iter();
+    let mut expanded = args.next().into_iter().collect();
+
+    for arg in args {
+        expand_arg(arg, &mut expanded, 0)?;
+    }
+
+    Ok(expanded)
+}
+
+fn expand_arg(arg: OsString, expanded: &mut Vec<OsString>, depth: usize) -> Result<()> {
+    let path = match arg.to_str().and_then(|arg| arg.strip_prefix('@')) {
+        Some(path) if !path.is_empty() => path,
+        _ => {
+            expanded.push(arg);
+            return Ok(());
+        },
+    };
+
+    if depth >= MAX_DEPTH {
+        return Err(format!("Too deep nesting of response files at `{}`", path).into());
+    }
+
+    let src = read_to_string(path)
+        .map_err(|error| format!("Unable to read response file `{}`: {}", path, error))?;
+
+    for arg in split_response_file(&src) {
+        expand_arg(arg.into(), expanded, depth + 1)?;
+    }
+
+    Ok(())
+}
+
+/// Split contents of response file to arguments
+///
+/// The single or double quotes groups whitespaces. The backslash escapes quotes, whitespaces
+/// and backslash itself only, so Windows paths can be written as is.
+pub fn split_response_file(src: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    // the empty quoted argument is argument too
+    let mut started = false;
+    let mut quote = None;
+    let mut chars = src.chars().peekable();
+
+    while let Some(chr) = chars.next() {
+        match (quote, chr) {
+            (Some(quote_chr), _) if chr == quote_chr => quote = None,
+            (None, '"') | (None, '\'') => {
+                quote = Some(chr);
+                started = true;
+            },
+            (_, '\\') if matches!(chars.peek(), Some(&next) if next == '"' || next == '\'' ||
+                                  next == '\\' || next.is_whitespace()) => {
+                arg.extend(chars.next());
+                started = true;
+            },
+            (None, _) if chr.is_whitespace() => if started {
+                args.push(std::mem::take(&mut arg));
+                started = false;
+            },
+            _ => {
+                arg.push(chr);
+                started = true;
+            },
+        }
+    }
+
+    if started {
+        args.push(arg);
+    }
+
+    args
+}
@@ -13,6 +13,11 @@ pub type Result<T> = StdResult<T, Error>;
 pub enum Error {
     Gen(String),
     Io(IoError),
+    Tool(String),
+    /// Headers cannot be parsed
+    Parse(String),
+    /// Too few functions matched by patterns
+    Unmatched(String),
 }
 
 impl StdError for Error {}
@@ -24,6 +29,9 @@ impl Display for Error {
         match self {
             Gen(e) => write!(f, "Generic error: {}", e),
             Io(e) => write!(f, "I/O error: {}", e),
+            Tool(e) => write!(f, "Tool error: {}", e),
+            Parse(e) => write!(f, "Parse error: {}", e),
+            Unmatched(e) => write!(f, "Match error: {}", e),
         }
     }
 }
@@ -0,0 +1,104 @@
+use std::{
+    fmt::{Display, Formatter, Result as FmtResult},
+    time::{Duration, Instant},
+};
+use crate::Decl;
+
+/// Translation statistics
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Stats {
+    /// Number of bound functions
+    pub functions: usize,
+    /// Number of translated structs
+    pub structs: usize,
+    /// Number of translated enums
+    pub enums: usize,
+    /// Number of translated macro constants
+    pub macros: usize,
+    /// Number of function-like macros which cannot be bound
+    pub function_macros: usize,
+    /// Number of skipped functions
+    pub skipped: usize,
+}
+
+impl Stats {
+    /// Count translated declarations
+    pub(crate) fn from_decls(decls: &[Decl]) -> Self {
+        let mut stats = Self::default();
+
+        for decl in decls {
+            match decl {
+                Decl::Struct(_) => stats.structs += 1,
+                Decl::Enum(_) => stats.enums += 1,
+                Decl::Const(_) => stats.macros += 1,
+                Decl::FunctionMacros(list) => stats.function_macros += list.len(),
+                Decl::Library(library) => {
+                    stats.functions += library.calls.len();
+                    stats.skipped += library.skipped.len();
+                },
+                _ => {},
+            }
+        }
+
+        stats
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{} functions, {} structs, {} enums, {} macros, {} function-like macros, {} skipped",
+               self.functions, self.structs, self.enums, self.macros, self.function_macros, self.skipped)
+    }
+}
+
+/// Translation progress
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    /// Current phase name
+    pub phase: &'static str,
+    /// Number of processed entities
+    pub done: usize,
+    /// Total number of entities
+    pub total: usize,
+}
+
+/// Durations of translation phases
+#[derive(Debug, Clone, Default)]
+pub struct Timings {
+    pub phases: Vec<(&'static str, Duration)>,
+}
+
+impl Timings {
+    /// Add phase which started at given instant
+    ///
+    /// The instant will be reset to start next phase.
+    pub fn add(&mut self, phase: &'static str, start: &mut Instant) {
+        let now = Instant::now();
+        self.phases.push((phase, now - *start));
+        *start = now;
+    }
+
+    /// Add durations of other phases to same ones
+    pub fn merge(&mut self, other: &Timings) {
+        for (phase, duration) in &other.phases {
+            match self.phases.iter_mut().find(|(name, _)| name == phase) {
+                Some((_, total)) => *total += *duration,
+                None => self.phases.push((phase, *duration)),
+            }
+        }
+    }
+
+    /// Total duration of all phases
+    pub fn total(&self) -> Duration {
+        self.phases.iter().map(|(_, duration)| *duration).sum()
+    }
+}
+
+impl Display for Timings {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        for (phase, duration) in &self.phases {
+            writeln!(f, "{:>24}: {:>10.3} ms", phase, duration.as_secs_f64() * 1e3)?;
+        }
+        write!(f, "{:>24}: {:>10.3} ms", "total", self.total().as_secs_f64() * 1e3)
+    }
+}
@@ -0,0 +1,36 @@
+use std::{
+    path::Path,
+    collections::HashSet,
+    fs::{read_to_string, write},
+    io::ErrorKind,
+};
+use crate::Result;
+
+/// Read names of translated declarations from symbol map sidecar
+///
+/// The missing sidecar is treated as empty map.
+pub fn read_symbol_map(path: &Path) -> Result<HashSet<String>> {
+    match read_to_string(path) {
+        Ok(src) => Ok(src.lines()
+                      .map(|line| line.trim())
+                      .filter(|line| !line.is_empty())
+                      .map(|line| line.into())
+                      .collect()),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(HashSet::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Write names of translated declarations to symbol map sidecar
+pub fn write_symbol_map<'a>(path: &Path, symbols: impl IntoIterator<Item = &'a String>) -> Result<()> {
+    let mut symbols = symbols.into_iter().map(|name| name.as_str()).collect::<Vec<_>>();
+    symbols.sort_unstable();
+    symbols.dedup();
+
+    let mut src = symbols.join("\n");
+    src.push('\n');
+
+    write(path, src)?;
+
+    Ok(())
+}
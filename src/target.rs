@@ -0,0 +1,158 @@
+use std::{
+    fs::read_dir,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+};
+use crate::{Options, Result, Error};
+
+/// Android target architecture
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AndroidArch {
+    Arm,
+    Arm64,
+    X86,
+    X86_64,
+}
+
+impl AndroidArch {
+    /// Target triple without API level
+    pub fn triple(&self) -> &'static str {
+        use AndroidArch::*;
+
+        match self {
+            Arm => "armv7a-linux-androideabi",
+            Arm64 => "aarch64-linux-android",
+            X86 => "i686-linux-android",
+            X86_64 => "x86_64-linux-android",
+        }
+    }
+}
+
+impl FromStr for AndroidArch {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use AndroidArch::*;
+
+        Ok(match s {
+            "arm" | "armv7a" | "armeabi-v7a" => Arm,
+            "arm64" | "aarch64" | "arm64-v8a" => Arm64,
+            "x86" | "i686" => X86,
+            "x86_64" | "x64" => X86_64,
+            _ => return Err(format!("Unknown android arch: {} (expected one of: arm, arm64, x86, x86_64)", s)),
+        })
+    }
+}
+
+/// Apple platform SDK
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppleSdk {
+    MacOSX,
+    IPhoneOS,
+    IPhoneSimulator,
+}
+
+impl AppleSdk {
+    /// SDK name for xcrun
+    pub fn name(&self) -> &'static str {
+        use AppleSdk::*;
+
+        match self {
+            MacOSX => "macosx",
+            IPhoneOS => "iphoneos",
+            IPhoneSimulator => "iphonesimulator",
+        }
+    }
+
+    /// Target triple (the host target is used for macOS)
+    pub fn triple(&self) -> Option<&'static str> {
+        use AppleSdk::*;
+
+        match self {
+            MacOSX => None,
+            IPhoneOS => Some("arm64-apple-ios"),
+            IPhoneSimulator => Some(if cfg!(target_arch = "aarch64") {
+                "arm64-apple-ios-simulator"
+            } else {
+                "x86_64-apple-ios-simulator"
+            }),
+        }
+    }
+}
+
+impl FromStr for AppleSdk {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        use AppleSdk::*;
+
+        Ok(match s {
+            "macosx" | "macos" => MacOSX,
+            "iphoneos" | "ios" => IPhoneOS,
+            "iphonesimulator" => IPhoneSimulator,
+            _ => return Err(format!("Unknown apple sdk: {} (expected one of: macosx, iphoneos, iphonesimulator)", s)),
+        })
+    }
+}
+
+impl Options {
+    /// Configure target triple, sysroot and compiler using Android NDK
+    ///
+    /// The NDK r19 and newer with prebuilt LLVM toolchain layout is supported.
+    pub fn android_ndk(&mut self, ndk: &Path, api: u32, arch: AndroidArch) -> Result<()> {
+        let prebuilt = ndk.join("toolchains").join("llvm").join("prebuilt");
+
+        // there is only one host toolchain in NDK distribution
+        let toolchain = read_dir(&prebuilt)
+            .map_err(|error| Error::Tool(format!("Unable to find NDK toolchain in `{}`: {}",
+                                                 prebuilt.display(), error)))?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .find(|path| path.is_dir())
+            .ok_or_else(|| Error::Tool(format!("Missing NDK toolchain in `{}`", prebuilt.display())))?;
+
+        let sysroot = toolchain.join("sysroot");
+
+        if !sysroot.is_dir() {
+            return Err(Error::Tool(format!("Missing NDK sysroot `{}`", sysroot.display())));
+        }
+
+        self.target = Some(format!("{}{}", arch.triple(), api));
+        self.sysroot = Some(sysroot);
+
+        if self.clang_path.is_none() {
+            let clang = toolchain.join("bin").join(if cfg!(windows) { "clang.exe" } else { "clang" });
+            if clang.is_file() {
+                self.clang_path = Some(clang);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Configure target triple and sysroot using Apple SDK
+    ///
+    /// The SDK path will be found using `xcrun --show-sdk-path`.
+    pub fn apple_sdk(&mut self, sdk: AppleSdk) -> Result<()> {
+        let out = Command::new("xcrun")
+            .arg("--sdk").arg(sdk.name()).arg("--show-sdk-path")
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|error| Error::Tool(format!("Unable to run xcrun: {}. Install Xcode command line tools.", error)))?;
+
+        if !out.status.success() {
+            return Err(Error::Tool(format!("Unable to find {} SDK: {}", sdk.name(),
+                                           String::from_utf8_lossy(&out.stderr).trim())));
+        }
+
+        let sysroot = PathBuf::from(String::from_utf8_lossy(&out.stdout).trim());
+
+        if self.target.is_none() {
+            self.target = sdk.triple().map(String::from);
+        }
+        self.sysroot = Some(sysroot);
+
+        Ok(())
+    }
+}
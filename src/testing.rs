@@ -0,0 +1,57 @@
+use std::{
+    path::Path,
+    fs::{read_to_string, write},
+};
+use crate::{Options, Result, Report, translate};
+
+/// Translate C headers to Dart source in memory
+pub fn translate_to_string(options: Options, input: &Path) -> Result<(String, Report)> {
+    let mut output = Vec::new();
+    let report = translate(options, input, &mut output)?;
+    
+    Ok((String::from_utf8(output).map_err(|error| error.to_string())?, report))
+}
+
+/// Check that translated C headers matches expected Dart source
+///
+/// The expected source will be updated instead when `UPDATE_EXPECT` environment variable is set.
+pub fn check_golden(options: Options, input: &Path, expected: &Path) -> Result<()> {
+    let (actual, _) = translate_to_string(options, input)?;
+
+    if std::env::var_os("UPDATE_EXPECT").is_some() {
+        write(expected, &actual)?;
+        return Ok(());
+    }
+
+    let expected_src = read_to_string(expected)?;
+
+    if let Some((line, expected_line, actual_line)) = first_difference(&expected_src, &actual) {
+        return Err(format!("Output of `{input}` differs from `{expected}` at line {line}:\n\
+                            expected: {expected_line}\n  \
+                            actual: {actual_line}\n\
+                            Run with UPDATE_EXPECT=1 to update expected output.",
+                           input = input.display(),
+                           expected = expected.display(),
+                           line = line,
+                           expected_line = expected_line,
+                           actual_line = actual_line).into());
+    }
+
+    Ok(())
+}
+
+/// Find first different line of sources
+fn first_difference<'a>(expected: &'a str, actual: &'a str) -> Option<(usize, &'a str, &'a str)> {
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (None, None) => return None,
+            (expected_line, actual_line) if expected_line != actual_line =>
+                return Some((line, expected_line.unwrap_or("<end of file>"), actual_line.unwrap_or("<end of file>"))),
+            _ => line += 1,
+        }
+    }
+}
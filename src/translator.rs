@@ -1,45 +1,108 @@
 use std::borrow::Cow;
+use std::io::Write;
+use std::time::Instant;
+use std::panic::{self, catch_unwind, AssertUnwindSafe};
+use std::sync::Once;
+use std::cell::{Cell, RefCell};
 use std::collections::{HashSet, HashMap};
-use clang::{Entity, EntityKind, Type, TypeKind};
+use std::string::FromUtf8Error;
+use clang::{Entity, EntityKind, Type, TypeKind, Availability, CallingConvention, StorageClass, Visibility};
 use log::*;
-use crate::{Options, Coder};
+use regex::Regex;
+use crate::{Options, Result, Error, Stats, Progress, Timings, Report, Requirements, Diagnostic, Severity, Model, Function, Struct, Enum, Coder, Names, Decl, ImportsDef, FuncDef, StructDef, FieldDef, EnumDef, ConstDef, VersionDef, LibraryDef, Pragmas, WrapDef, AsyncDef, Json, ToJson, MemberVisibility, MemberOrder, thread_notes, nullable_params, nullable_notes, banner_comments, comment_examples, translate_example, free_statement};
 
-#[derive(Debug, Clone)]
-pub struct FuncDef {
-    name: Option<String>,
-    cmt: Option<String>,
-    cffi: String,
-    dart: String,
+/// Translated type names
+#[derive(Debug, Clone, Default)]
+pub struct TypeNames {
+    /// C type name to Dart type name
+    types: HashMap<String, String>,
+    /// Native function signature to Dart typedef name
+    functions: HashMap<String, String>,
+    /// Typedef name to native type name
+    typedefs: HashMap<String, String>,
+    /// Translated types by spelling, canonical spelling and FFI flag
+    cache: RefCell<HashMap<(String, String, bool), Cow<'static, str>>>,
+    /// Number of cache hits and misses
+    cache_stats: Cell<(usize, usize)>,
+}
+
+impl TypeNames {
+    /// Register translated record or enum type
+    ///
+    /// Cached translations is dropped because it may refer to unregistered type.
+    fn insert_type(&mut self, key: String, name: String) {
+        self.types.insert(key, name);
+        self.cache.get_mut().clear();
+    }
+
+    /// Register native function type
+    fn insert_function(&mut self, signature: String, name: String) {
+        self.functions.insert(signature, name);
+        self.cache.get_mut().clear();
+    }
 }
 
 impl FuncDef {
-    fn from_entity(typenames: &HashMap<String, String>, entity: Entity) -> Self {
+    /// Make function definition from declaration entity
+    ///
+    /// The opaques maps names of `void*` params (or `return` for result) to opaque types.
+    fn from_entity(typenames: &TypeNames, entity: Entity, opaques: &HashMap<String, String>) -> Self {
         let res = entity.get_result_type();
         let args = entity.get_arguments();
 
-        let cffi_res = res.map(|type_| translate_type(typenames, type_, true))
+        let res_opaque = opaques.get("return").map(|name| Cow::from(format!("Pointer<{}>", name)));
+        
+        let cffi_res = res_opaque.clone()
+            .or_else(|| res.map(|type_| translate_type(typenames, type_, true)))
             .unwrap_or("Void".into());
-        let dart_res = res.map(|type_| translate_type(typenames, type_, false))
+        let dart_res = res_opaque
+            .or_else(|| res.map(|type_| translate_type(typenames, type_, false)))
             .unwrap_or("void".into());
 
-        let cffi_args = args.as_ref().map(|args| translate_args(typenames, args.clone(), true))
+        let cffi_args = args.as_ref().map(|args| translate_args(typenames, args.clone(), true, opaques))
             .unwrap_or("".into());
-        let dart_args = args.map(|args| translate_args(typenames, args, false))
+        let arg_names = args.as_ref().map(|args| args.iter().enumerate()
+                                          .map(|(num, arg)| dart_arg_name(num, *arg))
+                                          .collect())
+            .unwrap_or_default();
+        let dart_args = args.map(|args| translate_args(typenames, args, false, opaques))
             .unwrap_or("".into());
+
+        let attrs = attribute_tokens(entity);
+        let has_attr = |names: &[&str]| attrs.iter().any(|attr| names.contains(&attr.as_str()));
+        let noreturn = has_attr(&["noreturn", "__noreturn__", "_Noreturn"]);
+        let use_result = has_attr(&["warn_unused_result", "__warn_unused_result__", "nodiscard"]);
         
         Self {
-            name: entity.get_name(),
+            name: symbol_name(entity).or_else(|| entity.get_name()),
             cmt: entity.get_comment(),
+            deprecated: deprecation(entity),
+            notes: entity.get_type().and_then(calling_convention_note).into_iter()
+                .chain(qualified_signature_note(entity))
+                .chain(entity.get_arguments().unwrap_or_default().into_iter().enumerate()
+                       .filter_map(|(num, arg)| array_note(&arg_name(num, arg), arg.get_type().unwrap())))
+                .chain(entity.get_arguments().unwrap_or_default().into_iter().enumerate()
+                       .filter_map(|(num, arg)| qualifier_note(&arg_name(num, arg), arg.get_type().unwrap())))
+                .chain(res.and_then(|type_| qualifier_note("result", type_)))
+                .chain(if noreturn { Some("This function never returns.".into()) } else { None })
+                .chain(if use_result { Some("The result of this function should be used.".into()) } else { None })
+                .collect(),
+            use_result,
             cffi: format!("{res} Function({args})",
                           res = cffi_res,
                           args = cffi_args),
             dart: format!("{res} Function({args})",
                           res = dart_res,
                           args = dart_args),
+            res: dart_res.into(),
+            params: dart_args,
+            args: arg_names,
+            platforms: Vec::new(),
+            group: None,
         }
     }
     
-    fn from_type<'a>(typenames: &HashMap<String, String>, type_: Type<'a>) -> Self {
+    fn from_type<'a>(typenames: &TypeNames, type_: Type<'a>) -> Self {
         let res = type_.get_result_type();
         let args = type_.get_argument_types();
 
@@ -50,147 +113,767 @@ impl FuncDef {
 
         let cffi_args = args.as_ref().map(|args| translate_types(typenames, args.clone(), true))
             .unwrap_or("".into());
+        let arg_names = args.as_ref().map(|args| (0..args.len()).map(|num| format!("arg{}", num)).collect())
+            .unwrap_or_default();
         let dart_args = args.map(|args| translate_types(typenames, args, false))
             .unwrap_or("".into());
         
         Self {
             name: None,
             cmt: None,
+            deprecated: None,
+            notes: calling_convention_note(type_).into_iter().collect(),
+            use_result: false,
             cffi: format!("{res} Function({args})",
                           res = cffi_res,
                           args = cffi_args),
             dart: format!("{res} Function({args})",
                           res = dart_res,
                           args = dart_args),
+            res: dart_res.into(),
+            params: dart_args,
+            args: arg_names,
+            platforms: Vec::new(),
+            group: None,
         }
     }
 }
 
+/// Declared type which definition is pending
+#[derive(Debug, Clone)]
+struct TypeDef<'tu> {
+    name: String,
+    xname: String,
+    entity: Entity<'tu>,
+    cmt: Option<String>,
+}
+
 #[derive(Debug, Clone)]
-pub struct Translator {
+pub struct Translator<'tu> {
     options: Options,
 
+    /// Keys of exported entities
     exported: HashSet<String>,
-    typenames: HashMap<String, String>,
+    typenames: TypeNames,
+    /// Parsed functions keys with indexes in calls and signatures
+    functions: HashMap<String, (usize, String)>,
+
+    /// Top-level identifiers
+    global_names: Names,
+    /// Class names of optional features
+    feature_classes: Vec<String>,
+    /// Library class members identifiers
+    member_names: Names,
     
     calls: Vec<(String, FuncDef)>,
-    callbacks: Vec<(String, FuncDef)>,
+    callbacks: Vec<(String, String)>,
+    /// Wrapper methods with names of raw functions
+    wrappers: Vec<(String, String, WrapDef)>,
+    /// Asynchronous wrapper methods with native function names
+    asyncs: Vec<(String, String, AsyncDef)>,
+    /// Public forwarding functions with names of private functions
+    forwarders: Vec<(String, String)>,
+    /// Callbacks delivered via native ports with shim function names and types
+    ports: Vec<(String, String, String)>,
+    functypes: Vec<(String, FuncDef)>,
+    /// Skipped functions with reasons
+    skipped: Vec<(String, String)>,
+    /// Names of translated macro constants
+    macros: Vec<String>,
+    /// Values tokens of object-like macros to evaluate expressions
+    macro_defs: HashMap<String, Vec<String>>,
+    /// Indexes of additional library classes by names of functions
+    call_libraries: HashMap<String, usize>,
+    /// Expected sizes of structs to check at runtime
+    abi_sizes: Vec<(String, usize)>,
+    /// Name of library version class
+    version_class: Option<String>,
+    /// Function-like macros signatures with shim function names
+    function_macros: Vec<(String, Option<String>)>,
+    /// Opaque types names
+    opaques: HashMap<String, String>,
+    /// Banner comments of headers by file paths
+    banners: HashMap<String, Vec<(usize, String)>>,
+    /// String arrays (`char**`) is used
+    string_arrays: bool,
+    /// Names of complex number structs
+    complex_types: Vec<String>,
+    /// Layout of some struct is checked at runtime
+    layout_checks: bool,
+    /// Declared types to define
+    pending: Vec<TypeDef<'tu>>,
+    stats: Stats,
+    diagnostics: Vec<Diagnostic>,
+    /// Parsed declarations
+    model: Model,
+    timings: Timings,
+    /// Translated top-level declarations which is not emitted yet
+    decls: Vec<Decl>,
+    /// Declarations which is already written to output (kept only for intermediate representation)
+    flushed: Vec<Decl>,
+    /// Requirements of declarations which is already written to output
+    requirements: Requirements,
     
     coder: Coder,
+    shim: Coder,
 }
 
-impl Translator {
+impl<'tu> Translator<'tu> {
     pub fn new(options: Options) -> Self {
+        let style = options.style.clone();
+        let typenames = TypeNames {
+            typedefs: options.typedef_types.iter().cloned().collect(),
+            ..TypeNames::default()
+        };
+        
         Self {
             options,
             exported: HashSet::default(),
-            typenames: HashMap::default(),
+            functions: HashMap::default(),
+            typenames,
+            global_names: Names::default(),
+            feature_classes: Vec::default(),
+            member_names: Names::default(),
             calls: Vec::default(),
             callbacks: Vec::default(),
-            coder: Coder::default(),
+            wrappers: Vec::default(),
+            asyncs: Vec::default(),
+            forwarders: Vec::default(),
+            ports: Vec::default(),
+            functypes: Vec::default(),
+            skipped: Vec::default(),
+            macros: Vec::default(),
+            macro_defs: HashMap::default(),
+            call_libraries: HashMap::default(),
+            abi_sizes: Vec::default(),
+            version_class: None,
+            function_macros: Vec::default(),
+            opaques: HashMap::default(),
+            banners: HashMap::default(),
+            string_arrays: false,
+            complex_types: Vec::default(),
+            layout_checks: false,
+            pending: Vec::default(),
+            stats: Stats::default(),
+            diagnostics: Vec::default(),
+            model: Model::default(),
+            timings: Timings::default(),
+            decls: Vec::default(),
+            flushed: Vec::default(),
+            requirements: Requirements::default(),
+            coder: Coder::with_style(style.clone()),
+            shim: Coder::with_style(style),
         }
     }
     
-    pub fn translate(&mut self, entity: Entity) {
+    pub fn translate(&mut self, entity: Entity<'tu>) -> Result<()> {
+        self.translate_to(entity, None)
+    }
+
+    /// Translate declarations writing completed code to output when it is given
+    ///
+    /// The remaining code (library class) will be kept in coder.
+    pub fn translate_to(&mut self, entity: Entity<'tu>, mut output: Option<&mut dyn Write>) -> Result<()> {
         use EntityKind::*;
         
-        self.coder.line("import 'dart:ffi';");
-        self.coder.line("");
+        self.global_names.unique(&self.options.class_name);
+        // the classes of extra libraries is top-level declarations too
+        for (class, _) in &self.options.libraries {
+            self.global_names.unique(class);
+        }
+        for (feature, _) in &self.options.features {
+            let class = format!("{}{}", camel_case(&self.options.class_name), camel_case(feature));
+            self.feature_classes.push(self.global_names.unique(class));
+        }
 
-        for entity in entity.get_children() {
-            if let Some(name) = entity.get_name() {
-                if self.match_name(&name) {
-                    match entity.get_kind() {
-                        FunctionDecl => self.parse_function(&name, entity),
-                        _ => {},
+        let mut start = Instant::now();
+        let entities = entity.get_children();
+
+        // names of functions which is not matched by filters
+        let mut unmatched = Vec::new();
+        // functions to bind when all used types is discovered
+        let mut functions = Vec::new();
+
+        for (index, &entity) in entities.iter().enumerate() {
+            self.progress("discover types", index, entities.len());
+            // macros from any headers can be used in expressions
+            if entity.get_kind() == MacroDefinition && self.options.parse_macros() &&
+                !entity.is_builtin_macro() && !entity.is_function_like_macro() {
+                    if let (Some(name), Some(tokens)) = (entity.get_name(), macro_tokens(entity)) {
+                        // the values with braces can't be expanded into expressions
+                        if unsupported_construct(&tokens).is_none() {
+                            self.macro_defs.insert(name, tokens);
+                        }
                     }
+                    continue;
+                }
+            if self.is_excluded(entity) {
+                continue;
+            }
+            if let Some(name) = entity.get_name() {
+                match entity.get_kind() {
+                    FunctionDecl if self.is_known(&name) => {},
+                    FunctionDecl if self.match_name(&self.options.function_filter, &name) ||
+                        self.options.libraries.iter().chain(&self.options.features)
+                        .any(|(_, regex)| regex.is_match(&name)) => {
+                            if let Some(reason) = self.options.skip_functions.iter()
+                                .find(|(skip_name, _)| skip_name == &name)
+                                .map(|(_, reason)| reason.clone()) {
+                                    // the intentional omission is documented by stub
+                                    if self.export_once(entity_key(entity)) {
+                                        info!("Skip function by configuration: `{}`", name);
+                                        self.skipped.push((name, reason));
+                                    }
+                                    continue;
+                                }
+                            self.contain(entity, |this| {
+                                this.discover_function(entity);
+                                Ok(())
+                            })?;
+                            functions.push((name, entity));
+                        },
+                    FunctionDecl => unmatched.push(name),
+                    StructDecl | TypedefDecl if self.options.type_filter.is_some() &&
+                        self.match_name(&self.options.type_filter, &name) =>
+                        self.contain(entity, |this| {
+                            this.parse_type(entity.get_type().unwrap());
+                            Ok(())
+                        })?,
+                    _ => {},
                 }
             }
         }
 
-        for entity in entity.get_children() {
+        self.timings.add("discover types", &mut start);
+
+        // the signatures is built when names of all types is known
+        for (index, (name, entity)) in functions.iter().enumerate() {
+            self.progress("bind functions", index, functions.len());
+            self.contain(*entity, |this| this.parse_function(name, *entity))?;
+        }
+
+        if self.calls.len() < self.options.min_matches {
+            unmatched.sort();
+            unmatched.dedup();
+            
+            let sample = unmatched.iter().take(10)
+                .map(|name| format!("`{}`", name))
+                .collect::<Vec<_>>().join(", ");
+            
+            return Err(Error::Unmatched(if unmatched.is_empty() {
+                format!("Only {} functions bound (at least {} expected) and no other functions found",
+                        self.calls.len(), self.options.min_matches)
+            } else {
+                format!("Only {} functions bound (at least {} expected), check match patterns. Available functions: {}{}",
+                        self.calls.len(), self.options.min_matches, sample,
+                        if unmatched.len() > 10 { ", ..." } else { "" })
+            }));
+        }
+
+        self.timings.add("bind functions", &mut start);
+
+        // imports depends on bound functions only
+        self.translate_imports();
+        self.flush(&mut output)?;
+
+        // all used types now declared so it can be defined
+        self.define_types();
+        self.flush(&mut output)?;
+        self.timings.add("define types", &mut start);
+
+        for (index, &entity) in entities.iter().enumerate() {
+            self.progress("translate declarations", index, entities.len());
+            if self.is_excluded(entity) {
+                continue;
+            }
             if let Some(name) = entity.get_name() {
-                if self.match_name(&name) {
-                    let xname = self.make_name(&name);
-                    if self.export_once(&name) {
-                        match entity.get_kind() {
-                            EnumDecl => self.translate_enum(&name, &xname, entity),
-                            _ => {},
-                        }
-                    }
+                match entity.get_kind() {
+                    // the forward declarations is skipped because the definition has same key
+                    EnumDecl if entity.is_definition() && !self.is_known(&name) &&
+                        self.match_name(&self.options.enum_filter, &name) &&
+                        self.export_once(entity_key(entity)) => {
+                            let xname = self.global_names.unique(self.make_name(&name));
+                            self.contain(entity, |this| {
+                                this.translate_enum(&name, &xname, entity, entity.get_comment());
+                                Ok(())
+                            })?;
+                            self.flush(&mut output)?;
+                        },
+                    MacroDefinition if self.options.macro_filter.is_some() && !self.is_known(&name) &&
+                        self.match_name(&self.options.macro_filter, &name) &&
+                        !entity.is_builtin_macro() && !entity.is_function_like_macro() &&
+                        self.export_once(entity_key(entity)) => {
+                            let xname = self.global_names.unique(self.make_name(&name));
+                            self.contain(entity, |this| {
+                                this.translate_macro(&name, &xname, entity);
+                                Ok(())
+                            })?;
+                            self.flush(&mut output)?;
+                        },
+                    MacroDefinition if !self.is_known(&name) && (self.is_shim_macro(&name) ||
+                        self.options.macro_filter.is_some() && self.match_name(&self.options.macro_filter, &name)) &&
+                        !entity.is_builtin_macro() && entity.is_function_like_macro() &&
+                        self.export_once(entity_key(entity)) =>
+                        self.contain(entity, |this| {
+                            this.translate_function_macro(&name, entity);
+                            Ok(())
+                        })?,
+                    _ => {},
                 }
             }
         }
         
-        self.coder.comment("Library class");
+        self.timings.add("translate declarations", &mut start);
+        
+        self.resolve_frees();
+        self.translate_version();
+        
+        if !self.function_macros.is_empty() {
+            let list = self.function_macros.iter()
+                .map(|(signature, shim_name)| match shim_name {
+                    Some(shim_name) => format!("{} (wrapped by `{}`)", signature, shim_name),
+                    None => signature.clone(),
+                })
+                .collect();
+            
+            self.decls.push(Decl::FunctionMacros(list));
+        }
+        
+        let known_symbols = &self.options.known_symbols;
+        let mut opaques = self.opaques.iter()
+            .filter(|(name, _)| !known_symbols.contains(*name))
+            .map(|(_, xname)| xname.clone())
+            .collect::<Vec<_>>();
+        opaques.sort();
+        
+        self.decls.push(Decl::Opaques(opaques));
 
-        let class = &self.options.class_name;
-        let calls = &self.calls;
-        let callbacks = &self.callbacks;
+        let aliases = self.pointer_aliases();
+        if !aliases.is_empty() {
+            self.decls.push(Decl::TypeAliases(aliases));
+        }
+        let mut functypes = self.functypes.clone();
+        if self.options.member_order == MemberOrder::Alphabetical {
+            functypes.sort_by(|(a, _), (b, _)| a.cmp(b));
+        }
+        self.decls.push(Decl::FuncTypes(functypes));
         
-        self.coder.block(format!("class {name}", name = class), |coder| {
-            coder.comment("Callbacks");
+        let abi_checks = self.abi_checks();
+
+        let classes = std::iter::once(self.options.class_name.clone())
+            .chain(self.options.libraries.iter().map(|(class, _)| class.clone()))
+            .chain(self.feature_classes.iter().cloned())
+            .collect::<Vec<_>>();
+
+        if self.options.examples {
+            self.example_notes(&classes);
+        }
 
-            for (name, func) in callbacks {
-                if let Some(cmt) = &func.cmt {
-                    coder.comment(cmt);
+        // optional features is guarded by checking that library provides its functions
+        let features = self.options.features.iter().enumerate()
+            .filter_map(|(index, (feature, _))| {
+                let library = 1 + self.options.libraries.len() + index;
+                let symbols = self.calls.iter()
+                    .filter(|(name, _)| self.call_libraries.get(name) == Some(&library))
+                    .filter_map(|(_, func)| func.name.clone())
+                    .collect::<Vec<_>>();
+                if symbols.is_empty() {
+                    None
+                } else {
+                    Some((feature.clone(), classes[library].clone(), symbols))
                 }
-                coder.line(format!("final Pointer<NativeFunction<{type}>> {name};",
-                                   type = func.cffi,
-                                   name = name));
+            })
+            .collect::<Vec<_>>();
+        let features = features.into_iter()
+            .map(|(feature, class, symbols)| (self.member_name(feature), class, symbols))
+            .collect::<Vec<_>>();
+
+        for (library, class) in classes.into_iter().enumerate() {
+            let in_library = |name: &String| self.call_libraries.get(name).copied().unwrap_or(0) == library;
+            let mut calls = self.calls.iter()
+                .filter(|(name, _)| in_library(name))
+                .cloned().collect::<Vec<_>>();
+            let mut wrappers = self.wrappers.iter()
+                .filter(|(_, raw_name, _)| in_library(raw_name))
+                .cloned().collect::<Vec<_>>();
+            let mut forwarders = self.forwarders.iter()
+                .filter(|(_, field_name)| in_library(field_name))
+                .cloned().collect::<Vec<_>>();
+            let mut asyncs = self.asyncs.iter()
+                .filter(|(_, ffi_name, _)| calls.iter().any(|(_, func)| func.name.as_ref() == Some(ffi_name)))
+                .cloned().collect::<Vec<_>>();
+
+            // the sections is grouped by emitter
+            if self.options.member_order == MemberOrder::Alphabetical {
+                calls.sort_by(|(a, _), (b, _)| a.cmp(b));
+                wrappers.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+                forwarders.sort_by(|(a, _), (b, _)| a.cmp(b));
+                asyncs.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
             }
             
-            coder.comment("Functions");
+            // callbacks, native ports and checks belongs to main library class
+            let main = library == 0;
+            let library = if main {
+                LibraryDef {
+                    callbacks: self.callbacks.clone(),
+                    ports: self.ports.clone(),
+                    init_dart_api: if self.ports.is_empty() {
+                        None
+                    } else {
+                        Some(format!("{}init_dart_api_dl", self.options.shim_prefix))
+                    },
+                    skipped: self.skipped.clone(),
+                    abi_checks: abi_checks.clone(),
+                    features: features.clone(),
+                    ..LibraryDef::default()
+                }
+            } else {
+                LibraryDef::default()
+            };
+
+            let init_name = if !self.options.binding_shape.is_top_level() {
+                String::new()
+            } else if main {
+                self.global_names.unique("init")
+            } else {
+                self.global_names.unique(format!("init_{}", class))
+            };
+
+            self.decls.push(Decl::Library(LibraryDef {
+                name: class,
+                shape: self.options.binding_shape,
+                init_name,
+                calls,
+                wrappers,
+                forwarders,
+                asyncs,
+                manual_region: self.options.manual_regions,
+                legacy: !self.options.null_safety,
+                ..library
+            }));
+        }
 
-            for (name, func) in calls {
-                if let Some(cmt) = &func.cmt {
-                    coder.comment(cmt);
+        if self.options.wrappers && self.string_arrays {
+            self.decls.push(Decl::StringArrayHelpers);
+        }
+
+        if !self.complex_types.is_empty() {
+            self.decls.push(Decl::ComplexHelpers(self.complex_types.clone()));
+        }
+
+        if self.layout_checks {
+            self.decls.push(Decl::LayoutHelpers);
+        }
+
+        self.emit();
+        self.warn_renamed(None);
+
+        self.timings.add("generate library class", &mut start);
+
+        let (hits, misses) = self.typenames.cache_stats.get();
+        debug!("Type translation cache: {} hits, {} misses", hits, misses);
+
+        Ok(())
+    }
+
+    /// Add Dart usage snippets translated from code examples of functions comments
+    fn example_notes(&mut self, classes: &[String]) {
+        let top_level = self.options.binding_shape.is_top_level();
+        // the functions of additional classes is called using instances named like classes
+        let receiver = |library: usize| if library == 0 {
+            "lib".to_string()
+        } else {
+            let mut chars = classes[library].chars();
+            chars.next()
+                .map(|first| first.to_lowercase().chain(chars).collect())
+                .unwrap_or_default()
+        };
+
+        let callees = self.model.functions.iter().zip(&self.calls)
+            .map(|(function, (xname, _))| {
+                // private functions is called via public forwarding functions
+                let name = self.forwarders.iter()
+                    .find(|(_, field_name)| field_name == xname)
+                    .map(|(name, _)| name)
+                    .unwrap_or(xname);
+                let callee = if top_level {
+                    name.clone()
+                } else {
+                    format!("{}.{}", receiver(self.call_libraries.get(xname).copied().unwrap_or(0)), name)
+                };
+                (function.name.clone(), callee)
+            })
+            .collect::<HashMap<_, _>>();
+
+        let title = if top_level {
+            "Usage example translated from C:".to_string()
+        } else {
+            format!("Usage example translated from C (`lib` is instance of `{}`):", classes[0])
+        };
+
+        for (_, func) in &mut self.calls {
+            let examples = func.cmt.as_deref().map(comment_examples).unwrap_or_default();
+            func.notes.extend(examples.iter().map(|code| format!("{title}\n```dart\n{code}\n```",
+                                                                 title = title,
+                                                                 code = translate_example(code, &callees))));
+        }
+    }
+
+    /// Alias nested pointer types which is often used in signatures
+    ///
+    /// The signatures of functions and function types is rewritten to use aliases.
+    fn pointer_aliases(&mut self) -> Vec<(String, String)> {
+        let threshold = self.options.pointer_aliases;
+        if threshold == 0 || !self.options.null_safety {
+            return Vec::new();
+        }
+
+        let regex = Regex::new(r"(?:Pointer<){2,}(\w+)").unwrap();
+        // types with base names, depths and numbers of uses in order of first use
+        let mut uses: Vec<(String, String, usize, usize)> = Vec::new();
+
+        for (_, func) in self.calls.iter().chain(&self.functypes) {
+            for captures in regex.captures_iter(&func.dart) {
+                let found = captures.get(0).unwrap();
+                let depth = found.as_str().matches("Pointer<").count();
+                let closing = ">".repeat(depth);
+                if !func.dart[found.end()..].starts_with(&closing) {
+                    continue;
+                }
+                let type_ = format!("{}{}", found.as_str(), closing);
+                match uses.iter_mut().find(|(used, ..)| *used == type_) {
+                    Some((.., count)) => *count += 1,
+                    None => uses.push((type_, captures[1].into(), depth, 1)),
                 }
-                coder.line(format!("final {type} {name};",
-                                   type = func.dart,
-                                   name = name));
             }
+        }
 
-            coder.comment("Constructor");
-            coder.line(format!("{name}(", name = class));
-            coder.line("    DynamicLibrary dylib");
-            
-            for (name, _func) in callbacks {
-                coder.line(format!("  , this.{name}",
-                                   name = name));
+        let aliases = uses.into_iter()
+            .filter(|(.., count)| *count >= threshold)
+            .map(|(type_, base, depth, _)| {
+                let name = camel_case(base.strip_suffix("_t").unwrap_or(&base));
+                let name = self.global_names.unique(format!("{}{}", name, "Ptr".repeat(depth)));
+                (name, type_, depth)
+            })
+            .collect::<Vec<_>>();
+
+        // the deeper pointers is replaced first to not break it by aliases of shallower ones
+        let mut replaces = aliases.clone();
+        replaces.sort_by(|(_, _, a), (_, _, b)| b.cmp(a));
+
+        for (_, func) in self.calls.iter_mut().chain(&mut self.functypes) {
+            for (name, type_, _) in &replaces {
+                for text in [&mut func.dart, &mut func.cffi, &mut func.res, &mut func.params] {
+                    if text.contains(type_.as_str()) {
+                        *text = text.replace(type_.as_str(), name);
+                    }
+                }
             }
-            
-            coder.line(")");
+        }
+
+        aliases.into_iter().map(|(name, type_, _)| (name, type_)).collect()
+    }
+
+    /// Emit translated declarations to coder
+    fn emit(&mut self) {
+        self.order_types();
+        for decl in &self.decls {
+            decl.emit(&mut self.coder);
+        }
+    }
+
+    /// Sort structs and enums which is not yet written by names when it is required
+    ///
+    /// The types is placed in positions of unsorted ones, so other declarations keeps its order.
+    fn order_types(&mut self) {
+        if self.options.member_order != MemberOrder::Alphabetical {
+            return;
+        }
+
+        let type_name = |decl: &Decl| match decl {
+            Decl::Struct(struct_) => Some(struct_.name.clone()),
+            Decl::Enum(enum_) => Some(enum_.name.clone()),
+            _ => None,
+        };
+
+        let decls = &mut self.decls;
+        let positions = (0..decls.len())
+            .filter(|index| type_name(&decls[*index]).is_some())
+            .collect::<Vec<_>>();
+        let mut types = positions.iter()
+            .map(|index| decls[*index].clone())
+            .collect::<Vec<_>>();
+        types.sort_by_key(type_name);
 
-            let mut initial = true;
+        for (index, decl) in positions.into_iter().zip(types) {
+            decls[index] = decl;
+        }
+    }
 
-            coder.comment("Init functions");            
-            for (name, func) in calls {
-                coder.line(format!("{sep} {name} = dylib.lookup<NativeFunction<{type}>>('{ffi_name}').asFunction()",
-                                   type = func.cffi,
-                                   name = name,
-                                   ffi_name = func.name.as_ref().unwrap(),
-                                   sep = if initial { ':' } else { ',' }));
-                if initial { initial = false; }
+    /// Write completed code to output and start new one
+    fn flush(&mut self, output: &mut Option<&mut dyn Write>) -> Result<()> {
+        if let Some(output) = output {
+            self.order_types();
+            let decls = std::mem::take(&mut self.decls);
+            for decl in &decls {
+                decl.emit(&mut self.coder);
             }
-            
-            coder.line("{}");
-        });
+            self.requirements.merge(&Requirements::from_decls(&decls));
+            if self.options.ir_output.is_some() {
+                self.flushed.extend(decls);
+            }
+            write!(output, "{}", self.coder)?;
+            self.coder = Coder::with_style(self.options.style.clone());
+        }
+        Ok(())
+    }
+
+    /// Put imports before generated code
+    fn translate_imports(&mut self) {
+        let mut imports = ImportsDef {
+            ignore_lints: self.options.ignore_lints.clone(),
+            ..ImportsDef::default()
+        };
+        
+        if let Some(library) = &self.options.part_of {
+            // part can't have own imports so library should import all required
+            imports.part_of = Some(library.clone());
+        } else {
+            if !self.options.null_safety {
+                // legacy code should be opted out of null safety
+                imports.language_version = Some("2.9".into());
+            }
+            if self.wrappers.iter().any(|(_, _, wrap)| wrap.has_buffers()) {
+                imports.libraries.insert("dart:typed_data");
+            }
+            imports.libraries.insert("dart:ffi");
+            if !self.asyncs.is_empty() || !self.ports.is_empty() ||
+                self.wrappers.iter().any(|(_, _, wrap)| !wrap.checks.is_empty()) {
+                imports.libraries.insert("dart:isolate");
+            }
+            if self.options.wrappers {
+                imports.libraries.insert("package:ffi/ffi.dart");
+            }
+            if self.calls.iter().any(|(_, func)| func.use_result) {
+                imports.libraries.insert("package:meta/meta.dart");
+            }
+            imports.libraries.extend(self.options.imports.iter().cloned());
+        }
+
+        self.decls.insert(0, Decl::Imports(imports));
+    }
+
+    /// Register types which is used by function
+    ///
+    /// The functions which will be skipped by binding is ignored.
+    fn discover_function(&mut self, entity: Entity<'tu>) {
+        let types = entity.get_result_type().into_iter()
+            .chain(entity.get_arguments().unwrap_or_default().into_iter()
+                   .filter_map(|arg| arg.get_type()))
+            .collect::<Vec<_>>();
+
+        if types.iter().any(|type_| unsupported_type(*type_, &self.options).is_some()) {
+            return;
+        }
+
+        let has_prototype = entity.get_type().unwrap().get_canonical_type().get_kind() == TypeKind::FunctionPrototype;
+        
+        if !has_prototype && self.options.strict_prototypes {
+            return;
+        }
+
+        // not exported functions can be bound via shims only
+        let shimmable = self.options.shim_output.is_some() && entity.is_definition() &&
+            entity.get_visibility() != Some(Visibility::Hidden);
+        
+        if !is_exported(entity) && !shimmable {
+            return;
+        }
+
+        for type_ in types {
+            self.parse_type(type_);
+        }
     }
 
-    fn parse_function(&mut self, name: &str, entity: Entity) {
+    fn parse_function(&mut self, name: &str, entity: Entity<'tu>) -> Result<()> {
+        let key = entity_key(entity);
+        let signature = entity.get_type().unwrap().get_canonical_type();
+        let has_prototype = signature.get_kind() == TypeKind::FunctionPrototype;
+        let signature = signature.get_display_name();
+
+        // the same function may be declared several times
+        let replace = if let Some((index, prev_signature)) = self.functions.get(&key) {
+            if prev_signature == &signature || !has_prototype {
+                debug!("Skip redeclaration of function: `{}`", name);
+                return Ok(());
+            }
+            if !prev_signature.ends_with("()") {
+                return Err(format!("Conflicting declarations of function `{}`: `{}` and `{}`",
+                                   name, prev_signature, signature).into());
+            }
+            // replace declaration without prototype
+            Some(*index)
+        } else {
+            None
+        };
+
+        if let Some(reason) = entity.get_result_type().into_iter()
+            .chain(entity.get_arguments().unwrap_or_default().into_iter()
+                   .filter_map(|arg| arg.get_type()))
+            .find_map(|type_| unsupported_type(type_, &self.options)) {
+            if self.export_once(key) {
+                self.warn(entity, format!("Skip function: {}", reason));
+                self.skipped.push((name.into(), reason));
+            }
+            return Ok(());
+        }
+
+        if !has_prototype && self.options.strict_prototypes {
+            if self.export_once(key) {
+                let reason = "declared without prototype".to_string();
+                self.warn(entity, format!("Skip function: {}", reason));
+                self.skipped.push((name.into(), reason));
+            }
+            return Ok(());
+        }
+
+        let shim_name = if is_exported(entity) {
+            None
+        } else if self.options.shim_output.is_some() && entity.is_definition() &&
+            entity.get_visibility() != Some(Visibility::Hidden) {
+                let shim_name = format!("{}{}", self.options.shim_prefix, name);
+                if !self.translate_shim(name, &shim_name, entity) {
+                    return Ok(());
+                }
+                Some(shim_name)
+            } else {
+                info!("Skip non-exported function: `{}`", name);
+                return Ok(());
+            };
+
         info!("Parse function: `{}`", name);
 
+        // the signature with untranslated types would make the output invalid
+        if let Some(reason) = untranslated_type(&translate_type(&self.typenames, entity.get_type().unwrap(), true)) {
+            if self.export_once(key) {
+                self.warn(entity, format!("Skip function: {}", reason));
+                self.skipped.push((name.into(), reason));
+            }
+            return Ok(());
+        }
+
         let res = entity.get_result_type().unwrap();
-        let args = entity.get_arguments().unwrap();
+        let args = entity.get_arguments().unwrap_or_default();
 
-        let xname = self.make_name(name);
+        let xname = if let Some(index) = replace {
+            self.calls[index].0.clone()
+        } else {
+            self.member_name(self.make_name(name))
+        };
 
         self.parse_type(res);
 
-        let mut num = 0;
-        
-        for arg in args {
+        for (num, arg) in args.into_iter().enumerate() {
             use TypeKind::*;
             
             let type_ = arg.get_type().unwrap();
@@ -201,16 +884,27 @@ impl Translator {
 
                 match type_.get_kind() {
                     FunctionPrototype | FunctionNoPrototype => {
-                        let name = arg.get_name().unwrap_or_else(|| {
-                            let res = format!("cb{}", num);
-                            num += 1;
-                            res
-                        });
+                        let param = arg_name(num, arg);
+                        
+                        let xname = self.member_name(format!("{fn_name}_{arg_name}",
+                                                                     fn_name = xname,
+                                                                     arg_name = param));
+                        self.parse_type(type_);
+                        let typedef = callback_typedef(arg.get_type().unwrap());
+                        let type_name = self.register_functype(&xname, type_, typedef);
+                        
+                        if self.is_port_callback(name, &param) {
+                            let shim_name = format!("{prefix}{fn_name}_{arg_name}",
+                                                    prefix = self.options.shim_prefix,
+                                                    fn_name = name,
+                                                    arg_name = param);
+                            if self.translate_port_shim(&shim_name, arg, type_) {
+                                self.ports.push((xname, shim_name, type_name));
+                                continue;
+                            }
+                        }
                         
-                        let xname = format!("{fn_name}_{arg_name}",
-                                            fn_name = xname,
-                                            arg_name = name);
-                        self.callbacks.push((xname, FuncDef::from_type(&self.typenames, type_)));
+                        self.callbacks.push((xname, type_name));
                         continue;
                     }
                     _ => {}
@@ -220,225 +914,2502 @@ impl Translator {
             self.parse_type(type_);
         }
 
-        self.calls.push((xname, FuncDef::from_entity(&self.typenames, entity)));
-    }
+        if entity.get_result_type().into_iter()
+            .chain(entity.get_arguments().unwrap_or_default().into_iter()
+                   .filter_map(|arg| arg.get_type()))
+            .any(is_string_array) {
+                self.string_arrays = true;
+            }
 
-    fn parse_type<'a>(&mut self, type_: Type<'a>) {
-        use TypeKind::*;
-        use EntityKind::*;
-        
-        match type_.get_kind() {
-            Pointer => self.parse_type(type_.get_pointee_type().unwrap()),
-            _ => if let Some(entity) = type_.get_declaration() {
-                trace!("parse type: {:?}", entity);
-                if let Some(name) = entity.get_name() {
-                    let xname = self.make_name(&name);
-                    if !self.exported.contains(&name) {
-                        match entity.get_kind() {
-                            EnumDecl => self.translate_enum(&name, &xname, entity),
-                            StructDecl => self.translate_struct(&name, &xname, entity),
-                            TypedefDecl => if !self.translate_typedef(&name, &xname, entity) {
-                                warn!("Unparsed typedef: {:?}", entity);
-                                return;
-                            }
-                            _ => {
-                                warn!("Unparsed typedecl: {:?}", entity);
-                                return;
-                            }
-                        }
-                        self.exported.insert(name.clone());
-                        self.typenames.insert(name, xname);
+        let opaques = self.void_opaques(name, entity);
+        let mut func = FuncDef::from_entity(&self.typenames, entity, &opaques);
+        func.use_result &= self.options.use_result;
+
+        if shim_name.is_some() {
+            func.name = shim_name;
+        }
+
+        if self.options.async_filter.is_some() && self.match_name(&self.options.async_filter, name) {
+            self.parse_async(&xname, entity, &func, &opaques);
+        }
+
+        if !has_prototype && entity.get_arguments().map(|args| args.is_empty()).unwrap_or(true) {
+            self.warn(entity, "Function declared without prototype is bound as taking no arguments");
+            func.notes.push("Declared without prototype so it is bound as taking no arguments.".into());
+        }
+
+        if let Some(conv) = entity.get_type().and_then(unsupported_calling_convention) {
+            self.warn(entity, format!("Uses {:?} calling convention which is unsupported by Dart FFI", conv));
+        }
+
+        let pragmas = Pragmas::from_entity(entity);
+        func.notes.extend(thread_notes(&self.options, name));
+        func.notes.extend(pragmas.notes());
+        func.notes.extend(nullable_notes(&nullable_params(&self.options, &pragmas, name, entity)));
+        func.group = self.group_name(name, entity);
+
+        let xname = if self.options.wrappers {
+            let wrap = WrapDef::from_entity(&self.typenames, &self.options, name, entity, &mut self.diagnostics);
+            // the wrapper of replaced declaration is updated instead of adding another one
+            let prev = replace.and_then(|_| self.wrappers.iter().position(|(_, raw_name, _)| raw_name == &xname));
+            // the private function of replaced declaration is accessed via forwarder
+            let forwarder = replace.and_then(|_| self.forwarders.iter().position(|(_, field_name)| field_name == &xname));
+            match (prev, wrap, forwarder) {
+                (Some(index), Some(wrap), _) => {
+                    self.wrappers[index].2 = wrap;
+                    xname
+                },
+                (Some(index), None, _) => {
+                    // the function takes back the name of removed wrapper
+                    let (name, raw_name, _) = self.wrappers.remove(index);
+                    if self.options.member_visibility == MemberVisibility::Private {
+                        self.forwarders.push((name, raw_name.clone()));
+                        raw_name
+                    } else {
+                        name
                     }
-                }
+                },
+                (None, Some(wrap), Some(index)) => {
+                    // the wrapper takes the name of forwarder
+                    let (name, field_name) = self.forwarders.remove(index);
+                    self.wrappers.push((name, field_name.clone(), wrap));
+                    field_name
+                },
+                (None, Some(wrap), None) => {
+                    // the wrapper takes the name of raw function
+                    let raw_name = self.member_name(format!("{}_raw", xname));
+                    self.wrappers.push((xname, raw_name.clone(), wrap));
+                    raw_name
+                },
+                (None, None, _) => xname,
             }
-        }
-    }
+        } else {
+            xname
+        };
 
-    pub fn coder(&self) -> &Coder {
-        &self.coder
-    }
+        let function = Function::from_entity(&xname, func.deprecated.clone(), entity);
 
-    fn match_name(&self, name: impl AsRef<str>) -> bool {
-        self.options.names_match.is_match(name.as_ref())
-    }
+        // private functions is accessed via public forwarding functions or wrappers
+        let xname = if self.options.member_visibility == MemberVisibility::Private && replace.is_none() {
+            let field_name = self.member_name(format!("_{}", xname));
+            if let Some((_, raw_name, _)) = self.wrappers.iter_mut().find(|(_, raw_name, _)| raw_name == &xname) {
+                *raw_name = field_name.clone();
+            } else {
+                self.forwarders.push((xname, field_name.clone()));
+            }
+            field_name
+        } else {
+            xname
+        };
 
-    fn make_name(&self, name: impl AsRef<str>) -> String {
-        self.options.names_match.replace(name.as_ref(), &self.options.names_replace as &str).into()
-    }
+        // the classes of optional features follows additional library classes
+        if let Some(library) = self.options.libraries.iter().chain(&self.options.features)
+            .position(|(_, regex)| regex.is_match(name)) {
+            self.call_libraries.insert(xname.clone(), library + 1);
+        }
 
-    fn export_once(&mut self, name: impl AsRef<str>) -> bool {
-        let name = name.as_ref();
-        if self.exported.contains(name) {
-            false
+        if let Some(index) = replace {
+            self.model.functions[index] = function;
+            self.calls[index] = (xname, func);
+            self.functions.insert(key, (index, signature));
         } else {
-            self.exported.insert(name.into());
-            true
+            self.functions.insert(key, (self.calls.len(), signature));
+            self.model.functions.push(function);
+            self.calls.push((xname, func));
         }
-    }
 
-    fn translate_enum(&mut self, name: &str, xname: &str, entity: Entity) {
-        info!("Translate enum: `{}` as `{}`", name, xname);
+        Ok(())
+    }
 
-        if let Some(cmt) = entity.get_comment() {
-            self.coder.comment(cmt);
+    /// Make asynchronous wrapper for function
+    fn parse_async(&mut self, xname: &str, entity: Entity, func: &FuncDef, opaques: &HashMap<String, String>) {
+        let ffi_name = func.name.clone().unwrap();
+        let args = entity.get_arguments().unwrap_or_default();
+        
+        let res = opaques.get("return").map(|name| format!("Pointer<{}>", name))
+            .or_else(|| entity.get_result_type().map(|type_| translate_type(&self.typenames, type_, false).into()))
+            .unwrap_or_else(|| "void".into());
+        
+        let index = self.asyncs.iter().position(|(_, name, _)| name == &ffi_name);
+        
+        let ptr_name = if let Some(index) = index {
+            // replace declaration without prototype
+            self.asyncs[index].2.ptr_name.clone()
+        } else {
+            self.member_name(format!("{}_ptr", xname))
+        };
+        
+        let async_ = AsyncDef {
+            ptr_name,
+            res,
+            params: translate_args(&self.typenames, args.clone(), false, opaques),
+            args: args.into_iter().enumerate().map(|(num, arg)| dart_arg_name(num, arg)).collect(),
+            cffi: func.cffi.clone(),
+            dart: func.dart.clone(),
+        };
+        
+        if let Some(index) = index {
+            self.asyncs[index].2 = async_;
+        } else {
+            let name = self.member_name(format!("{}_async", xname));
+            self.asyncs.push((name, ffi_name, async_));
         }
-        self.coder.block(format!("class {name}",
-                                 name = xname), |coder| {
-            for entity in entity.get_children() {
-                if entity.get_kind() == EntityKind::EnumConstantDecl {
-                    let ent_name = entity.get_name().unwrap();
-                    let ent_name = without_prefix(ent_name, &name);
-                    
-                    let ent_val = entity.get_enum_constant_value().unwrap().0;
-                    
-                    coder.line(format!("static const {name} = {value};",
-                                       name = ent_name,
-                                       value = ent_val));
-                }
-            }
-        });
     }
 
-    fn translate_field(coder: &mut Coder, entity: Entity) {
-        if entity.get_kind() == EntityKind::FieldDecl {
-            let name = entity.get_name().unwrap();
-            let type_ = entity.get_type().unwrap();
+    fn translate_shim(&mut self, name: &str, shim_name: &str, entity: Entity) -> bool {
+        let res = entity.get_result_type().unwrap();
+        let args = entity.get_arguments().unwrap_or_default();
 
-            info!("Translate field: `{}` of type `{:?}`", name, type_);
-            
-            let ffi_type = type_annotation(type_);
-            let native_type = native_type(type_);
+        let res_type = res.get_display_name();
+        let arg_types = args.iter()
+            .map(|arg| arg.get_type().unwrap().get_display_name())
+            .collect::<Vec<_>>();
 
-            if let Some(cmt) = entity.get_comment() {
-                coder.comment(cmt);
+        // types like function pointers or arrays cannot be spelled before argument name
+        if res_type.contains(&['(', '['][..]) ||
+            arg_types.iter().any(|type_| type_.contains(&['(', '['][..])) {
+                self.warn(entity, "Unable to generate shim for function");
+                return false;
             }
-            coder.line(format!("{ffi_type} {native_type} {name};",
-                               name = name,
-                               ffi_type = ffi_type,
-                               native_type = native_type));
-        }
-    }
-    
-    fn translate_struct(&mut self, name: &str, xname: &str, entity: Entity) {
-        info!("Translate struct: `{}` as `{}`", name, xname);
 
-        if let Some(cmt) = entity.get_comment() {
-            self.coder.comment(cmt);
-        }
-        self.coder.block(format!("class {name} extends Struct",
-                                 name = xname), |coder| {
-            for field in entity.get_children() {
-                Self::translate_field(coder, field);
-            }
+        info!("Generate shim for function: `{}` as `{}`", name, shim_name);
+
+        let arg_names = (0..args.len())
+            .map(|num| format!("arg{}", num))
+            .collect::<Vec<_>>();
+
+        let params = if args.is_empty() {
+            "void".into()
+        } else {
+            arg_types.iter().zip(&arg_names)
+                .map(|(type_, name)| format!("{} {}", type_, name))
+                .collect::<Vec<_>>().join(", ")
+        };
+        
+        let call = format!("{name}({args});",
+                           name = name,
+                           args = arg_names.join(", "));
+
+        let call = if res.get_kind() == TypeKind::Void {
+            call
+        } else {
+            format!("return {}", call)
+        };
+
+        self.shim.block(format!("{res} {name}({params})",
+                                res = res_type,
+                                name = shim_name,
+                                params = params), |coder| {
+            coder.line(call);
         });
+        
+        true
     }
 
-    fn translate_typedef(&mut self, name: &str, xname: &str, entity: Entity) -> bool {
+    fn is_port_callback(&self, name: &str, param: &str) -> bool {
+        let path = format!("{}.{}", name, param);
+        self.options.port_callbacks.iter().any(|regex| regex.is_match(&path))
+    }
+
+    /// Generate shim function which posts callback arguments to Dart port
+    fn translate_port_shim(&mut self, shim_name: &str, entity: Entity, type_: Type) -> bool {
         use TypeKind::*;
         
-        let type_ = entity.get_typedef_underlying_type().unwrap();
-        let type_ = type_.get_canonical_type();
+        if self.options.shim_output.is_none() {
+            self.warn(entity, "Native port callback requires shim output");
+            return false;
+        }
 
-        match type_.get_kind() {
-            Record => {
-                info!("Translate typedef record: `{}` as `{}`", name, xname);
+        if type_.get_result_type().map(|type_| type_.get_kind() != Void).unwrap_or(true) {
+            self.warn(entity, "Native port callback should not return value");
+            return false;
+        }
 
-                if let Some(cmt) = entity.get_comment() {
-                    self.coder.comment(cmt);
-                }
-                self.coder.block(format!("class {name} extends Struct",
-                                         name = xname), |coder| {
-                    for field in type_.get_fields().unwrap() {
-                        Self::translate_field(coder, field);
-                    }
-                });
-            }
-            _ => {
-                warn!("Untranslated typedef {:?}: `{}` as `{}`", type_, name, xname);
+        let arg_types = type_.get_argument_types().unwrap_or_default();
+        let mut params = Vec::new();
+        let mut values = Vec::new();
+
+        for (num, type_) in arg_types.into_iter().enumerate() {
+            let type_name = type_.get_display_name();
+            let arg = format!("arg{}", num);
+
+            if type_name.contains(&['(', '['][..]) {
+                self.warn(entity, format!("Unable to generate native port shim `{}`", shim_name));
                 return false;
             }
+
+            let value = match type_.get_canonical_type().get_kind() {
+                Pointer =>
+                    format!("Dart_CObject_kInt64; values[{num}].value.as_int64 = (int64_t)(intptr_t){arg}",
+                            num = num, arg = arg),
+                Bool | CharS | CharU | SChar | UChar | WChar | Char16 | Char32 |
+                Short | UShort | Int | UInt | Long | ULong | LongLong | ULongLong | Enum =>
+                    format!("Dart_CObject_kInt64; values[{num}].value.as_int64 = (int64_t){arg}",
+                            num = num, arg = arg),
+                Float | Double =>
+                    format!("Dart_CObject_kDouble; values[{num}].value.as_double = (double){arg}",
+                            num = num, arg = arg),
+                kind => {
+                    self.warn(entity, format!("Unable to post {:?} argument from native port shim `{}`",
+                                              kind, shim_name));
+                    return false;
+                }
+            };
+
+            params.push(format!("{} {}", type_name, arg));
+            values.push(format!("values[{num}].type = {value};", num = num, value = value));
         }
 
-        true
-    }
-}
+        info!("Generate native port shim: `{}`", shim_name);
 
-fn translate_type(typenames: &HashMap<String, String>, type_: Type<'_>, ffi: bool) -> Cow<'static, str> {
-    use TypeKind::*;
+        if self.ports.is_empty() {
+            self.shim.line("#include \"dart_api_dl.h\"");
+            self.shim.block(format!("intptr_t {prefix}init_dart_api_dl(void *data)",
+                                    prefix = self.options.shim_prefix), |coder| {
+                coder.line("return Dart_InitializeApiDL(data);");
+            });
+        }
 
-    let canonical_type = type_.get_canonical_type();
-    
-    debug!("Translate type: {:?} canonical: {:?}", type_, canonical_type);
+        self.shim.line(format!("static Dart_Port_DL {name}_port = ILLEGAL_PORT;", name = shim_name));
+        
+        self.shim.block(format!("void {name}_set_port(Dart_Port_DL port)", name = shim_name), |coder| {
+            coder.line(format!("{name}_port = port;", name = shim_name));
+        });
+
+        self.shim.block(format!("void {name}({params})",
+                                name = shim_name,
+                                params = if params.is_empty() { "void".into() } else { params.join(", ") }),
+                        |coder| {
+            let len = values.len();
+            
+            if len > 0 {
+                coder.line(format!("Dart_CObject values[{}];", len));
+                coder.line(format!("Dart_CObject *items[{}];", len));
+                for (num, value) in values.into_iter().enumerate() {
+                    coder.line(value);
+                    coder.line(format!("items[{num}] = &values[{num}];", num = num));
+                }
+            }
+            coder.raw(format!("
+                Dart_CObject message;
+                message.type = Dart_CObject_kArray;
+                message.value.as_array.length = {len};
+                message.value.as_array.values = {items};
+                Dart_PostCObject_DL({name}_port, &message);
+            ", len = len, items = if len > 0 { "items" } else { "NULL" }, name = shim_name));
+        });
+        
+        true
+    }
+
+    /// Find opaque types for `void*` params and result of function
+    fn void_opaques(&mut self, name: &str, entity: Entity) -> HashMap<String, String> {
+        let mut opaques = HashMap::default();
+
+        if self.options.void_opaques.is_empty() {
+            return opaques;
+        }
+
+        let mut params = entity.get_arguments().unwrap_or_default().into_iter().enumerate()
+            .filter(|(_, arg)| is_void_pointer(arg.get_type().unwrap()))
+            .map(|(num, arg)| arg_name(num, arg))
+            .collect::<Vec<_>>();
+
+        if entity.get_result_type().map(is_void_pointer).unwrap_or(false) {
+            params.push("return".into());
+        }
+
+        for param in params {
+            let path = format!("{}.{}", name, param);
+            
+            if let Some((_, type_name)) = self.options.void_opaques.iter()
+                .find(|(regex, _)| regex.is_match(&path)) {
+                    let type_name = type_name.clone();
+                    let xname = self.opaque_type(&type_name);
+                    
+                    debug!("Map `{}` to opaque `{}`", path, xname);
+                    opaques.insert(param, xname);
+                }
+        }
+
+        opaques
+    }
+
+    /// Register opaque type
+    fn opaque_type(&mut self, name: &str) -> String {
+        if let Some(xname) = self.opaques.get(name) {
+            return xname.clone();
+        }
+
+        let xname = self.global_names.unique(name);
+        self.opaques.insert(name.into(), xname.clone());
+        
+        xname
+    }
+
+    /// Register record as opaque type
+    fn register_opaque(&mut self, key: String, name: &str) -> String {
+        info!("Translate opaque type: `{}`", name);
+        
+        let xname = self.opaque_type(&self.make_name(name));
+        self.exported.insert(key.clone());
+        self.typenames.insert_type(key, xname.clone());
+        xname
+    }
+
+    fn is_opaque_type(&self, name: &str) -> bool {
+        (!self.options.no_system_opaques && SYSTEM_OPAQUES.contains(&name)) ||
+            self.options.opaque_types.iter().any(|regex| regex.is_match(name))
+    }
+
+    /// Register native function type once per unique signature
+    ///
+    /// The type declared by typedef will be named and commented as typedef.
+    fn register_functype(&mut self, name: &str, type_: Type, typedef: Option<Entity>) -> String {
+        let mut func = FuncDef::from_type(&self.typenames, type_);
+        
+        if let Some(type_name) = self.typenames.functions.get(&func.cffi) {
+            return type_name.clone();
+        }
+
+        let type_name = if let Some(typedef_name) = typedef.and_then(|typedef| typedef.get_name()) {
+            func.cmt = typedef.and_then(|typedef| typedef.get_comment());
+            self.global_names.unique(self.make_name(typedef_name))
+        } else {
+            self.global_names.unique(format!("{}_fn", name))
+        };
+        
+        if let Some(conv) = unsupported_calling_convention(type_) {
+            let reason = format!("Function type `{}` uses {:?} calling convention which is unsupported by Dart FFI",
+                                 type_.get_display_name(), conv);
+            let diagnostic = match typedef {
+                Some(typedef) => Diagnostic::with_entity(Severity::Warning, typedef, reason),
+                None => Diagnostic::new(Severity::Warning, reason),
+            };
+            diagnostic.log();
+            self.diagnostics.push(diagnostic);
+        }
+
+        self.typenames.insert_function(func.cffi.clone(), type_name.clone());
+        self.functypes.push((type_name.clone(), func));
+        
+        type_name
+    }
+
+    fn parse_type(&mut self, type_: Type<'tu>) {
+        use TypeKind::*;
+        use EntityKind::*;
+        
+        match type_.get_kind() {
+            Pointer => self.parse_type(type_.get_pointee_type().unwrap()),
+            ConstantArray | IncompleteArray | VariableArray | DependentSizedArray =>
+                self.parse_type(type_.get_element_type().unwrap()),
+            FunctionPrototype | FunctionNoPrototype => {
+                if let Some(type_) = type_.get_result_type() {
+                    self.parse_type(type_);
+                }
+                for type_ in type_.get_argument_types().unwrap_or_default() {
+                    self.parse_type(type_);
+                }
+            }
+            Complex if self.options.complex_structs => if let Some(part) = complex_part(type_) {
+                self.declare_complex(part);
+            },
+            Vector if self.options.vector_structs => if let (Ok(size), Ok(alignment)) = (type_.get_sizeof(), type_.get_alignof()) {
+                self.declare_vector(size, alignment);
+            },
+            _ => if let Some(entity) = type_.get_declaration() {
+                trace!("parse type: {:?}", entity);
+                if let Some(name) = entity.get_name() {
+                    let key = entity_key(entity);
+                    if !self.exported.contains(&key) {
+                        match entity.get_kind() {
+                            // the incomplete structs is accessible via pointers only
+                            StructDecl if self.is_opaque_type(&name) || is_incomplete(type_) => {
+                                self.register_opaque(key, &name);
+                            }
+                            // the values of enums is passed as integers anyway
+                            EnumDecl if is_incomplete(type_) => debug!("Skip incomplete enum: `{}`", name),
+                            EnumDecl | StructDecl => {
+                                let xname = self.register_type(key, &name);
+                                self.declare_type(&name, xname, entity, entity.get_comment());
+                            }
+                            TypedefDecl => self.parse_typedef(key, &name, entity),
+                            _ => self.warn(entity, "Unparsed type declaration"),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Define struct of complex number with real and imaginary parts
+    fn declare_complex(&mut self, part: &str) {
+        let key = format!("_Complex {}", part);
+        if self.typenames.types.contains_key(&key) {
+            return;
+        }
+
+        let name = self.global_names.unique(format!("Complex{}", part));
+        info!("Translate complex type: `{}` as `{}`", key, name);
+        self.stats.structs += 1;
+        self.typenames.insert_type(key, name.clone());
+
+        let field = |name: &str| FieldDef {
+            name: name.into(),
+            cmt: None,
+            deprecated: None,
+            annotation: Some(format!("@{}()", part)),
+            type_: "double".into(),
+            padding: false,
+        };
+
+        self.decls.push(Decl::Struct(StructDef {
+            name: name.clone(),
+            cmt: Some(format!("Complex number with `{}` parts", part.to_lowercase())),
+            deprecated: None,
+            fields: vec![field("re"), field("im")],
+            external: self.options.null_safety,
+            pointer_ext: false,
+            debug_string: false,
+            alignment: None,
+            layout: None,
+        }));
+        self.complex_types.push(name);
+    }
+
+    /// Define struct of bytes which holds vector value of given size
+    fn declare_vector(&mut self, size: usize, alignment: usize) {
+        let key = format!("vector {}", size);
+        if self.typenames.types.contains_key(&key) {
+            return;
+        }
+
+        let name = self.global_names.unique(format!("Vector{}", size));
+        info!("Translate vector type of {} bytes as `{}`", size, name);
+        self.stats.structs += 1;
+        self.typenames.insert_type(key, name.clone());
+
+        // the bytes is aligned by one in Dart
+        let alignment = Some(alignment).filter(|alignment| *alignment > 1);
+        let cmt = format!("Vector (SIMD) value of {} bytes", size);
+
+        self.decls.push(Decl::Struct(StructDef {
+            name,
+            cmt: Some(match alignment {
+                Some(alignment) => format!("{}\n\n{}", cmt, alignment_note(alignment)),
+                None => cmt,
+            }),
+            deprecated: None,
+            fields: vec![FieldDef {
+                name: "bytes".into(),
+                cmt: None,
+                deprecated: None,
+                annotation: Some(format!("@Array({})", size)),
+                type_: "Array<Uint8>".into(),
+                padding: false,
+            }],
+            external: self.options.null_safety,
+            pointer_ext: false,
+            debug_string: false,
+            alignment,
+            layout: None,
+        }));
+    }
+
+    fn parse_typedef(&mut self, key: String, name: &str, entity: Entity<'tu>) {
+        use TypeKind::*;
+        
+        let type_ = entity.get_typedef_underlying_type().unwrap();
+        let canonical_type = type_.get_canonical_type();
+
+        if self.model.typedef(name).is_none() {
+            self.model.typedefs.push(crate::Typedef::from_entity(name, entity));
+        }
+
+        match canonical_type.get_kind() {
+            Record if self.is_opaque_type(name) || is_incomplete(canonical_type) => {
+                let decl = canonical_type.get_declaration().unwrap();
+                let xname = self.register_opaque(key, name);
+                self.exported.insert(entity_key(decl));
+                self.typenames.insert_type(entity_key(decl), xname);
+            }
+            Enum if is_incomplete(canonical_type) => debug!("Skip typedef of incomplete enum: `{}`", name),
+            Record | Enum => {
+                let decl = canonical_type.get_declaration().unwrap();
+                let decl_key = entity_key(decl);
+
+                if let Some(xname) = self.typenames.types.get(&decl_key).cloned() {
+                    // the record or enum already translated so reuse it
+                    debug!("Alias typedef: `{}` as `{}`", name, xname);
+                    self.exported.insert(key.clone());
+                    self.typenames.insert_type(key, xname);
+                    return;
+                }
+
+                info!("Translate typedef {:?}: `{}`", canonical_type.get_kind(), name);
+
+                // the record or enum will be translated under the typedef name
+                let xname = self.register_type(key, name);
+                self.exported.insert(decl_key.clone());
+                self.typenames.insert_type(decl_key, xname.clone());
+
+                let cmt = entity.get_comment().or_else(|| decl.get_comment());
+                
+                self.declare_type(name, xname, decl, cmt);
+            }
+            _ => {
+                // discover the types which is used by typedef
+                self.parse_type(type_);
+                debug!("Untranslated typedef {:?}: `{}`", canonical_type, name);
+            }
+        }
+    }
+
+    /// Declare record or enum type which will be defined later
+    ///
+    /// The types of record fields will be declared too.
+    fn declare_type(&mut self, name: &str, xname: String, entity: Entity<'tu>, cmt: Option<String>) {
+        if entity.get_kind() != EntityKind::EnumDecl {
+            for field in entity.get_children() {
+                if field.get_kind() == EntityKind::FieldDecl {
+                    self.parse_type(field.get_type().unwrap());
+                }
+            }
+        }
+        
+        self.pending.push(TypeDef {
+            name: name.into(),
+            xname,
+            entity,
+            cmt,
+        });
+    }
+
+    /// Define all declared types
+    fn define_types(&mut self) {
+        for def in std::mem::take(&mut self.pending) {
+            if self.is_known(&def.name) {
+                debug!("Skip known type: `{}`", def.name);
+                continue;
+            }
+            let _ = self.contain(def.entity, |this| {
+                if def.entity.get_kind() == EntityKind::EnumDecl {
+                    this.translate_enum(&def.name, &def.xname, def.entity, def.cmt);
+                } else {
+                    this.translate_struct(&def.name, &def.xname, def.entity, def.cmt);
+                }
+                Ok(())
+            });
+        }
+    }
+
+    /// Translate entity containing panics
+    ///
+    /// The entity which translation panics will be skipped with error report.
+    fn contain(&mut self, entity: Entity, translate: impl FnOnce(&mut Self) -> Result<()>) -> Result<()> {
+        install_panic_hook();
+
+        // the state of outer guarded translation is restored after nested one
+        let outer = CAUGHT_PANIC.with(|caught| caught.replace(Some(None)));
+        let result = catch_unwind(AssertUnwindSafe(|| translate(self)));
+        let caught = CAUGHT_PANIC.with(|caught| caught.replace(outer)).flatten();
+
+        let result = match result {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = caught
+                    .or_else(|| payload.downcast_ref::<&str>().map(|message| message.to_string()))
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown error".into());
+                
+                let diagnostic = Diagnostic::with_entity(Severity::Error, entity,
+                                                         format!("Skip entity due to internal error: {}", message));
+                diagnostic.log();
+                self.diagnostics.push(diagnostic);
+                
+                Ok(())
+            }
+        };
+
+        self.warn_renamed(Some(entity));
+        result
+    }
+
+    /// Replace native functions which frees results of wrappers by its bindings
+    ///
+    /// The results is not freed when function is not bound by same library class.
+    fn resolve_frees(&mut self) {
+        for index in 0..self.wrappers.len() {
+            let (name, raw_name, wrap) = &self.wrappers[index];
+            let free = match &wrap.free {
+                Some(free) => free,
+                None => continue,
+            };
+            let library = self.call_libraries.get(raw_name);
+            let callee = self.calls.iter()
+                .find(|(_, func)| func.name.as_ref() == Some(free))
+                .filter(|(xname, _)| self.call_libraries.get(xname) == library)
+                .map(|(xname, _)| xname.clone());
+            let (name, free) = (name.clone(), free.clone());
+
+            let wrap = &mut self.wrappers[index].2;
+            wrap.res_post.retain(|line| *line != free_statement(&free));
+            match callee {
+                Some(callee) => wrap.res_post.push(free_statement(&callee)),
+                None => {
+                    let diagnostic = Diagnostic {
+                        name: Some(name),
+                        ..Diagnostic::new(Severity::Warning,
+                                          format!("Result is not freed because `{}` is not bound by library class", free))
+                    };
+                    diagnostic.log();
+                    self.diagnostics.push(diagnostic);
+                }
+            }
+        }
+    }
+
+    /// Report names which is renamed due to conflicts
+    fn warn_renamed(&mut self, entity: Option<Entity>) {
+        let renamed = self.global_names.take_renamed().into_iter()
+            .chain(self.member_names.take_renamed())
+            .collect::<Vec<_>>();
+
+        for (name, alt_name) in renamed {
+            let reason = format!("Name `{}` already in use so renamed to `{}`", name, alt_name);
+            let diagnostic = match entity {
+                Some(entity) => Diagnostic::with_entity(Severity::Warning, entity, reason),
+                None => Diagnostic::new(Severity::Warning, reason),
+            };
+            diagnostic.log();
+            self.diagnostics.push(diagnostic);
+        }
+    }
+
+    /// Allocate unique name of library class member
+    ///
+    /// The members is top-level declarations when bindings is shaped so.
+    fn member_name(&mut self, name: impl AsRef<str>) -> String {
+        if self.options.binding_shape.is_top_level() {
+            self.global_names.unique(name)
+        } else {
+            self.member_names.unique(name)
+        }
+    }
+
+    /// Register translated type before translation to handle recursive references
+    fn register_type(&mut self, key: String, name: &str) -> String {
+        let xname = self.global_names.unique(self.make_name(name));
+        self.exported.insert(key.clone());
+        self.typenames.insert_type(key, xname.clone());
+        xname
+    }
+
+    pub fn coder(&self) -> &Coder {
+        &self.coder
+    }
+
+    pub fn shim(&self) -> &Coder {
+        &self.shim
+    }
+
+    /// Translated top-level declarations which is not written to output
+    pub fn decls(&self) -> &[Decl] {
+        &self.decls
+    }
+
+    /// Intermediate representation of translated declarations
+    pub fn ir(&self) -> Json {
+        self.flushed.iter().chain(&self.decls).cloned().collect::<Vec<_>>().to_json()
+    }
+
+    pub fn report(&self) -> Report {
+        Report {
+            stats: Stats {
+                functions: self.calls.len(),
+                skipped: self.skipped.len(),
+                ..self.stats
+            },
+            diagnostics: self.diagnostics.clone(),
+            function_macros: self.function_macros.iter()
+                .map(|(signature, _)| signature.clone())
+                .collect(),
+            symbols: self.model.functions.iter().map(|function| &function.name)
+                .chain(self.model.structs.iter().map(|struct_| &struct_.name))
+                .chain(self.model.enums.iter().map(|enum_| &enum_.name))
+                .chain(self.opaques.keys())
+                .chain(self.macros.iter())
+                .cloned()
+                .collect(),
+            timings: self.timings.clone(),
+            // the parser errors is added by caller
+            parse_errors: 0,
+            requirements: {
+                let mut requirements = self.requirements.clone();
+                requirements.merge(&Requirements::from_decls(&self.decls));
+                requirements
+            },
+        }
+    }
+
+    /// Add duration of phase which is done outside of translator
+    pub fn add_timing(&mut self, phase: &'static str, start: &mut Instant) {
+        self.timings.add(phase, start);
+    }
+
+    /// Report progress of translation
+    fn progress(&self, phase: &'static str, done: usize, total: usize) {
+        if let Some(progress) = self.options.progress {
+            progress(&Progress { phase, done, total });
+        }
+    }
+
+    /// Get parsed declarations
+    pub fn model(&self) -> &Model {
+        &self.model
+    }
+
+    /// Report warning about entity
+    fn warn(&mut self, entity: Entity, reason: impl Into<String>) {
+        let diagnostic = Diagnostic::with_entity(Severity::Warning, entity, reason);
+        diagnostic.log();
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Check that declaration is already translated
+    fn is_known(&self, name: &str) -> bool {
+        self.options.known_symbols.contains(name)
+    }
+
+    /// Get section of function from config or from banner comment before it
+    ///
+    /// The functions is not grouped when other order is required.
+    fn group_name(&mut self, name: &str, entity: Entity) -> Option<String> {
+        if self.options.member_order != MemberOrder::Grouped {
+            return None;
+        }
+
+        if let Some((group, _)) = self.options.groups.iter().find(|(_, regex)| regex.is_match(name)) {
+            return Some(group.clone());
+        }
+
+        if !self.options.group_banners {
+            return None;
+        }
+
+        let location = entity.get_location()?.get_file_location();
+        let file = location.file?;
+        let banners = self.banners.entry(file.get_path().display().to_string())
+            .or_insert_with(|| file.get_contents().map(|contents| banner_comments(&contents)).unwrap_or_default());
+
+        banners.iter().rev()
+            .find(|(offset, _)| *offset < location.offset as usize)
+            .map(|(_, title)| title.clone())
+    }
+
+    fn match_name(&self, filter: &Option<Regex>, name: impl AsRef<str>) -> bool {
+        filter.as_ref().unwrap_or(&self.options.names_match).is_match(name.as_ref())
+    }
+
+    /// Function-like macro is requested to wrap by shim
+    fn is_shim_macro(&self, name: &str) -> bool {
+        self.options.macro_shims.iter().any(|(regex, _)| regex.is_match(name))
+    }
+
+    fn is_excluded(&self, entity: Entity) -> bool {
+        if self.options.exclude_headers.is_empty() {
+            return false;
+        }
+        
+        entity.get_location()
+            .and_then(|location| location.get_file_location().file)
+            .map(|file| {
+                let path = file.get_path();
+                let path = path.to_string_lossy();
+                self.options.exclude_headers.iter().any(|regex| regex.is_match(&path))
+            })
+            .unwrap_or(false)
+    }
+
+    fn make_name(&self, name: impl AsRef<str>) -> String {
+        self.options.names_match.replace(name.as_ref(), &self.options.names_replace as &str).into()
+    }
+
+    fn export_once(&mut self, key: impl AsRef<str>) -> bool {
+        let key = key.as_ref();
+        if self.exported.contains(key) {
+            false
+        } else {
+            self.exported.insert(key.into());
+            true
+        }
+    }
+
+    fn translate_enum(&mut self, name: &str, xname: &str, entity: Entity, cmt: Option<String>) {
+        info!("Translate enum: `{}` as `{}`", name, xname);
+        self.stats.enums += 1;
+        self.model.enums.push(Enum::from_entity(name, xname, cmt.clone(), entity));
+
+        // the constants usually prefixed by enum name without `_t` suffix
+        let prefix = self.options.enum_prefixes.iter()
+            .find(|(regex, _)| regex.is_match(name))
+            .map(|(_, prefix)| prefix.clone())
+            .unwrap_or_else(|| name.strip_suffix("_t").unwrap_or(name).into());
+        let mut names = Names::default();
+        let unsigned = entity.get_enum_underlying_type().map(is_unsigned).unwrap_or(false);
+        let mut constants = Vec::new();
+        
+        for entity in entity.get_children() {
+            if entity.get_kind() == EntityKind::EnumConstantDecl {
+                let ent_name = entity.get_name().unwrap();
+                let ent_name = names.unique(constant_name(&ent_name, &prefix));
+                for (name, alt_name) in names.take_renamed() {
+                    self.warn(entity, format!("Name `{}` already in use so renamed to `{}`", name, alt_name));
+                }
+                
+                let (signed_val, unsigned_val) = entity.get_enum_constant_value().unwrap();
+                let ent_val = if unsigned { unsigned_val as i128 } else { signed_val as i128 };
+
+                // keep the style of initializer when it has same value
+                let ent_val = entity.get_range()
+                    .map(|range| range.tokenize().into_iter()
+                         .map(|token| token.get_spelling())
+                         .collect::<Vec<_>>())
+                    .and_then(|tokens| tokens.iter().position(|token| token == "=")
+                              .and_then(|index| translate_int_expr(&tokens[index + 1..])))
+                    .and_then(IntExpr::value)
+                    .filter(|(_, value)| *value == ent_val)
+                    .map(|(expr, _)| expr)
+                    .unwrap_or_else(|| int_literal_value(ent_val));
+                
+                constants.push(ConstDef {
+                    name: ent_name,
+                    cmt: entity.get_comment(),
+                    deprecated: deprecation(entity),
+                    type_: None,
+                    value: ent_val,
+                });
+            }
+        }
+
+        if self.options.member_order == MemberOrder::Alphabetical {
+            constants.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+
+        self.decls.push(Decl::Enum(EnumDef {
+            name: xname.into(),
+            cmt,
+            deprecated: deprecation(entity),
+            constants,
+        }));
+    }
+
+    fn translate_macro(&mut self, name: &str, xname: &str, entity: Entity) {
+        let value = if let Some(tokens) = macro_tokens(entity) {
+            tokens
+        } else {
+            return;
+        };
+
+        if let Some(construct) = unsupported_construct(&value) {
+            self.warn(entity, format!("Macro value uses {} which cannot be translated", construct));
+            return;
+        }
+
+        let int_value = match translate_int_expr(&value).or_else(|| self.eval_macro_expr(&value)) {
+            Some(IntExpr::Unmodelled(reason)) => {
+                self.warn(entity, format!("Macro value {} so it cannot be translated", reason));
+                return;
+            },
+            // the values above 64-bit signed range is emitted as hex which is allowed in Dart
+            Some(IntExpr::Value(expr, value)) if (i64::MIN as i128..=u64::MAX as i128).contains(&value) => Some(expr),
+            _ => None,
+        };
+
+        let string = match translate_string_literal(&value) {
+            Some(Ok(string)) => Some(string),
+            Some(Err(_)) => {
+                self.warn(entity, "Macro value is a string which is not valid UTF-8 so it cannot be translated");
+                return;
+            },
+            None => None,
+        };
+
+        if let Some(string) = string {
+            info!("Translate string macro: `{}` as `{}`", name, xname);
+            self.stats.macros += 1;
+            self.macros.push(name.into());
+
+            self.decls.push(Decl::Const(ConstDef {
+                name: xname.into(),
+                cmt: entity.get_comment(),
+                deprecated: None,
+                type_: Some("String".into()),
+                value: string,
+            }));
+        } else if let Some(value) = int_value
+            .or_else(|| translate_char_literal(&value))
+            .or_else(|| translate_literal(&value)) {
+            info!("Translate macro: `{}` as `{}`", name, xname);
+            self.stats.macros += 1;
+            self.macros.push(name.into());
+
+            self.decls.push(Decl::Const(ConstDef {
+                name: xname.into(),
+                cmt: entity.get_comment(),
+                deprecated: None,
+                type_: None,
+                value,
+            }));
+        } else {
+            debug!("Untranslated macro: `{}` = `{}`", name, value.join(" "));
+        }
+    }
+
+    /// Make runtime ABI checks as pairs of actual and expected values
+    fn abi_checks(&mut self) -> Vec<(String, String)> {
+        let mut checks = self.abi_sizes.iter()
+            .map(|(name, size)| (format!("sizeOf<{}>()", name), size.to_string()))
+            .collect::<Vec<_>>();
+
+        if let Some((function, field)) = &self.options.abi_version {
+            // the functions of model has same indexes as bound functions
+            let index = self.model.functions.iter().position(|func| &func.name == function);
+            match (index, &self.version_class) {
+                (Some(index), Some(class)) if self.model.functions[index].params.is_empty() =>
+                    checks.push((format!("{}()", self.calls[index].0), format!("{}.{}", class, field))),
+                _ => {
+                    let diagnostic = Diagnostic::new(Severity::Warning,
+                                                     format!("Unable to check version using `{}`: the function without params should be bound and version macros should be set", function));
+                    diagnostic.log();
+                    self.diagnostics.push(diagnostic);
+                },
+            }
+        }
+
+        checks
+    }
+
+    /// Generate class with values of library version macros
+    fn translate_version(&mut self) {
+        if self.options.version_macros.is_empty() {
+            return;
+        }
+
+        let mut fields = Vec::new();
+
+        for (field, name) in &self.options.version_macros {
+            let value = self.macro_defs.get(name)
+                .and_then(|tokens| translate_string_literal(tokens).and_then(|string| string.ok())
+                          .or_else(|| translate_int_expr(&expand_macros(tokens, &self.macro_defs, 0)?)
+                                   .and_then(IntExpr::value)
+                                   .map(|(_, value)| int_literal_value(value))));
+            
+            if let Some(value) = value {
+                fields.push((field.clone(), value));
+            } else {
+                let diagnostic = Diagnostic::new(Severity::Warning,
+                                                 format!("Version macro `{}` not found or cannot be evaluated", name));
+                diagnostic.log();
+                self.diagnostics.push(diagnostic);
+            }
+        }
+
+        let name = self.global_names.unique(format!("{}Version", self.options.class_name));
+
+        info!("Translate library version as `{}`", name);
+
+        self.version_class = Some(name.clone());
+        self.decls.push(Decl::Version(VersionDef { name, fields }));
+    }
+
+    /// Evaluate expression which uses other macros
+    ///
+    /// The macros is expanded recursively and the resulting expression is evaluated,
+    /// so the value will be emitted instead of expression.
+    fn eval_macro_expr(&self, tokens: &[String]) -> Option<IntExpr> {
+        if !tokens.iter().any(|token| self.macro_defs.contains_key(token)) {
+            return None;
+        }
+
+        let tokens = expand_macros(tokens, &self.macro_defs, 0)?;
+        let (_, value) = match translate_int_expr(&tokens)? {
+            IntExpr::Value(expr, value) => (expr, value),
+            unmodelled => return Some(unmodelled),
+        };
+
+        debug!("Evaluated macro expression: `{}` = {}", tokens.join(" "), value);
+
+        Some(IntExpr::Value(int_literal_value(value), value))
+    }
+
+    fn translate_function_macro(&mut self, name: &str, entity: Entity) {
+        let tokens = if let Some(range) = entity.get_range() {
+            range.tokenize().iter()
+                .map(|token| token.get_spelling())
+                .collect::<Vec<_>>()
+        } else {
+            return;
+        };
+
+        // first token is the name of macro itself followed by parenthesized params
+        let params = tokens.iter().skip(2)
+            .take_while(|token| *token != ")")
+            .filter(|token| *token != ",")
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let signature = format!("{}({})", name, params.join(", "));
+        
+        info!("Found function-like macro: `{}`", signature);
+        self.stats.function_macros += 1;
+
+        let shim_name = self.options.macro_shims.iter()
+            .find(|(regex, _)| regex.is_match(name))
+            .map(|(_, spec)| spec.clone())
+            .and_then(|spec| self.translate_macro_shim(name, &params, &spec, entity));
+        
+        self.function_macros.push((signature, shim_name));
+    }
+
+    /// Generate shim function which calls function-like macro
+    fn translate_macro_shim(&mut self, name: &str, params: &[String], spec: &str, entity: Entity) -> Option<String> {
+        if self.options.shim_output.is_none() {
+            self.warn(entity, "Macro shim requires shim output");
+            return None;
+        }
+
+        let (res, types) = match (spec.find('('), spec.trim_end().strip_suffix(')')) {
+            (Some(open), Some(spec)) => (spec[..open].trim(), &spec[open + 1..]),
+            _ => {
+                self.warn(entity, format!("Invalid macro shim signature `{}`", spec));
+                return None;
+            },
+        };
+
+        let types = types.split(',')
+            .map(|type_| type_.trim())
+            .filter(|type_| !type_.is_empty() && *type_ != "void")
+            .collect::<Vec<_>>();
+
+        if params.iter().any(|param| param == "...") {
+            self.warn(entity, "Unable to generate shim for variadic macro");
+            return None;
+        }
+
+        if types.len() != params.len() {
+            self.warn(entity, format!("Macro shim signature `{}` has {} params but macro has {}",
+                                      spec, types.len(), params.len()));
+            return None;
+        }
+
+        let shim_name = format!("{}{}", self.options.shim_prefix, name);
+
+        info!("Generate shim for macro: `{}` as `{}`", name, shim_name);
+
+        let params_decl = if params.is_empty() {
+            "void".into()
+        } else {
+            types.iter().zip(params)
+                .map(|(type_, name)| format!("{} {}", type_, name))
+                .collect::<Vec<_>>().join(", ")
+        };
+
+        let call = format!("{name}({args});",
+                           name = name,
+                           args = params.join(", "));
+
+        let call = if res == "void" {
+            call
+        } else {
+            format!("return {}", call)
+        };
+
+        self.shim.block(format!("{res} {name}({params})",
+                                res = res,
+                                name = shim_name,
+                                params = params_decl), |coder| {
+            coder.line(call);
+        });
+        
+        Some(shim_name)
+    }
+
+    fn translate_field(typenames: &TypeNames, entity: Entity) -> FieldDef {
+        let name = entity.get_name().unwrap();
+        let type_ = entity.get_type().unwrap();
+
+        info!("Translate field: `{}` of type `{:?}`", name, type_);
+        
+        let native_type = native_type(type_);
+
+        let (annotation, type_) = if type_.get_canonical_type().get_kind() == TypeKind::ConstantArray {
+            // fixed-size arrays is inlined into struct
+            let mut dims = Vec::new();
+            let mut type_ = type_;
+            
+            while type_.get_canonical_type().get_kind() == TypeKind::ConstantArray {
+                dims.push(type_.get_canonical_type().get_size().unwrap().to_string());
+                type_ = type_.get_element_type()
+                    .or_else(|| type_.get_canonical_type().get_element_type())
+                    .unwrap();
+            }
+
+            let elem_type = translate_type(typenames, type_, true);
+            let array_type = dims.iter()
+                .fold(elem_type.to_string(), |type_, _| format!("Array<{}>", type_));
+            
+            (Some(format!("@Array({})", dims.join(", "))), array_type)
+        } else if let Some(atomic_type) = atomic_value_type(type_) {
+            (Some(format!("@{}()", atomic_type)), scalar_dart_type(atomic_type).into())
+        } else if native_type.is_empty() {
+            // pointers and nested structs has no annotations
+            (None, translate_type(typenames, type_, false).into())
+        } else {
+            (Some(type_annotation(typenames, type_)), native_type.into())
+        };
+
+        let cmt = match (entity.get_comment(), qualifier_note(&name, entity.get_type().unwrap())) {
+            (Some(cmt), Some(note)) => Some(format!("{}\n\n{}.", cmt, note)),
+            (cmt, note) => cmt.or_else(|| note.map(|note| format!("{}.", note))),
+        };
+
+        FieldDef {
+            name,
+            cmt,
+            deprecated: deprecation(entity),
+            annotation,
+            type_,
+            padding: false,
+        }
+    }
+    
+    fn translate_struct(&mut self, name: &str, xname: &str, entity: Entity, cmt: Option<String>) {
+        info!("Translate struct: `{}` as `{}`", name, xname);
+
+        // the struct which layout can't be reproduced is accessible via pointers only
+        if let Some((field, reason)) = entity.get_children().into_iter()
+            .filter(|field| field.get_kind() == EntityKind::FieldDecl)
+            .find_map(|field| {
+                let type_ = field.get_type().unwrap();
+                if self.options.vector_structs && type_.get_canonical_type().get_kind() == TypeKind::Vector {
+                    None
+                } else {
+                    unsupported_type(type_, &self.options)
+                        .or_else(|| untranslated_type(&translate_type(&self.typenames, type_, true)))
+                        .map(|reason| (field.get_name().unwrap_or_default(), reason))
+                }
+            }) {
+                self.warn(entity, format!("Struct is bound as opaque because field `{}` is unsupported: {}", field, reason));
+                self.opaques.insert(name.into(), xname.into());
+                return;
+            }
+
+        let fields = entity.get_children().into_iter()
+            .filter(|field| field.get_kind() == EntityKind::FieldDecl)
+            .map(|field| Self::translate_field(&self.typenames, field))
+            .collect();
+
+        // the fields which native offsets differs from natural ones is moved by padding
+        let fields = match pad_fields(entity, fields) {
+            Ok(fields) => fields,
+            Err(reason) => {
+                self.warn(entity, format!("Struct is bound as opaque because {}", reason));
+                self.opaques.insert(name.into(), xname.into());
+                return;
+            }
+        };
+
+        self.stats.structs += 1;
+        self.model.structs.push(Struct::from_entity(name, xname, cmt.clone(), entity));
+
+        if self.options.abi_structs.iter().any(|regex| regex.is_match(name)) {
+            if let Ok(size) = entity.get_type().unwrap().get_sizeof() {
+                self.abi_sizes.push((xname.into(), size));
+            }
+        }
+
+        // the over-alignment (like `_Alignas(64)`) is lost because Dart aligns structs by fields
+        let natural = dart_alignment(entity.get_type().unwrap());
+        let alignment = entity.get_type().unwrap().get_alignof().ok()
+            .filter(|alignment| *alignment > natural);
+        let mut cmt = cmt;
+
+        if let Some(alignment) = alignment {
+            self.warn(entity, format!("Struct is aligned to {} bytes but Dart aligns it to {} bytes",
+                                      alignment, natural));
+            cmt = Some(match cmt {
+                Some(cmt) => format!("{}\n\n{}", cmt, alignment_note(alignment)),
+                None => alignment_note(alignment),
+            });
+        }
+
+        // the offsets of bit-fields is not addressable
+        let layout = if self.options.layout_checks {
+            entity.get_type().unwrap().get_sizeof().ok().map(|size| {
+                let offsets = entity.get_children().into_iter()
+                    .filter(|field| field.get_kind() == EntityKind::FieldDecl && !field.is_bit_field())
+                    .filter_map(|field| Some((field.get_name()?, field.get_offset_of_field().ok()? / 8)))
+                    .collect();
+                (size, offsets)
+            })
+        } else {
+            None
+        };
+        self.layout_checks |= layout.is_some();
+
+        self.decls.push(Decl::Struct(StructDef {
+            name: xname.into(),
+            cmt,
+            deprecated: deprecation(entity),
+            fields,
+            external: self.options.null_safety,
+            pointer_ext: self.options.pointer_extensions,
+            debug_string: self.options.debug_strings,
+            alignment,
+            layout,
+        }));
+    }
+}
+
+pub(crate) fn translate_type(typenames: &TypeNames, type_: Type<'_>, ffi: bool) -> Cow<'static, str> {
+    let key = (type_.get_display_name(), type_.get_canonical_type().get_display_name(), ffi);
+    let (hits, misses) = typenames.cache_stats.get();
+    
+    if let Some(type_) = typenames.cache.borrow().get(&key) {
+        typenames.cache_stats.set((hits + 1, misses));
+        return type_.clone();
+    }
+    
+    typenames.cache_stats.set((hits, misses + 1));
+    
+    let translated = translate_type_uncached(typenames, type_, ffi);
+    typenames.cache.borrow_mut().insert(key, translated.clone());
+    translated
+}
+
+fn translate_type_uncached(typenames: &TypeNames, type_: Type<'_>, ffi: bool) -> Cow<'static, str> {
+    use TypeKind::*;
+
+    let canonical_type = type_.get_canonical_type();
+    
+    debug!("Translate type: {:?} canonical: {:?}", type_, canonical_type);
     
     let kind = canonical_type.get_kind();
     
-    if let Some(type_) = if ffi { cffi_type(kind) } else { dart_type(kind) } {
+    if ffi {
+        if let Some(type_) = typedef_type(typenames, type_) {
+            return type_.into();
+        }
+    }
+    
+    if let Some(type_) = if ffi { cffi_type(canonical_type) } else { dart_type(kind) } {
         return type_.into();
     }
+
+    if let Some(type_) = atomic_value_type(canonical_type) {
+        return if ffi { type_ } else { scalar_dart_type(type_) }.into();
+    }
+    
+    match kind {
+        Pointer => {
+            let type_ = type_.get_pointee_type()
+                .or_else(|| canonical_type.get_pointee_type())
+                .unwrap();
+            
+            if type_.get_canonical_type().get_kind() == Void {
+                // untyped pointer is same on both sides
+                return "Pointer<Void>".into();
+            }
+            
+            format!("Pointer<{}>", translate_type(typenames, type_, true)).into()
+        }
+        ConstantArray | IncompleteArray | VariableArray | DependentSizedArray => {
+            // arrays decays to pointers to first element
+            let type_ = type_.get_element_type()
+                .or_else(|| canonical_type.get_element_type())
+                .unwrap();
+            
+            format!("Pointer<{}>", translate_type(typenames, type_, true)).into()
+        }
+        Record => {
+            let decl = type_.get_declaration().unwrap();
+            let name = decl.get_name().unwrap();
+
+            if let Some(name) = typenames.types.get(&entity_key(decl)) {
+                name.clone().into()
+            } else {
+                name.into()
+            }
+        }
+        Vector => match canonical_type.get_sizeof().ok()
+            .and_then(|size| typenames.types.get(&format!("vector {}", size))) {
+                Some(name) => name.clone().into(),
+                None => {
+                    error!("Unsupported vector type: {:?}", canonical_type);
+                    format!("{}{:?}>", UNTRANSLATED_TYPE, kind).into()
+                }
+            },
+        Complex => match complex_part(canonical_type)
+            .and_then(|part| typenames.types.get(&format!("_Complex {}", part))) {
+                Some(name) => name.clone().into(),
+                None => {
+                    error!("Unsupported complex type: {:?}", canonical_type);
+                    format!("{}{:?}>", UNTRANSLATED_TYPE, kind).into()
+                }
+            },
+        Enum => {
+            // enums is passed as underlying integers
+            match canonical_type.get_declaration().and_then(|decl| decl.get_enum_underlying_type()) {
+                Some(type_) => translate_type(typenames, type_, ffi),
+                None => if ffi { "Int32" } else { "int" }.into(),
+            }
+        }
+        FunctionPrototype | FunctionNoPrototype => {
+            let cb = FuncDef::from_type(typenames, canonical_type);
+            if let Some(name) = typenames.functions.get(&cb.cffi) {
+                format!("NativeFunction<{}>", name).into()
+            } else {
+                format!("NativeFunction<{}>", cb.cffi).into()
+            }
+        }
+        kind => {
+            error!("Unsupported type kind: {:?}", kind);
+            format!("{}{:?}>", UNTRANSLATED_TYPE, kind).into()
+        }
+    }
+}
+
+thread_local! {
+    /// Message of panic which is caught while translating entity
+    ///
+    /// The outer option is set while translation is guarded.
+    static CAUGHT_PANIC: RefCell<Option<Option<String>>> = const { RefCell::new(None) };
+}
+
+/// Install panic hook which captures messages of guarded translations instead of printing
+///
+/// The hook is installed once for all threads so panics of other threads is passed to previous hook.
+fn install_panic_hook() {
+    static INSTALL: Once = Once::new();
+
+    INSTALL.call_once(|| {
+        let hook = panic::take_hook();
+
+        panic::set_hook(Box::new(move |info| {
+            let message = info.payload().downcast_ref::<&str>().map(|message| message.to_string())
+                .or_else(|| info.payload().downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown error".into());
+            let message = match info.location() {
+                Some(location) => format!("{} at {}", message, location),
+                None => message,
+            };
+
+            let guarded = CAUGHT_PANIC.with(|caught| match &mut *caught.borrow_mut() {
+                Some(caught) => {
+                    *caught = Some(message);
+                    true
+                },
+                None => false,
+            });
+
+            if !guarded {
+                hook(info);
+            }
+        }));
+    });
+}
+
+/// Prefix of placeholder which is produced for types which cannot be translated
+const UNTRANSLATED_TYPE: &str = "<unsupported_type_kind:";
+
+/// Get reason why translated type is unusable (when it contains placeholders)
+fn untranslated_type(src: &str) -> Option<String> {
+    let kind = &src[src.find(UNTRANSLATED_TYPE)? + UNTRANSLATED_TYPE.len()..];
+    let kind = &kind[..kind.find('>').unwrap_or(kind.len())];
+
+    Some(format!("type of kind `{}` cannot be translated", kind))
+}
+
+fn translate_types<'a>(typenames: &TypeNames, types: impl IntoIterator<Item = Type<'a>>, ffi: bool) -> String {
+    types.into_iter().map(|type_| translate_type(typenames, type_, ffi))
+        .collect::<Vec<_>>().join(", ")
+}
+
+fn translate_args<'a>(typenames: &TypeNames, args: impl IntoIterator<Item = Entity<'a>>, ffi: bool,
+                      opaques: &HashMap<String, String>) -> String {
+    args.into_iter().enumerate().map(|(num, arg)| {
+        let type_ = if let Some(name) = opaques.get(&arg_name(num, arg)) {
+            format!("Pointer<{}>", name).into()
+        } else {
+            translate_type(typenames, arg.get_type().unwrap(), ffi)
+        };
+        
+        // the names is useful on Dart side only
+        if ffi {
+            type_
+        } else {
+            format!("{type} {name}", type = type_, name = dart_arg_name(num, arg)).into()
+        }
+    }).collect::<Vec<_>>().join(", ")
+}
+
+/// Get the name of function argument
+///
+/// The unnamed arguments is named by its positions like `arg0`.
+pub(crate) fn arg_name(num: usize, arg: Entity) -> String {
+    arg.get_name().unwrap_or_else(|| format!("arg{}", num))
+}
+
+/// Get the name of function argument which is valid in Dart
+///
+/// The names which is reserved in Dart is suffixed by `_`.
+pub(crate) fn dart_arg_name(num: usize, arg: Entity) -> String {
+    let name = arg_name(num, arg);
+    
+    if DART_KEYWORDS.contains(&name.as_str()) {
+        format!("{}_", name)
+    } else {
+        name
+    }
+}
+
+/// Find typedef which names function pointer type (or function type)
+fn callback_typedef<'tu>(type_: Type<'tu>) -> Option<Entity<'tu>> {
+    use TypeKind::*;
+
+    match type_.get_kind() {
+        Typedef => type_.get_declaration(),
+        Elaborated | Attributed => desugar(type_).and_then(callback_typedef),
+        Pointer => type_.get_pointee_type()
+            .filter(|type_| matches!(type_.get_kind(), Typedef | Elaborated | Attributed))
+            .and_then(callback_typedef),
+        _ => None,
+    }
+}
+
+/// Get type under elaborated (like `struct foo`) or attributed (like `int * _Nonnull`) sugar
+///
+/// The typedefs is kept because its names is used to translate types.
+/// The attributed types can be desugared only with `clang_8_0` feature.
+fn desugar(type_: Type<'_>) -> Option<Type<'_>> {
+    use TypeKind::*;
+
+    match type_.get_kind() {
+        Elaborated => type_.get_elaborated_type(),
+        #[cfg(feature = "clang_8_0")]
+        Attributed => type_.get_modified_type(),
+        _ => None,
+    }
+}
+
+/// Check that the canonical kind of type can be translated
+fn is_translatable_kind(kind: TypeKind) -> bool {
+    use TypeKind::*;
+
+    dart_type(kind).is_some() || matches!(kind, Pointer | ConstantArray | IncompleteArray | VariableArray | DependentSizedArray |
+                                Record | Enum | FunctionPrototype | FunctionNoPrototype)
+}
+
+/// Check that the type is an array of C strings (like `char**`)
+fn is_string_array(type_: Type<'_>) -> bool {
+    use TypeKind::*;
+    
+    let type_ = type_.get_canonical_type();
+    
+    type_.get_kind() == Pointer &&
+        type_.get_pointee_type().map(|type_| {
+            let type_ = type_.get_canonical_type();
+            
+            type_.get_kind() == Pointer &&
+                type_.get_pointee_type().map(|type_| matches!(type_.get_canonical_type().get_kind(),
+                                                              CharS | CharU | SChar | UChar))
+                .unwrap_or(false)
+        }).unwrap_or(false)
+}
+
+/// Get the reason why the type cannot be used with dart:ffi
+fn unsupported_type(type_: Type<'_>, options: &Options) -> Option<String> {
+    use TypeKind::*;
+
+    if is_va_list(type_) {
+        return Some("`va_list` cannot be constructed in Dart".into());
+    }
+
+    let type_ = type_.get_canonical_type();
+
+    match type_.get_kind() {
+        LongDouble => Some("`long double` is not supported by dart:ffi".into()),
+        Int128 | UInt128 => Some("128-bit integers are not supported by dart:ffi".into()),
+        // the vectors can be accessed via pointers only because it passed in vector registers
+        Pointer => type_.get_pointee_type().and_then(|type_| match type_.get_canonical_type().get_kind() {
+            Vector if options.vector_structs => None,
+            // the incomplete records is opaque behind pointers
+            Record => None,
+            _ => unsupported_type(type_, options),
+        }),
+        ConstantArray | IncompleteArray | VariableArray | DependentSizedArray =>
+            type_.get_element_type().and_then(|type_| unsupported_type(type_, options)),
+        FunctionPrototype | FunctionNoPrototype =>
+            type_.get_result_type().into_iter()
+            .chain(type_.get_argument_types().unwrap_or_default())
+            .find_map(|type_| unsupported_type(type_, options)),
+        Vector => Some("vector types are not supported by dart:ffi".into()),
+        Record if is_incomplete(type_) =>
+            Some(format!("`{}` is incomplete type which can be passed by pointer only", type_.get_display_name())),
+        Complex if options.complex_structs && complex_part(type_).is_some() => None,
+        Complex => Some(format!("`{}` is not supported by dart:ffi (complex numbers of `float` and `double` can be bound as structs)",
+                                type_.get_display_name())),
+        _ if atomic_value_type(type_).is_some() => None,
+        kind if !is_translatable_kind(kind) =>
+            Some(format!("`{}` ({:?}) is not supported by dart:ffi", type_.get_display_name(), kind)),
+        _ => None,
+    }
+}
+
+/// Check that the type is a variable arguments list
+///
+/// The `va_list` is recognized by typedef names because the underlying builtin type differs between targets.
+fn is_va_list(type_: Type<'_>) -> bool {
+    use TypeKind::*;
+
+    match type_.get_kind() {
+        Typedef => match type_.get_typedef_name().as_deref() {
+            Some("va_list") | Some("__gnuc_va_list") | Some("__builtin_va_list") => true,
+            _ => type_.get_declaration()
+                .and_then(|decl| decl.get_typedef_underlying_type())
+                .map(is_va_list)
+                .unwrap_or(false),
+        },
+        Elaborated | Attributed => desugar(type_).map(is_va_list).unwrap_or(false),
+        // decayed types are unexposed
+        Unexposed => {
+            let type_ = type_.get_canonical_type();
+            type_.get_kind() != Unexposed && is_va_list(type_)
+        },
+        // the array va_list decays to pointer to its element when passed as argument
+        Pointer => type_.get_pointee_type().map(|type_| {
+            let type_ = type_.get_canonical_type();
+            type_.get_kind() == Record && is_va_list(type_)
+        }).unwrap_or(false),
+        Record => matches!(type_.get_declaration().and_then(|decl| decl.get_name()).as_deref(),
+                           Some("__va_list_tag") | Some("__va_list")),
+        _ => false,
+    }
+}
+
+/// Check that the record or enum type is declared but not defined anywhere
+fn is_incomplete(type_: Type<'_>) -> bool {
+    type_.get_canonical_type().get_declaration()
+        .map(|decl| decl.get_definition().is_none())
+        .unwrap_or(false)
+}
+
+/// Check that the type is untyped pointer
+fn is_void_pointer(type_: Type<'_>) -> bool {
+    let type_ = type_.get_canonical_type();
+    
+    type_.get_kind() == TypeKind::Pointer &&
+        type_.get_pointee_type().unwrap().get_canonical_type().get_kind() == TypeKind::Void
+}
+
+/// Strip prefix ignoring case when it followed by underscore
+fn without_prefix(src: impl AsRef<str>, pfx: impl AsRef<str>) -> String {
+    let src = src.as_ref();
+    let pfx = pfx.as_ref();
+    if !pfx.is_empty() && src.len() > pfx.len() && src.is_char_boundary(pfx.len()) &&
+        src[..pfx.len()].eq_ignore_ascii_case(pfx) &&
+        (pfx.ends_with('_') || src[pfx.len()..].starts_with('_')) {
+        let mut src = &src[pfx.len()..];
+        while src.starts_with('_') {
+            src = &src[1..];
+        }
+        src
+    } else {
+        src
+    }.into()
+}
+
+/// Convert name to upper camel case (`foo_bar` becomes `FooBar`)
+fn camel_case(name: &str) -> String {
+    name.split(|chr: char| !chr.is_alphanumeric())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Get Dart name of enum constant without prefix
+///
+/// The original name is used when stripped one is not a valid Dart identifier.
+fn constant_name(src: &str, pfx: &str) -> String {
+    let name = without_prefix(src, pfx);
+    
+    let valid = name.chars().next().map(|chr| chr.is_ascii_alphabetic() || chr == '_' || chr == '$').unwrap_or(false) &&
+        name.chars().all(|chr| chr.is_ascii_alphanumeric() || chr == '_' || chr == '$') &&
+        !DART_KEYWORDS.contains(&name.as_str());
+
+    if valid {
+        name
+    } else {
+        debug!("Keep name of constant `{}` because `{}` is not valid identifier", src, name);
+        src.into()
+    }
+}
+
+/// Get unique key of entity to distinguish declarations
+///
+/// The USR is used when available because different kinds of entities may have same names
+/// and same entity may be declared several times.
+fn entity_key(entity: Entity) -> String {
+    entity.get_usr().map(|usr| usr.0)
+        .or_else(|| entity.get_name())
+        .unwrap_or_default()
+}
+
+/// Check that the function will be exported from shared library
+fn is_exported(entity: Entity) -> bool {
+    if entity.get_visibility() == Some(Visibility::Hidden) {
+        return false;
+    }
+
+    match entity.get_storage_class() {
+        Some(StorageClass::Static) => false,
+        Some(StorageClass::Extern) => true,
+        _ => !(entity.is_inline_function() && entity.is_definition()),
+    }
+}
+
+/// Get the real symbol name of function redirected using asm label
+fn symbol_name(entity: Entity) -> Option<String> {
+    entity.get_children().into_iter()
+        .find(|child| child.get_kind() == EntityKind::AsmLabelAttr)
+        .and_then(|attr| attr.get_name())
+        // clang marks asm labels to bypass global prefix on some platforms
+        .map(|name| name.trim_start_matches('\u{1}').into())
+}
+
+/// Get the note with original C signature when it has qualified types
+///
+/// The qualifiers like `const` has no representation in Dart FFI so the C signature
+/// helps to understand which data can be modified by function.
+fn qualified_signature_note(entity: Entity) -> Option<String> {
+    let res = entity.get_result_type()?;
+    let args = entity.get_arguments()?;
+    
+    if !is_qualified(res) && !args.iter().any(|arg| arg.get_type().map(is_qualified).unwrap_or(false)) {
+        return None;
+    }
+
+    Some(format!("C signature: `{res} {name}({args})`",
+                 res = res.get_display_name(),
+                 name = entity.get_name()?,
+                 args = args.iter().enumerate()
+                 .map(|(num, arg)| format!("{} {}", arg.get_type().unwrap().get_display_name(), arg_name(num, *arg)))
+                 .collect::<Vec<_>>().join(", ")))
+}
+
+/// Check that the type or pointee type has qualifiers
+fn is_qualified(type_: Type<'_>) -> bool {
+    let type_ = type_.get_canonical_type();
+    
+    type_.is_const_qualified() || type_.is_volatile_qualified() || type_.is_restrict_qualified() ||
+        (type_.get_kind() == TypeKind::Pointer && type_.get_pointee_type().map(is_qualified).unwrap_or(false))
+}
+
+/// Get the note about declared length of array param
+fn array_note(name: &str, type_: Type<'_>) -> Option<String> {
+    if type_.get_kind() != TypeKind::ConstantArray {
+        return None;
+    }
+
+    Some(format!("`{name}` is an array of {size} elements",
+                 name = name,
+                 size = type_.get_size()?))
+}
+
+/// Get the note about atomic or volatile value (or pointed value)
+///
+/// The Dart accesses such values as plain ones, so the required memory ordering is not guaranteed.
+fn qualifier_note(name: &str, type_: Type<'_>) -> Option<String> {
+    let type_ = type_.get_canonical_type();
+    let (pointee, relation) = match type_.get_pointee_type() {
+        Some(pointee) if type_.get_kind() == TypeKind::Pointer => (pointee.get_canonical_type(), "points to"),
+        _ => (type_, "is"),
+    };
+
+    let qualifier = if atomic_value_type(pointee).is_some() {
+        "atomic"
+    } else if pointee.is_volatile_qualified() {
+        "volatile"
+    } else {
+        return None;
+    };
+
+    Some(format!("`{name}` {relation} {qualifier} value which is accessed from Dart without memory ordering guarantees, so the synchronization is responsibility of caller",
+                 name = name,
+                 relation = relation,
+                 qualifier = qualifier))
+}
+
+/// Get alignment which Dart derives for type from natural alignments of fields
+///
+/// The explicit alignments (like `_Alignas(64)` or `aligned` attribute) is not reproduced by Dart.
+fn dart_alignment(type_: Type<'_>) -> usize {
+    let type_ = type_.get_canonical_type();
+
+    match type_.get_kind() {
+        TypeKind::Record => type_.get_fields().unwrap_or_default().into_iter()
+            .filter_map(|field| field.get_type())
+            .map(dart_alignment)
+            .max()
+            .unwrap_or(1),
+        TypeKind::ConstantArray => type_.get_element_type().map(dart_alignment).unwrap_or(1),
+        // the vectors is bound as arrays of bytes
+        TypeKind::Vector => 1,
+        _ => type_.get_alignof().unwrap_or(1),
+    }
+}
+
+/// Insert padding fields where Dart places fields before their native offsets
+///
+/// The Dart places fields by natural alignments, so the over-aligned fields (like `_Alignas(16)` members or vectors
+/// which is bound as arrays of bytes) should be moved by padding. The padding is appended to the end of struct also,
+/// so it has native size. The layouts which cannot be reproduced by padding (like packed structs) is reported as errors.
+fn pad_fields(entity: Entity<'_>, fields: Vec<FieldDef>) -> std::result::Result<Vec<FieldDef>, String> {
+    let decls = entity.get_children().into_iter()
+        .filter(|field| field.get_kind() == EntityKind::FieldDecl)
+        .collect::<Vec<_>>();
+
+    // the bit-fields is not addressable
+    if decls.iter().any(|field| field.is_bit_field()) {
+        return Ok(fields);
+    }
+
+    let layout = decls.iter()
+        .map(|field| {
+            let type_ = field.get_type()?;
+            Some((field.get_offset_of_field().ok()? / 8, type_.get_sizeof().ok()?, dart_alignment(type_)))
+        })
+        .collect::<Option<Vec<_>>>();
+    let (layout, size) = match (layout, entity.get_type().and_then(|type_| type_.get_sizeof().ok())) {
+        (Some(layout), Some(size)) => (layout, size),
+        _ => return Ok(fields),
+    };
+
+    let padding = |index: usize, size: usize| FieldDef {
+        name: format!("_padding{}", index),
+        cmt: None,
+        deprecated: None,
+        annotation: Some(format!("@Array({})", size)),
+        type_: "Array<Uint8>".into(),
+        padding: true,
+    };
+
+    let mut padded = Vec::new();
+    let mut end = 0;
+    let mut count = 0;
+
+    for (field, (offset, field_size, alignment)) in fields.into_iter().zip(layout.iter().copied()) {
+        let natural = align_to(end, alignment);
+        if offset < natural || offset % alignment != 0 {
+            return Err(format!("field `{}` is placed at offset {} but Dart places it at offset {}",
+                               field.name, offset, natural));
+        }
+        if offset > natural {
+            padded.push(padding(count, offset - end));
+            count += 1;
+        }
+        padded.push(field);
+        end = offset + field_size;
+    }
+
+    let alignment = layout.iter().map(|(_, _, alignment)| *alignment).max().unwrap_or(1);
+    let natural = align_to(end, alignment);
+    if size < natural || size % alignment != 0 {
+        return Err(format!("size is {} bytes but Dart makes it {} bytes", size, natural));
+    }
+    if size > natural {
+        padded.push(padding(count, size - end));
+    }
+
+    Ok(padded)
+}
+
+/// Round offset up to alignment
+fn align_to(offset: usize, alignment: usize) -> usize {
+    offset.div_ceil(alignment) * alignment
+}
+
+/// Note about alignment of struct which is stricter than Dart derives
+fn alignment_note(alignment: usize) -> String {
+    format!("The native struct is aligned to {} bytes, so it should be allocated with same alignment.", alignment)
+}
+
+/// Get native type of atomic scalar (like `_Atomic(int)`)
+///
+/// The atomic types is exposed by newer libclang only, so it is recognized by spelling of canonical type.
+fn atomic_value_type(type_: Type<'_>) -> Option<&'static str> {
+    let type_ = type_.get_canonical_type();
+    let spelling = type_.get_display_name();
+    let value = spelling[spelling.find("_Atomic(")? + 8..].strip_suffix(')')?;
+    let unsigned = value.contains("unsigned");
+
+    Some(match (value, type_.get_sizeof().ok()?) {
+        ("float", _) => "Float",
+        ("double", _) => "Double",
+        ("_Bool", _) => "Uint8",
+        (value, _) if value.contains(&['*', '(', '['][..]) ||
+            value.starts_with("struct ") || value.starts_with("union ") || value.contains("double") => return None,
+        (_, 1) => if unsigned { "Uint8" } else { "Int8" },
+        (_, 2) => if unsigned { "Uint16" } else { "Int16" },
+        (_, 4) => if unsigned { "Uint32" } else { "Int32" },
+        (_, 8) => if unsigned { "Uint64" } else { "Int64" },
+        _ => return None,
+    })
+}
+
+/// Get native type of parts of complex number which can be bound as struct
+fn complex_part(type_: Type<'_>) -> Option<&'static str> {
+    match type_.get_canonical_type().get_element_type()?.get_canonical_type().get_kind() {
+        TypeKind::Float => Some("Float"),
+        TypeKind::Double => Some("Double"),
+        _ => None,
+    }
+}
+
+/// Get Dart type of native scalar type
+fn scalar_dart_type(type_: &str) -> &'static str {
+    match type_ {
+        "Float" | "Double" => "double",
+        "Void" => "void",
+        _ => "int",
+    }
+}
+
+/// Get the note about non-default calling convention of function type
+fn calling_convention_note(type_: Type<'_>) -> Option<String> {
+    use CallingConvention::*;
     
-    match kind {
-        Pointer => {
-            let type_ = type_.get_pointee_type()
-                .or_else(|| canonical_type.get_pointee_type())
-                .unwrap();
-            format!("Pointer<{}>", translate_type(typenames, type_, true)).into()
+    Some(match type_.get_calling_convention()? {
+        Cdecl | SysV64 | Win64 | Aapcs | AapcsVfp => return None,
+        Stdcall => {
+            info!("Function type `{}` uses __stdcall calling convention", type_.get_display_name());
+            "Uses `__stdcall` calling convention which matches the default one only on 64-bit targets".into()
+        },
+        conv => format!("WARNING: Uses {:?} calling convention which is unsupported by Dart FFI", conv),
+    })
+}
+
+/// Get calling convention of function type when it is unsupported by Dart FFI
+fn unsupported_calling_convention(type_: Type<'_>) -> Option<CallingConvention> {
+    use CallingConvention::*;
+
+    match type_.get_calling_convention()? {
+        Cdecl | SysV64 | Win64 | Aapcs | AapcsVfp | Stdcall => None,
+        conv => Some(conv),
+    }
+}
+
+/// Get deprecation message when entity is deprecated
+fn deprecation(entity: Entity) -> Option<String> {
+    if entity.get_availability() != Availability::Deprecated {
+        return None;
+    }
+
+    // libclang does not expose the deprecation message so try to extract it from attribute tokens
+    let msg = entity.get_children().into_iter()
+        .filter(|child| child.is_attribute())
+        .filter_map(|attr| attr.get_range())
+        .map(|range| range.tokenize().into_iter()
+             .map(|token| token.get_spelling())
+             .collect::<Vec<_>>())
+        .filter_map(|tokens| tokens.iter()
+                    .position(|token| token == "deprecated" || token == "__deprecated__")
+                    .and_then(|pos| match &tokens[pos + 1..] {
+                        [open, msg, ..] if open == "(" && msg.starts_with('"') =>
+                            unquote_string(msg),
+                        _ => None,
+                    }))
+        .next();
+
+    Some(msg.unwrap_or_else(|| "Deprecated".into()))
+}
+
+/// Get tokens of entity attributes
+///
+/// Most of attributes is not exposed by libclang so it should be recognized by spelling.
+fn attribute_tokens(entity: Entity) -> Vec<String> {
+    entity.get_children().into_iter()
+        .filter(|child| child.is_attribute())
+        .filter_map(|attr| attr.get_range())
+        .flat_map(|range| range.tokenize().into_iter()
+                  .map(|token| token.get_spelling()))
+        .collect()
+}
+
+pub(crate) fn deprecated_annotation(msg: &str) -> String {
+    format!("@Deprecated({})", quote_string(msg))
+}
+
+/// Make Dart string literal
+pub(crate) fn quote_string(src: &str) -> String {
+    let mut out = String::with_capacity(src.len() + 2);
+    out.push('\'');
+    for chr in src.chars() {
+        match chr {
+            '\\' => out.push_str("\\\\"),
+            '\'' => out.push_str("\\'"),
+            '$' => out.push_str("\\$"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            chr if chr.is_control() => out.push_str(&format!("\\u{{{:x}}}", chr as u32)),
+            chr => out.push(chr),
         }
-        Record => {
-            let decl = type_.get_declaration().unwrap();
-            let name = decl.get_name().unwrap();
+    }
+    out.push('\'');
+    out
+}
 
-            if let Some(name) = typenames.get(&name) {
-                name.clone().into()
-            } else {
-                name.into()
+/// Parse C string literal
+fn unquote_string(src: &str) -> Option<String> {
+    String::from_utf8(unquote_string_bytes(src)?).ok()
+}
+
+/// Parse C string literal to bytes
+fn unquote_string_bytes(src: &str) -> Option<Vec<u8>> {
+    unquote(src.strip_prefix("u8").unwrap_or(src), '"')
+}
+
+/// Parse C literal enclosed by quotes with escape sequences
+///
+/// The hex and octal escapes is a bytes so the result should be decoded.
+fn unquote(src: &str, quote: char) -> Option<Vec<u8>> {
+    let src = src.strip_prefix(quote)?.strip_suffix(quote)?;
+
+    let mut out = Vec::with_capacity(src.len());
+    let mut chars = src.chars().peekable();
+
+    while let Some(chr) = chars.next() {
+        let chr = if chr == '\\' {
+            match chars.next()? {
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                'a' => '\x07',
+                'b' => '\x08',
+                'f' => '\x0c',
+                'v' => '\x0b',
+                'x' => {
+                    let mut code = 0u32;
+                    let mut digits = 0;
+                    while let Some(digit) = chars.peek().and_then(|chr| chr.to_digit(16)) {
+                        code = code.checked_mul(16)? + digit;
+                        digits += 1;
+                        chars.next();
+                    }
+                    if digits == 0 {
+                        return None;
+                    }
+                    if code > 0xff {
+                        return None;
+                    }
+                    out.push(code as u8);
+                    continue;
+                },
+                chr @ '0'..='7' => {
+                    let mut code = chr.to_digit(8).unwrap();
+                    for _ in 0..2 {
+                        match chars.peek().and_then(|chr| chr.to_digit(8)) {
+                            Some(digit) => code = code * 8 + digit,
+                            None => break,
+                        }
+                        chars.next();
+                    }
+                    if code > 0xff {
+                        return None;
+                    }
+                    out.push(code as u8);
+                    continue;
+                },
+                chr => chr,
             }
+        } else {
+            chr
+        };
+        out.extend_from_slice(chr.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+
+    Some(out)
+}
+
+/// Translate string literal or several adjacent literals to Dart string
+///
+/// The error is returned when the bytes of literal is not a valid UTF-8.
+fn translate_string_literal(tokens: &[String]) -> Option<std::result::Result<String, FromUtf8Error>> {
+    let tokens = if tokens.len() > 2 && tokens[0] == "(" && tokens[tokens.len()-1] == ")" {
+        &tokens[1..tokens.len()-1]
+    } else {
+        tokens
+    };
+
+    if tokens.is_empty() {
+        return None;
+    }
+
+    // adjacent literals is concatenated by compiler
+    let mut out = Vec::new();
+    for token in tokens {
+        out.extend(unquote_string_bytes(token)?);
+    }
+
+    Some(String::from_utf8(out).map(|out| quote_string(&out)))
+}
+
+/// Translate character literal to its integer code
+fn translate_char_literal(tokens: &[String]) -> Option<String> {
+    match tokens {
+        [src] => {
+            let value = unquote(src, '\'')?;
+            // the escaped byte is taken as is
+            if let [byte] = value[..] {
+                return Some(byte.to_string());
+            }
+            let value = String::from_utf8(value).ok()?;
+            let mut chars = value.chars();
+            let chr = chars.next()?;
+            if chars.next().is_some() {
+                return None;
+            }
+            Some((chr as u32).to_string())
+        },
+        _ => None,
+    }
+}
+
+fn translate_literal(tokens: &[String]) -> Option<String> {
+    let tokens = if tokens.len() > 2 && tokens[0] == "(" && tokens[tokens.len()-1] == ")" {
+        &tokens[1..tokens.len()-1]
+    } else {
+        tokens
+    };
+
+    let (neg, src) = match tokens {
+        [src] => (false, src),
+        [sign, src] if sign == "-" => (true, src),
+        _ => return None,
+    };
+
+    let sign = if neg { "-" } else { "" };
+
+    let lower = src.to_lowercase();
+
+    // the integers is translated as expressions
+    if lower.starts_with("0x") || !(lower.contains('.') || lower.contains('e')) {
+        return None;
+    }
+
+    let src = lower.trim_end_matches(&['f', 'l'][..]);
+    src.parse::<f64>().ok().map(|_| format!("{}{}", sign, src))
+}
+
+/// Get tokens of object-like macro value
+fn macro_tokens(entity: Entity) -> Option<Vec<String>> {
+    let range = entity.get_range()?;
+
+    // first token is the name of macro itself
+    Some(range.tokenize().iter().skip(1)
+         .map(|token| token.get_spelling())
+         .collect())
+}
+
+/// Find construct of macro value which has no Dart equivalent
+///
+/// The compound literals, statement expressions and initializer lists is GNU or C99 constructs
+/// which can be used in macros but can't be evaluated as constants.
+fn unsupported_construct(tokens: &[String]) -> Option<&'static str> {
+    if tokens.first().map(|token| token == "{").unwrap_or(false) {
+        return Some("initializer list");
+    }
+
+    tokens.windows(2).find_map(|pair| match (pair[0].as_str(), pair[1].as_str()) {
+        ("(", "{") => Some("statement expression"),
+        (")", "{") => Some("compound literal"),
+        _ => None,
+    })
+}
+
+/// Maximum depth of macro expansion
+const MAX_MACRO_DEPTH: usize = 32;
+
+/// Replace macros names by its parenthesized values recursively
+fn expand_macros(tokens: &[String], macros: &HashMap<String, Vec<String>>, depth: usize) -> Option<Vec<String>> {
+    // too deep or recursive macros
+    if depth > MAX_MACRO_DEPTH {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(tokens.len());
+    
+    for token in tokens {
+        if let Some(value) = macros.get(token) {
+            out.push("(".into());
+            out.extend(expand_macros(value, macros, depth + 1)?);
+            out.push(")".into());
+        } else {
+            out.push(token.clone());
         }
-        FunctionPrototype | FunctionNoPrototype => {
-            let cb = FuncDef::from_type(typenames, canonical_type);
-            format!("NativeFunction<{}>", cb.cffi).into()
-        }
-        kind => {
-            error!("Unsupported type kind: {:?}", kind);
-            format!("<unsupported_type_kind:{:?}>", kind).into()
+    }
+
+    Some(out)
+}
+
+/// Format integer value as Dart literal
+///
+/// The unsigned values which is out of 64-bit signed range is formatted as hex
+/// because Dart allows such hex literals only.
+fn int_literal_value(value: i128) -> String {
+    if value > i64::MAX as i128 {
+        format!("0x{:x}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// Check that the type is an unsigned integer
+fn is_unsigned(type_: Type<'_>) -> bool {
+    use TypeKind::*;
+
+    matches!(type_.get_canonical_type().get_kind(),
+             Bool | CharU | UChar | UShort | UInt | ULong | ULongLong | UInt128)
+}
+
+/// Translated integer constant expression
+#[derive(Debug, Clone, PartialEq)]
+enum IntExpr {
+    /// Dart expression with the value of C expression
+    Value(String, i128),
+    /// Expression which value cannot be modelled with the reason why
+    Unmodelled(&'static str),
+}
+
+impl IntExpr {
+    fn value(self) -> Option<(String, i128)> {
+        match self {
+            IntExpr::Value(expr, value) => Some((expr, value)),
+            IntExpr::Unmodelled(_) => None,
         }
     }
 }
 
-fn translate_types<'a>(typenames: &HashMap<String, String>, types: impl IntoIterator<Item = Type<'a>>, ffi: bool) -> String {
-    types.into_iter().map(|type_| translate_type(typenames, type_, ffi))
-        .collect::<Vec<_>>().join(", ")
+/// Translate integer constant expression keeping the style of C literals
+///
+/// The hex literals and operators like shifts is preserved, binary literals is converted to hex
+/// and octal literals to decimal because Dart has no such literals. The value is evaluated
+/// to check it against the value from C side.
+///
+/// The expression is evaluated using C rules for both widths of `long` and using Dart rules.
+/// The value is emitted instead of expression when Dart evaluates it differently (like `~0u`).
+fn translate_int_expr(tokens: &[String]) -> Option<IntExpr> {
+    let mut pos = 0;
+    let (expr, values) = int_expr(tokens, &mut pos, 0)?;
+
+    if pos < tokens.len() {
+        return None;
+    }
+
+    let value = match (values.long32, values.long64) {
+        (Some((value32, _)), Some((value64, _))) if value32 == value64 => value64,
+        (None, None) => return Some(IntExpr::Unmodelled("overflows its C type")),
+        _ => return Some(IntExpr::Unmodelled("depends on width of `long`")),
+    };
+
+    // the unsigned 64-bit values has same bits in Dart
+    if !values.dart.map(|dart| dart as i128 == value || dart as u64 as i128 == value).unwrap_or(false) {
+        return Some(IntExpr::Value(int_literal_value(value), value));
+    }
+
+    // outer parentheses is redundant
+    let expr = if tokens.len() > 2 && tokens[0] == "(" && tokens[tokens.len() - 1] == ")" &&
+        translate_int_expr(&tokens[1..tokens.len() - 1]).is_some() {
+        expr[1..expr.len() - 1].into()
+    } else {
+        expr
+    };
+
+    Some(IntExpr::Value(expr, value))
 }
 
-fn translate_args<'a>(typenames: &HashMap<String, String>, args: impl IntoIterator<Item = Entity<'a>>, ffi: bool) -> String {
-    args.into_iter().map(|arg| {
-        let type_ = arg.get_type().unwrap();
-        let type_ = translate_type(typenames, type_, ffi);
-        
-        if let Some(name) = arg.get_name() {
-            format!("{type} {name}", type = type_, name = name).into()
+/// Integer type of C constant expression
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IntType {
+    bits: u32,
+    unsigned: bool,
+}
+
+impl IntType {
+    fn min(self) -> i128 {
+        if self.unsigned { 0 } else { -(1 << (self.bits - 1)) }
+    }
+
+    fn max(self) -> i128 {
+        if self.unsigned { (1 << self.bits) - 1 } else { (1 << (self.bits - 1)) - 1 }
+    }
+
+    fn contains(self, value: i128) -> bool {
+        (self.min()..=self.max()).contains(&value)
+    }
+
+    /// Convert value to this type
+    ///
+    /// The unsigned values is wrapped, signed overflow cannot be evaluated.
+    fn convert(self, value: i128) -> Option<i128> {
+        if self.unsigned {
+            Some(value.rem_euclid(1 << self.bits))
+        } else if self.contains(value) {
+            Some(value)
         } else {
-            type_
+            None
         }
-    }).collect::<Vec<_>>().join(", ")
+    }
+
+    /// Wrap value to this type like two's complement integers
+    fn wrap(self, value: i128) -> i128 {
+        let value = value.rem_euclid(1 << self.bits);
+        if value > self.max() { value - (1 << self.bits) } else { value }
+    }
+
+    /// Get type of binary operation result using usual arithmetic conversions
+    fn common(self, other: Self) -> Self {
+        if self.unsigned == other.unsigned {
+            Self { bits: self.bits.max(other.bits), unsigned: self.unsigned }
+        } else {
+            let (unsigned, signed) = if self.unsigned { (self, other) } else { (other, self) };
+            if unsigned.bits >= signed.bits { unsigned } else { signed }
+        }
+    }
+
+    /// Get type of literal value with given suffix
+    ///
+    /// The decimal literals without `u` suffix can be unsigned only when it is too large
+    /// for signed types (like compilers does).
+    fn literal(value: i128, decimal: bool, unsigned: bool, longs: usize, long_bits: u32) -> Option<Self> {
+        let min_bits = match longs {
+            0 => 32,
+            1 => long_bits,
+            _ => 64,
+        };
+
+        [32, 64].iter()
+            .filter(|bits| **bits >= min_bits)
+            .flat_map(|&bits| [Self { bits, unsigned: false }, Self { bits, unsigned: true }])
+            .filter(|type_| if unsigned { type_.unsigned } else { !type_.unsigned || !decimal })
+            .chain(std::iter::once(Self { bits: 64, unsigned: true }))
+            .find(|type_| type_.contains(value))
+    }
 }
 
-fn without_prefix(src: impl AsRef<str>, pfx: impl AsRef<str>) -> String {
-    let src = src.as_ref();
-    let pfx = pfx.as_ref();
-    if src.starts_with(pfx) {
-        let mut src = &src[pfx.len()..];
-        while src.starts_with('_') {
-            src = &src[1..];
+/// C value with its type
+type CValue = Option<(i128, IntType)>;
+
+/// Value of integer constant expression evaluated in several ways
+///
+/// The missing value means that it cannot be evaluated due to overflow or other undefined behavior.
+#[derive(Debug, Clone, Copy)]
+struct IntValue {
+    /// C value when `long` is 32-bit
+    long32: CValue,
+    /// C value when `long` is 64-bit
+    long64: CValue,
+    /// Dart value
+    dart: Option<i64>,
+}
+
+impl IntValue {
+    fn unary(self, op: &str) -> Self {
+        Self {
+            long32: self.long32.and_then(|value| c_unary(op, value)),
+            long64: self.long64.and_then(|value| c_unary(op, value)),
+            dart: self.dart.map(|value| match op {
+                "-" => value.wrapping_neg(),
+                "~" => !value,
+                _ => value,
+            }),
         }
-        src
+    }
+
+    fn binary(self, op: &str, rhs: Self) -> Self {
+        Self {
+            long32: self.long32.zip(rhs.long32).and_then(|(lhs, rhs)| c_binary(op, lhs, rhs)),
+            long64: self.long64.zip(rhs.long64).and_then(|(lhs, rhs)| c_binary(op, lhs, rhs)),
+            dart: self.dart.zip(rhs.dart).and_then(|(lhs, rhs)| dart_binary(op, lhs, rhs)),
+        }
+    }
+}
+
+fn c_unary(op: &str, (value, type_): (i128, IntType)) -> CValue {
+    let value = match op {
+        "-" => type_.convert(-value)?,
+        "~" if type_.unsigned => type_.max() - value,
+        "~" => !value,
+        _ => value,
+    };
+    Some((value, type_))
+}
+
+fn c_binary(op: &str, (lhs, lhs_type): (i128, IntType), (rhs, rhs_type): (i128, IntType)) -> CValue {
+    // the shifts has type of left operand and signed values is shifted as two's complement by compilers
+    if op == "<<" || op == ">>" {
+        if !(0..lhs_type.bits as i128).contains(&rhs) {
+            return None;
+        }
+        let value = if op == "<<" { lhs << rhs } else { lhs >> rhs };
+        return Some((lhs_type.wrap(value), lhs_type));
+    }
+
+    let type_ = lhs_type.common(rhs_type);
+    let lhs = type_.convert(lhs)?;
+    let rhs = type_.convert(rhs)?;
+
+    let value = match op {
+        "*" => lhs * rhs,
+        "/" => lhs.checked_div(rhs)?,
+        "%" => lhs.checked_rem(rhs)?,
+        "+" => lhs + rhs,
+        "-" => lhs - rhs,
+        "&" => lhs & rhs,
+        "^" => lhs ^ rhs,
+        "|" => lhs | rhs,
+        _ => return None,
+    };
+
+    Some((type_.convert(value)?, type_))
+}
+
+fn dart_binary(op: &str, lhs: i64, rhs: i64) -> Option<i64> {
+    Some(match op {
+        "*" => lhs.wrapping_mul(rhs),
+        // integer division in Dart has separate operator
+        "/" => if rhs == 0 { return None } else { lhs.wrapping_div(rhs) },
+        // remainder is always positive in Dart
+        "%" => if rhs == 0 { return None } else { lhs.wrapping_rem_euclid(rhs) },
+        "+" => lhs.wrapping_add(rhs),
+        "-" => lhs.wrapping_sub(rhs),
+        "<<" if (0..64).contains(&rhs) => lhs << rhs,
+        ">>" if (0..64).contains(&rhs) => lhs >> rhs,
+        "&" => lhs & rhs,
+        "^" => lhs ^ rhs,
+        "|" => lhs | rhs,
+        _ => return None,
+    })
+}
+
+/// Parse binary operators expression with precedence climbing
+fn int_expr(tokens: &[String], pos: &mut usize, min_prec: u8) -> Option<(String, IntValue)> {
+    let (mut expr, mut value) = int_unary(tokens, pos)?;
+
+    while let Some(op) = tokens.get(*pos) {
+        let prec = match op.as_str() {
+            "*" | "/" | "%" => 5,
+            "+" | "-" => 4,
+            "<<" | ">>" => 3,
+            "&" => 2,
+            "^" => 1,
+            "|" => 0,
+            _ => break,
+        };
+
+        if prec < min_prec {
+            break;
+        }
+
+        *pos += 1;
+        let (rhs_expr, rhs) = int_expr(tokens, pos, prec + 1)?;
+
+        value = value.binary(op, rhs);
+
+        // integer division in Dart has separate operator
+        expr = format!("{} {} {}", expr, if op == "/" { "~/" } else { op }, rhs_expr);
+    }
+
+    Some((expr, value))
+}
+
+/// Parse unary operators, parenthesized expression or literal
+fn int_unary(tokens: &[String], pos: &mut usize) -> Option<(String, IntValue)> {
+    let token = tokens.get(*pos)?;
+    *pos += 1;
+
+    match token.as_str() {
+        "(" => {
+            let (expr, value) = int_expr(tokens, pos, 0)?;
+            if tokens.get(*pos)? != ")" {
+                return None;
+            }
+            *pos += 1;
+            Some((format!("({})", expr), value))
+        }
+        "-" => int_unary(tokens, pos).map(|(expr, value)| (format!("-{}", expr), value.unary("-"))),
+        "+" => int_unary(tokens, pos),
+        "~" => int_unary(tokens, pos).map(|(expr, value)| (format!("~{}", expr), value.unary("~"))),
+        _ => int_literal(token),
+    }
+}
+
+/// Translate integer literal
+///
+/// The type of literal is determined by its suffix and value.
+fn int_literal(src: &str) -> Option<(String, IntValue)> {
+    let digits = src.trim_end_matches(&['u', 'U', 'l', 'L'][..]);
+    let suffix = src[digits.len()..].to_lowercase();
+    let unsigned = suffix.contains('u');
+    let longs = suffix.matches('l').count();
+    let lower = digits.to_lowercase();
+
+    if suffix.matches('u').count() > 1 || longs > 2 {
+        return None;
+    }
+
+    let (expr, value, decimal) = if let Some(hex) = lower.strip_prefix("0x") {
+        let value = i128::from_str_radix(hex, 16).ok()?;
+        (format!("0x{}", &digits[2..]), value, false)
+    } else if let Some(bin) = lower.strip_prefix("0b") {
+        let value = i128::from_str_radix(bin, 2).ok()?;
+        (format!("0x{:x}", value), value, false)
+    } else if let Some(oct) = lower.strip_prefix('0').filter(|oct| !oct.is_empty()) {
+        let value = i128::from_str_radix(oct, 8).ok()?;
+        (value.to_string(), value, false)
     } else {
-        src
-    }.into()
+        let value = lower.parse::<i128>().ok()?;
+        (lower, value, true)
+    };
+
+    let typed = |long_bits| IntType::literal(value, decimal, unsigned, longs, long_bits)
+        .map(|type_| (value, type_));
+
+    // Dart allows hex literals up to 64 bits only
+    let dart = if expr.starts_with("0x") && value <= u64::MAX as i128 {
+        Some(value as u64 as i64)
+    } else if value <= i64::MAX as i128 {
+        Some(value as i64)
+    } else {
+        None
+    };
+
+    Some((expr, IntValue { long32: typed(32), long64: typed(64), dart }))
 }
 
-fn type_annotation(type_: Type<'_>) -> String {
+fn type_annotation(typenames: &TypeNames, type_: Type<'_>) -> String {
+    if let Some(type_) = typedef_type(typenames, type_) {
+        return format!("@{}()", type_);
+    }
+    
     let type_ = type_.get_canonical_type();
-    if let Some(type_) = cffi_type(type_.get_kind()) {
+    if let Some(type_) = cffi_type(type_) {
         format!("@{}()", type_)
     } else {
         "".into()
@@ -454,21 +3425,133 @@ fn native_type(type_: Type<'_>) -> &'static str {
     }
 }
 
-fn cffi_type(type_kind: TypeKind) -> Option<&'static str> {
+/// Reserved words of Dart which cannot be used as identifiers
+const DART_KEYWORDS: &[&str] = &[
+    "assert", "break", "case", "catch", "class", "const", "continue", "default", "do",
+    "else", "enum", "extends", "false", "final", "finally", "for", "if", "in", "is",
+    "new", "null", "rethrow", "return", "super", "switch", "this", "throw", "true",
+    "try", "var", "void", "while", "with",
+];
+
+/// Common system records which is used via pointers only
+const SYSTEM_OPAQUES: &[&str] = &[
+    "FILE", "_IO_FILE", "__sFILE",
+    "DIR", "__dirstream",
+    "fpos_t",
+    "pthread_attr_t",
+    "pthread_mutex_t",
+    "pthread_mutexattr_t",
+    "pthread_cond_t",
+    "pthread_condattr_t",
+    "pthread_rwlock_t",
+    "pthread_rwlockattr_t",
+    "sem_t",
+    "regex_t",
+    "mbstate_t",
+];
+
+/// Common system typedefs with signedness
+///
+/// The sizes of these types depend on target so it takes from parsed declarations.
+const SYSTEM_TYPEDEFS: &[(&str, bool)] = &[
+    ("size_t", false),
+    ("ssize_t", true),
+    ("ptrdiff_t", true),
+    ("intptr_t", true),
+    ("uintptr_t", false),
+    ("time_t", true),
+    ("clock_t", true),
+    ("suseconds_t", true),
+    ("off_t", true),
+    ("off64_t", true),
+    ("pid_t", true),
+    ("uid_t", false),
+    ("gid_t", false),
+    ("mode_t", false),
+    ("dev_t", false),
+    ("ino_t", false),
+    ("nlink_t", false),
+    ("blksize_t", true),
+    ("blkcnt_t", true),
+    ("socklen_t", false),
+    ("sa_family_t", false),
+    ("in_addr_t", false),
+    ("in_port_t", false),
+];
+
+/// Get native type of known typedef
+///
+/// The user-defined mapping takes precedence over built-in one.
+fn typedef_type(typenames: &TypeNames, type_: Type<'_>) -> Option<String> {
+    use TypeKind::*;
+
+    match type_.get_kind() {
+        Typedef => {
+            let name = type_.get_typedef_name()?;
+            
+            if let Some(type_) = typenames.typedefs.get(&name) {
+                return Some(type_.clone());
+            }
+
+            if let Some(type_) = fixed_width_type(&name) {
+                return Some(type_.into());
+            }
+
+            if let Some((_, signed)) = SYSTEM_TYPEDEFS.iter().find(|(type_name, _)| *type_name == name) {
+                if let Ok(size @ 1..=8) = type_.get_sizeof() {
+                    return Some(format!("{sign}{bits}",
+                                        sign = if *signed { "Int" } else { "Uint" },
+                                        bits = size * 8));
+                }
+            }
+
+            type_.get_declaration()
+                .and_then(|decl| decl.get_typedef_underlying_type())
+                .and_then(|type_| typedef_type(typenames, type_))
+        }
+        Elaborated | Attributed => desugar(type_).and_then(|type_| typedef_type(typenames, type_)),
+        _ => None,
+    }
+}
+
+/// Get native type of fixed-width integer typedef
+fn fixed_width_type(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "int8_t" => "Int8",
+        "int16_t" => "Int16",
+        "int32_t" => "Int32",
+        "int64_t" => "Int64",
+        "uint8_t" => "Uint8",
+        "uint16_t" => "Uint16",
+        "uint32_t" => "Uint32",
+        "uint64_t" => "Uint64",
+        _ => return None,
+    })
+}
+
+fn cffi_type(type_: Type<'_>) -> Option<&'static str> {
     use TypeKind::*;
     
-    Some(match type_kind {
+    Some(match type_.get_kind() {
         Void => "Void".into(),
         Bool => "Uint8".into(),
         SChar => "Int8".into(),
         CharS => "Int8".into(),
+        CharU => "Uint8".into(),
         UChar => "Uint8".into(),
+        // wchar_t is unsigned 16-bit on Windows and signed 32-bit elsewhere
+        WChar => if type_.get_sizeof() == Ok(2) { "Uint16" } else { "Int32" },
+        Char16 => "Uint16".into(),
+        Char32 => "Uint32".into(),
         Short => "Int16".into(),
         UShort => "Uint16".into(),
         Int => "Int32".into(),
         UInt => "Uint32".into(),
-        Long => "Int64".into(),
-        ULong => "Uint64".into(),
+        // long is 32-bit on Windows and 32-bit targets
+        Long => if type_.get_sizeof() == Ok(4) { "Int32" } else { "Int64" },
+        ULong => if type_.get_sizeof() == Ok(4) { "Uint32" } else { "Uint64" },
+        LongLong => "Int64".into(),
+        ULongLong => "Uint64".into(),
         Float => "Float".into(),
         Double => "Double".into(),
         _ => return None,
@@ -481,10 +3564,12 @@ fn dart_type(type_kind: TypeKind) -> Option<&'static str> {
     Some(match type_kind {
         Void => "void".into(),
         Bool |
-        SChar | CharS | UChar |
+        SChar | CharS | CharU | UChar |
+        WChar | Char16 | Char32 |
         Short | UShort |
         Int | UInt |
-        Long | ULong => "int".into(),
+        Long | ULong |
+        LongLong | ULongLong => "int".into(),
         Float | Double => "double".into(),
         _ => return None,
     })
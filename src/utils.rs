@@ -1,17 +1,127 @@
 use std::{
-    path::PathBuf,
-    str::from_utf8,
+    env,
+    ffi::OsString,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
 };
+use log::*;
+use crate::{Result, Error};
 
-pub fn system_includes_search_paths() -> Vec<PathBuf> {
-    let out = Command::new("clang")
-        .arg("-E").arg("-xc").arg("-v").arg("-")
+/// System search paths of compiler
+#[derive(Debug, Clone, Default)]
+pub struct SearchPaths {
+    /// Headers directories
+    pub includes: Vec<PathBuf>,
+    /// Frameworks directories (on Apple platforms)
+    pub frameworks: Vec<PathBuf>,
+}
+
+/// Detect system includes paths
+///
+/// The compilers will be tried in the following order: the given compiler, `$CLANG_PATH`, `$CC`, `clang`, `cc`, `gcc`.
+/// The target arguments like `--sysroot` will be passed to compiler to get target-specific paths.
+pub fn system_includes_search_paths(compiler: Option<&Path>, msvc: bool, target_args: &[String]) -> Result<SearchPaths> {
+    if msvc {
+        return msvc_includes_search_paths(compiler, target_args);
+    }
+
+    for (program, args) in compiler_candidates(compiler, &["clang", "cc", "gcc"]) {
+        debug!("Detect system includes using `{}`", program.to_string_lossy());
+        
+        if let Some(out) = compiler_output(&program, &args, target_args, &["-E", "-xc", "-v", "-"]) {
+            let paths = parse_search_paths(&out);
+            
+            if !paths.includes.is_empty() {
+                return Ok(paths);
+            }
+        }
+    }
+
+    Err(Error::Tool(format!("Unable to detect system includes search paths using {}. \
+                             Install clang or set the compiler using --clang-path or CLANG_PATH/CC \
+                             environment variables, or skip detection using --no-system-includes.",
+                            compilers_list(compiler, &["clang", "cc", "gcc"]))))
+}
+
+/// Detect MSVC and Windows SDK includes paths
+///
+/// The `INCLUDE` environment variable which is set by `vcvars` scripts is preferred.
+/// When it is missing the clang in cl driver mode will be used to find the paths.
+pub fn msvc_includes_search_paths(compiler: Option<&Path>, target_args: &[String]) -> Result<SearchPaths> {
+    if let Some(paths) = env::var_os("INCLUDE") {
+        return Ok(SearchPaths {
+            includes: paths.to_string_lossy().split(';')
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from)
+                .collect(),
+            frameworks: Vec::new(),
+        });
+    }
+
+    for (program, args) in compiler_candidates(compiler, &["clang"]) {
+        debug!("Detect MSVC includes using `{}`", program.to_string_lossy());
+        
+        if let Some(out) = compiler_output(&program, &args, target_args, &["--driver-mode=cl", "/E", "/TC", "-v", "-"]) {
+            let paths = parse_search_paths(&out);
+            
+            if !paths.includes.is_empty() {
+                return Ok(paths);
+            }
+        }
+    }
+
+    Err(Error::Tool(format!("Unable to detect MSVC includes search paths using {}. \
+                             Run the tool from the Developer Command Prompt to set INCLUDE, \
+                             or skip detection using --no-system-includes.",
+                            compilers_list(compiler, &["clang"]))))
+}
+
+/// Get compilers with extra arguments to try
+fn compiler_candidates(compiler: Option<&Path>, defaults: &[&str]) -> Vec<(OsString, Vec<OsString>)> {
+    let mut candidates = Vec::new();
+
+    if let Some(compiler) = compiler {
+        candidates.push((compiler.into(), Vec::new()));
+    }
+
+    if let Some(compiler) = env::var_os("CLANG_PATH") {
+        candidates.push((compiler, Vec::new()));
+    }
+
+    // CC may contain wrappers and arguments like `ccache gcc -m32`
+    if let Ok(compiler) = env::var("CC") {
+        let mut words = compiler.split_whitespace().map(OsString::from);
+        if let Some(program) = words.next() {
+            candidates.push((program, words.collect()));
+        }
+    }
+
+    for compiler in defaults {
+        candidates.push((compiler.into(), Vec::new()));
+    }
+
+    candidates
+}
+
+fn compilers_list(compiler: Option<&Path>, defaults: &[&str]) -> String {
+    compiler_candidates(compiler, defaults).iter()
+        .map(|(program, _)| format!("`{}`", program.to_string_lossy()))
+        .collect::<Vec<_>>().join(", ")
+}
+
+fn compiler_output(program: &OsString, args: &[OsString], target_args: &[String], extra_args: &[&str]) -> Option<String> {
+    let out = Command::new(program)
+        .args(args).args(target_args).args(extra_args)
         .stdin(Stdio::null())
-        .output().unwrap().stderr;
-    
-    let out = from_utf8(out.as_ref()).unwrap();
+        .output().ok()?.stderr;
+
+    Some(String::from_utf8_lossy(&out).into())
+}
 
+/// Parse search list of verbose preprocessor output
+///
+/// The frameworks directories is marked by suffix (like `/System/Library/Frameworks (framework directory)`).
+fn parse_search_paths(out: &str) -> SearchPaths {
     let mut lines = out.lines();
 
     for line in &mut lines {
@@ -20,13 +130,17 @@ pub fn system_includes_search_paths() -> Vec<PathBuf> {
         }
     }
 
-    let mut paths = Vec::new();
+    let mut paths = SearchPaths::default();
 
     for line in &mut lines {
         if line == "End of search list." {
             break;
         }
-        paths.push(PathBuf::from(line.trim()));
+        let line = line.trim();
+        match line.strip_suffix("(framework directory)") {
+            Some(path) => paths.frameworks.push(PathBuf::from(path.trim_end())),
+            None => paths.includes.push(PathBuf::from(line)),
+        }
     }
 
     paths
@@ -0,0 +1,565 @@
+use clang::{Entity, Type, TypeKind};
+use crate::{Options, Coder, Pragmas, Diagnostic, Severity, TypeNames, translate_type, arg_name, dart_arg_name};
+
+/// Wrapper method param
+#[derive(Debug, Clone, PartialEq)]
+pub struct WrapParam {
+    /// Dart type of param
+    pub type_: String,
+    /// Name of param
+    pub name: String,
+    /// Statements before native call
+    pub pre: Vec<String>,
+    /// Argument expression for native call
+    pub arg: String,
+    /// Statements after native call
+    pub post: Vec<String>,
+    /// Optional named param
+    pub optional: bool,
+    /// Default value of optional param
+    pub default: Option<String>,
+    /// Param is passed by wrapper itself (like output length of returned buffer)
+    pub hidden: bool,
+}
+
+json_struct!(WrapParam { type_, name, pre, arg, post, optional, default, hidden });
+
+impl WrapParam {
+    /// Pass argument as is
+    fn plain(typenames: &TypeNames, name: String, type_: Type<'_>) -> Self {
+        Self {
+            type_: translate_type(typenames, type_, false).into(),
+            arg: name.clone(),
+            name,
+            pre: Vec::new(),
+            post: Vec::new(),
+            optional: false,
+            default: None,
+            hidden: false,
+        }
+    }
+
+    /// Make param optional named
+    ///
+    /// The pointer without default value is nullable and `nullptr` is passed instead of null.
+    fn make_optional(&mut self, default: Option<String>, pointer: bool, null_safety: bool) -> bool {
+        match default {
+            Some(default) => self.default = Some(default),
+            None if pointer => self.make_nullable(null_safety),
+            None => return false,
+        }
+        self.optional = true;
+        true
+    }
+
+    /// Accept null which is passed as `nullptr`
+    fn make_nullable(&mut self, null_safety: bool) {
+        self.type_ = nullable_type(&self.type_, null_safety);
+        self.arg = format!("{} ?? nullptr", self.name);
+    }
+
+    /// Pass Dart string as temporary C string
+    fn string(name: String, encoding: Encoding) -> Self {
+        let (convert, native) = match encoding {
+            Encoding::Utf8 => ("toNativeUtf8", "Int8"),
+            Encoding::Utf16 => ("toNativeUtf16", "Uint16"),
+        };
+        
+        Self {
+            type_: "String".into(),
+            pre: vec![format!("final {name}Ptr = {name}.{convert}();",
+                              name = name,
+                              convert = convert)],
+            arg: format!("{name}Ptr.cast<{native}>()",
+                         name = name,
+                         native = native),
+            post: vec![format!("malloc.free({}Ptr);", name)],
+            name,
+            optional: false,
+            default: None,
+            hidden: false,
+        }
+    }
+
+    /// Pass Dart bytes as temporary native buffer with length
+    ///
+    /// The contents of mutable buffer will be copied back after call.
+    fn buffer(name: String, mutable: bool) -> Self {
+        let mut post = Vec::new();
+        
+        if mutable {
+            post.push(format!("{name}.setAll(0, {name}Ptr.asTypedList({name}.length));",
+                              name = name));
+        }
+        post.push(format!("malloc.free({}Ptr);", name));
+        
+        Self {
+            type_: "Uint8List".into(),
+            pre: vec![
+                format!("final {name}Ptr = malloc<Uint8>({name}.length);", name = name),
+                format!("{name}Ptr.asTypedList({name}.length).setAll(0, {name});", name = name),
+            ],
+            arg: format!("{name}Ptr.cast(), {name}.length", name = name),
+            post,
+            name,
+            optional: false,
+            default: None,
+            hidden: false,
+        }
+    }
+
+    /// Pass temporary native integer which receives length of returned buffer
+    fn out_length(name: String, native: String) -> Self {
+        Self {
+            type_: "int".into(),
+            pre: vec![format!("final {name}Ptr = malloc<{native}>();",
+                              name = name,
+                              native = native)],
+            arg: format!("{}Ptr", name),
+            post: vec![format!("malloc.free({}Ptr);", name)],
+            name,
+            optional: false,
+            default: None,
+            hidden: true,
+        }
+    }
+}
+
+/// Wrapper method definition
+#[derive(Debug, Clone, PartialEq)]
+pub struct WrapDef {
+    /// Dart result type
+    pub res: String,
+    /// Result expression where `{}` is replaced by native call result
+    pub res_expr: String,
+    /// Statements after result conversion which uses native `result`
+    pub res_post: Vec<String>,
+    /// Native function which frees result (resolved to its binding by translator)
+    pub free: Option<String>,
+    /// Wrapper params
+    pub params: Vec<WrapParam>,
+    /// Assertions before native call
+    pub checks: Vec<String>,
+    /// Documentation notes
+    pub notes: Vec<String>,
+}
+
+json_struct!(WrapDef { res, res_expr, res_post, free, params, checks, notes });
+
+impl WrapDef {
+    /// Make wrapper for function when it is required
+    pub fn from_entity(typenames: &TypeNames, options: &Options, name: &str, entity: Entity,
+                       diagnostics: &mut Vec<Diagnostic>) -> Option<Self> {
+        let buffer_length = &options.buffer_length_match;
+        let pragmas = Pragmas::from_entity(entity);
+        
+        // the function which returns owned string
+        let free = options.owned_strings.iter()
+            .find(|(regex, _)| regex.is_match(name))
+            .map(|(_, free)| free.as_str())
+            .or_else(|| pragmas.owns_return.as_ref()
+                     .map(|free| free.as_deref().unwrap_or("malloc.free")));
+
+        let checks = if options.thread_asserts && options.main_thread.iter().any(|regex| regex.is_match(name)) {
+            vec![format!("assert(Isolate.current.debugName == 'main', '{} must be called on the main isolate');", name)]
+        } else {
+            Vec::new()
+        };
+
+        let nullable = nullable_params(options, &pragmas, name, entity);
+        let notes = thread_notes(options, name).into_iter()
+            .chain(nullable_notes(&nullable))
+            .collect();
+        
+        let mut wrapped = !checks.is_empty();
+        let mut params = Vec::new();
+        let args = entity.get_arguments()?;
+
+        let res_type = entity.get_result_type();
+        // the returned bytes is copied using length which is written to output param
+        let res_buffer = res_type.and_then(buffer_pointer).is_some();
+        let mut out_length = None;
+
+        let optional_param = |num: usize, arg: Entity| {
+            let path = format!("{}.{}", name, arg_name(num, arg));
+            options.optional_params.iter()
+                .find(|(regex, _)| regex.is_match(&path))
+                .map(|(_, default)| (path, default.clone()))
+        };
+
+        // only trailing params can be optional
+        let first_optional = args.iter().enumerate().rev()
+            .take_while(|(num, arg)| optional_param(*num, **arg).is_some())
+            .last()
+            .map(|(num, _)| num)
+            .unwrap_or_else(|| args.len());
+
+        let mut args = args.into_iter().enumerate().peekable();
+
+        let mut warn = |reason: String| {
+            let diagnostic = Diagnostic::with_entity(Severity::Warning, entity, reason);
+            diagnostic.log();
+            diagnostics.push(diagnostic);
+        };
+
+        while let Some((num, arg)) = args.next() {
+            let optional = optional_param(num, arg);
+            let name = arg_name(num, arg);
+            let dart_name = dart_arg_name(num, arg);
+            let type_ = arg.get_type().unwrap();
+
+            if res_buffer && out_length.is_none() && buffer_length.is_match(&name) {
+                if let Some(native) = integer_pointee(type_) {
+                    out_length = Some(dart_name.clone());
+                    params.push(WrapParam::out_length(dart_name, translate_type(typenames, native, true).into()));
+                    continue;
+                }
+            }
+
+            if let Some(mutable) = buffer_pointer(type_).filter(|_| !pragmas.is_borrowed(&name)) {
+                if let Some((len_num, len_arg)) = args.peek().cloned() {
+                    if is_integer(len_arg.get_type().unwrap()) &&
+                        buffer_length.is_match(&arg_name(len_num, len_arg)) {
+                            args.next();
+                            wrapped = true;
+                            params.push(WrapParam::buffer(dart_name, mutable));
+                            continue;
+                        }
+                }
+            }
+            
+            // borrowed values should not be freed after call
+            let encoding = string_encoding(type_)
+                .filter(|(_, const_)| *const_ && !pragmas.is_borrowed(&name))
+                .map(|(encoding, _)| encoding);
+            
+            params.push(if let Some(encoding) = encoding {
+                wrapped = true;
+                if let Some((path, _)) = optional {
+                    warn(format!("String param `{}` cannot be optional", path));
+                }
+                WrapParam::string(dart_name, encoding)
+            } else {
+                let mut param = WrapParam::plain(typenames, dart_name, type_);
+                if nullable.contains(&name) && optional.is_none() {
+                    wrapped = true;
+                    param.make_nullable(options.null_safety);
+                }
+                if let Some((path, default)) = optional {
+                    if num < first_optional {
+                        warn(format!("Param `{}` cannot be optional because it is followed by required params", path));
+                    } else if param.make_optional(default, type_.get_canonical_type().get_kind() == TypeKind::Pointer,
+                                                   options.null_safety) {
+                        wrapped = true;
+                    } else {
+                        warn(format!("Optional param `{}` requires default value", path));
+                    }
+                }
+                param
+            });
+        }
+
+        if let Some(length) = out_length {
+            return Some(Self {
+                res: nullable_type("Uint8List", options.null_safety),
+                res_expr: format!("{{}} == nullptr ? null : Uint8List.fromList({{}}.cast<Uint8>().asTypedList({}Ptr.value))",
+                                  length),
+                res_post: free.map(free_statement).into_iter().collect(),
+                free: free.filter(|free| is_native_free(free)).map(String::from),
+                params,
+                checks,
+                notes,
+            });
+        }
+
+        if let Some((encoding, const_)) = res_type.and_then(string_encoding) {
+            // string is converted when it is borrowed (constant) or owned (freed after conversion)
+            if const_ || free.is_some() {
+                let utf = match encoding {
+                    Encoding::Utf8 => "Utf8",
+                    Encoding::Utf16 => "Utf16",
+                };
+                
+                return Some(Self {
+                    res: nullable_type("String", options.null_safety),
+                    res_expr: format!("{{}} == nullptr ? null : {{}}.cast<{}>().toDartString()", utf),
+                    res_post: free.map(free_statement).into_iter().collect(),
+                    free: free.filter(|free| is_native_free(free)).map(String::from),
+                    params,
+                    checks,
+                    notes,
+                });
+            }
+        }
+        
+        if !wrapped {
+            return None;
+        }
+
+        let res = res_type
+            .map(|type_| translate_type(typenames, type_, false).into())
+            .unwrap_or_else(|| "void".into());
+        
+        Some(Self {
+            res,
+            res_expr: "{}".into(),
+            res_post: Vec::new(),
+            free: None,
+            params,
+            checks,
+            notes,
+        })
+    }
+
+    /// Check that wrapper uses typed data buffers
+    pub fn has_buffers(&self) -> bool {
+        self.res.starts_with("Uint8List") || self.params.iter().any(|param| param.type_ == "Uint8List")
+    }
+
+    /// Generate wrapper method which calls raw function
+    pub fn translate(&self, coder: &mut Coder, name: &str, raw_name: &str) {
+        let required = self.params.iter()
+            .filter(|param| !param.optional && !param.hidden)
+            .map(|param| format!("{type} {name}", type = param.type_, name = param.name))
+            .collect::<Vec<_>>();
+        let optional = self.params.iter()
+            .filter(|param| param.optional)
+            .map(|param| match &param.default {
+                Some(default) => format!("{type} {name} = {default}", type = param.type_, name = param.name, default = default),
+                None => format!("{type} {name}", type = param.type_, name = param.name),
+            })
+            .collect::<Vec<_>>();
+        
+        let params = if optional.is_empty() {
+            required.join(", ")
+        } else {
+            required.into_iter()
+                .chain(std::iter::once(format!("{{{}}}", optional.join(", "))))
+                .collect::<Vec<_>>().join(", ")
+        };
+        
+        let call = format!("{name}({args})",
+                           name = raw_name,
+                           args = self.params.iter()
+                           .map(|param| param.arg.as_str())
+                           .collect::<Vec<_>>().join(", "));
+        
+        let res_post = &self.res_post;
+        let ret = |coder: &mut Coder| if self.res_expr == "{}" {
+            coder.line(if self.res == "void" {
+                format!("{};", call)
+            } else {
+                format!("return {};", call)
+            });
+        } else {
+            // native result is kept to convert it and free after conversion
+            coder.line(format!("final result = {};", call));
+            
+            let ret = format!("return {};", self.res_expr.replace("{}", "result"));
+            
+            if res_post.is_empty() {
+                coder.line(ret);
+            } else {
+                coder.block("try", |coder| {
+                    coder.line(ret);
+                });
+                coder.block("finally", |coder| {
+                    for line in res_post {
+                        coder.line(line);
+                    }
+                });
+            }
+        };
+
+        let pre = self.params.iter().flat_map(|param| &param.pre).collect::<Vec<_>>();
+        let post = self.params.iter().flat_map(|param| &param.post).collect::<Vec<_>>();
+        
+        if !self.notes.is_empty() {
+            coder.comment(self.notes.join("\n"));
+        }
+        coder.block(format!("{res} {name}({params})",
+                            res = self.res,
+                            name = name,
+                            params = params), |coder| {
+            for line in &self.checks {
+                coder.line(line);
+            }
+            for line in pre {
+                coder.line(line);
+            }
+            if post.is_empty() {
+                ret(coder);
+            } else {
+                coder.block("try", ret);
+                coder.block("finally", |coder| {
+                    for line in post {
+                        coder.line(line);
+                    }
+                });
+            }
+        });
+    }
+}
+
+/// Statement which frees native `result` using function
+pub fn free_statement(free: &str) -> String {
+    format!("{}(result.cast());", free)
+}
+
+/// Check that free function is native one (like `sqlite3_free`) but not Dart expression (like `malloc.free`)
+fn is_native_free(free: &str) -> bool {
+    !free.contains('.')
+}
+
+/// Get nullable Dart type
+///
+/// All types is nullable in legacy code.
+pub fn nullable_type(type_: &str, null_safety: bool) -> String {
+    if null_safety {
+        format!("{}?", type_)
+    } else {
+        type_.into()
+    }
+}
+
+/// Get names of pointer params which accepts NULL
+pub fn nullable_params(options: &Options, pragmas: &Pragmas, name: &str, entity: Entity) -> Vec<String> {
+    entity.get_arguments().unwrap_or_default().into_iter().enumerate()
+        .filter(|(_, arg)| arg.get_type().unwrap().get_canonical_type().get_kind() == TypeKind::Pointer)
+        .map(|(num, arg)| arg_name(num, arg))
+        .filter(|arg| {
+            let path = format!("{}.{}", name, arg);
+            pragmas.is_nullable(arg) || options.nullable_params.iter().any(|regex| regex.is_match(&path))
+        })
+        .collect()
+}
+
+/// Get documentation notes about params which accepts NULL
+pub fn nullable_notes(nullable: &[String]) -> Vec<String> {
+    nullable.iter()
+        .map(|arg| format!("The `{}` can be null.", arg))
+        .collect()
+}
+
+/// Get documentation notes about threading contract of function
+pub fn thread_notes(options: &Options, name: &str) -> Vec<String> {
+    let mut notes = Vec::new();
+    
+    if options.not_thread_safe.iter().any(|regex| regex.is_match(name)) {
+        notes.push("This function is not thread-safe.".into());
+    }
+    if options.main_thread.iter().any(|regex| regex.is_match(name)) {
+        notes.push("This function must be called on the main thread.".into());
+    }
+    
+    notes
+}
+
+/// Asynchronous wrapper method definition
+///
+/// The native function will be called in separate isolate using `Isolate.run`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AsyncDef {
+    /// Name of native function pointer field
+    pub ptr_name: String,
+    /// Dart result type
+    pub res: String,
+    /// Dart params with types
+    pub params: String,
+    /// Argument names
+    pub args: Vec<String>,
+    /// Native function type
+    pub cffi: String,
+    /// Dart function type
+    pub dart: String,
+}
+
+json_struct!(AsyncDef { ptr_name, res, params, args, cffi, dart });
+
+impl AsyncDef {
+    /// Generate asynchronous wrapper method
+    pub fn translate(&self, coder: &mut Coder, name: &str) {
+        coder.block(format!("Future<{res}> {name}({params})",
+                            res = self.res,
+                            name = name,
+                            params = self.params), |coder| {
+            // only sendable values should be captured by closure
+            coder.line(format!("final ptr = {};", self.ptr_name));
+            coder.line(format!("return Isolate.run(() => ptr.asFunction<{type}>()({args}));",
+                               type = self.dart,
+                               args = self.args.join(", ")));
+        });
+    }
+}
+
+/// Strings encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Encoding {
+    Utf8,
+    Utf16,
+}
+
+/// Get encoding and constness of string when the type is a pointer to C string
+///
+/// The wide strings can be mapped to UTF-16 only when `wchar_t` is 16-bit (on Windows).
+fn string_encoding(type_: Type<'_>) -> Option<(Encoding, bool)> {
+    use TypeKind::*;
+    
+    let type_ = type_.get_canonical_type();
+
+    if type_.get_kind() != Pointer {
+        return None;
+    }
+    
+    let type_ = type_.get_pointee_type()?;
+    let const_ = type_.is_const_qualified();
+    let type_ = type_.get_canonical_type();
+    
+    match type_.get_kind() {
+        CharS | CharU | SChar | UChar => Some((Encoding::Utf8, const_)),
+        Char16 => Some((Encoding::Utf16, const_)),
+        WChar if type_.get_sizeof() == Ok(2) => Some((Encoding::Utf16, const_)),
+        _ => None,
+    }
+}
+
+/// Check that the type is a pointer to bytes and get its mutability
+fn buffer_pointer(type_: Type<'_>) -> Option<bool> {
+    use TypeKind::*;
+    
+    let type_ = type_.get_canonical_type();
+
+    if type_.get_kind() != Pointer {
+        return None;
+    }
+    
+    let type_ = type_.get_pointee_type()?;
+    let mutable = !type_.is_const_qualified();
+    
+    match type_.get_canonical_type().get_kind() {
+        UChar | Void => Some(mutable),
+        _ => None,
+    }
+}
+
+/// Check that the type is an integer
+fn is_integer(type_: Type<'_>) -> bool {
+    use TypeKind::*;
+
+    matches!(type_.get_canonical_type().get_kind(),
+             UShort | UInt | ULong | ULongLong |
+             Short | Int | Long | LongLong)
+}
+
+/// Get integer type which is pointed by mutable pointer
+fn integer_pointee(type_: Type<'_>) -> Option<Type<'_>> {
+    let pointer = type_.get_canonical_type();
+
+    if pointer.get_kind() != TypeKind::Pointer {
+        return None;
+    }
+
+    // the pointee typedef is kept to translate it to native type
+    type_.get_pointee_type().or_else(|| pointer.get_pointee_type())
+        .filter(|type_| !type_.is_const_qualified() && is_integer(*type_))
+}
@@ -0,0 +1,25 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class item extends Struct {
+    @Int32() external int id;
+}
+/*Type aliases
+ */
+typedef ItemPtrPtr = Pointer<Pointer<item>>;
+/*Library class
+ */
+class aliases {
+    /*Functions
+     */
+    final int Function(ItemPtrPtr items, int count) list_items;
+    final void Function(ItemPtrPtr items) free_items;
+    final int Function(Pointer<item> item) first_id;
+    /*Constructor
+     */
+    aliases(DynamicLibrary dylib)
+        : list_items = dylib.lookup<NativeFunction<Int32 Function(ItemPtrPtr, Int32)>>('list_items').asFunction(),
+          free_items = dylib.lookup<NativeFunction<Void Function(ItemPtrPtr)>>('free_items').asFunction(),
+          first_id = dylib.lookup<NativeFunction<Int32 Function(Pointer<item>)>>('first_id').asFunction();
+}
+
@@ -0,0 +1,7 @@
+struct item {
+    int id;
+};
+
+int list_items(struct item **items, int count);
+void free_items(struct item **items);
+int first_id(struct item *item);
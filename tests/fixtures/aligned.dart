@@ -0,0 +1,43 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*The native struct is aligned to 64 bytes, so it should be allocated with same alignment.
+ */
+class cache_line extends Struct {
+    @Array(8) external Array<Uint8> data;
+    @Int32() external int owner;
+    @Array(52) external Array<Uint8> _padding0;
+}
+extension cache_linePointerExt on Pointer<cache_line> {
+    Array<Uint8> get data => ref.data;
+    int get owner => ref.owner;
+    set owner(int value) => ref.owner = value;
+}
+/*The native struct is aligned to 16 bytes, so it should be allocated with same alignment.
+ */
+class slot extends Struct {
+    @Int32() external int id;
+    @Array(12) external Array<Uint8> _padding0;
+    @Int32() external int value;
+    @Array(12) external Array<Uint8> _padding1;
+}
+extension slotPointerExt on Pointer<slot> {
+    int get id => ref.id;
+    set id(int value) => ref.id = value;
+    int get value => ref.value;
+    set value(int value) => ref.value = value;
+}
+/*Library class
+ */
+class aligned {
+    /*Functions
+     */
+    final void Function(Pointer<cache_line> line) flush;
+    final void Function(Pointer<slot> s) store;
+    /*Constructor
+     */
+    aligned(DynamicLibrary dylib)
+        : flush = dylib.lookup<NativeFunction<Void Function(Pointer<cache_line>)>>('flush').asFunction(),
+          store = dylib.lookup<NativeFunction<Void Function(Pointer<slot>)>>('store').asFunction();
+}
+
@@ -0,0 +1,12 @@
+struct cache_line {
+    _Alignas(64) unsigned char data[8];
+    int owner;
+};
+
+struct slot {
+    int id;
+    _Alignas(16) int value;
+};
+
+void flush(struct cache_line *line);
+void store(struct slot *s);
@@ -0,0 +1,27 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class counter extends Struct {
+    /*`hits` is atomic value which is accessed from Dart without memory ordering guarantees, so the synchronization is responsibility of caller.
+     */
+    @Int32() external int hits;
+    /*`flags` is volatile value which is accessed from Dart without memory ordering guarantees, so the synchronization is responsibility of caller.
+     */
+    @Uint32() external int flags;
+}
+/*Library class
+ */
+class atomic {
+    /*Functions
+     */
+    final void Function(Pointer<counter> counter) bump;
+    /*`value` points to atomic value which is accessed from Dart without memory ordering guarantees, so the synchronization is responsibility of caller
+     */
+    final int Function(Pointer<Int64> value) load;
+    /*Constructor
+     */
+    atomic(DynamicLibrary dylib)
+        : bump = dylib.lookup<NativeFunction<Void Function(Pointer<counter>)>>('bump').asFunction(),
+          load = dylib.lookup<NativeFunction<Int32 Function(Pointer<Int64>)>>('load').asFunction();
+}
+
@@ -0,0 +1,7 @@
+struct counter {
+    _Atomic int hits;
+    volatile unsigned int flags;
+};
+
+void bump(struct counter *counter);
+int load(_Atomic long *value);
@@ -0,0 +1,54 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+import 'dart:typed_data';
+import 'package:ffi/ffi.dart';
+
+/*Library class
+ */
+class buffers {
+    /*Functions
+     */
+    final int Function(Pointer<Uint8> data, int len) buffers_write_raw;
+    final void Function(Pointer<Uint8> buf, int size) buffers_fill_raw;
+    final Pointer<Uint8> Function(int id, Pointer<Uint64> out_len) buffers_read_raw;
+    /*Constructor
+     */
+    buffers(DynamicLibrary dylib)
+        : buffers_write_raw = dylib.lookup<NativeFunction<Int32 Function(Pointer<Uint8>, Uint64)>>('buffers_write').asFunction(),
+          buffers_fill_raw = dylib.lookup<NativeFunction<Void Function(Pointer<Uint8>, Uint64)>>('buffers_fill').asFunction(),
+          buffers_read_raw = dylib.lookup<NativeFunction<Pointer<Uint8> Function(Int32, Pointer<Uint64>)>>('buffers_read').asFunction();
+    /*Wrappers
+     */
+    int buffers_write(Uint8List data) {
+        final dataPtr = malloc<Uint8>(data.length);
+        dataPtr.asTypedList(data.length).setAll(0, data);
+        try {
+            return buffers_write_raw(dataPtr.cast(), data.length);
+        }
+        finally {
+            malloc.free(dataPtr);
+        }
+    }
+    void buffers_fill(Uint8List buf) {
+        final bufPtr = malloc<Uint8>(buf.length);
+        bufPtr.asTypedList(buf.length).setAll(0, buf);
+        try {
+            buffers_fill_raw(bufPtr.cast(), buf.length);
+        }
+        finally {
+            buf.setAll(0, bufPtr.asTypedList(buf.length));
+            malloc.free(bufPtr);
+        }
+    }
+    Uint8List? buffers_read(int id) {
+        final out_lenPtr = malloc<Uint64>();
+        try {
+            final result = buffers_read_raw(id, out_lenPtr);
+            return result == nullptr ? null : Uint8List.fromList(result.cast<Uint8>().asTypedList(out_lenPtr.value));
+        }
+        finally {
+            malloc.free(out_lenPtr);
+        }
+    }
+}
+
@@ -0,0 +1,3 @@
+int buffers_write(const unsigned char *data, unsigned long len);
+void buffers_fill(unsigned char *buf, unsigned long size);
+unsigned char *buffers_read(int id, unsigned long *out_len);
@@ -0,0 +1,55 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*Complex number with `double` parts
+ */
+class ComplexDouble extends Struct {
+    @Double() external double re;
+    @Double() external double im;
+}
+/*Complex number with `float` parts
+ */
+class ComplexFloat extends Struct {
+    @Float() external double re;
+    @Float() external double im;
+}
+/*Library class
+ */
+class complex {
+    /*Functions
+     */
+    final ComplexDouble Function(ComplexDouble a, ComplexDouble b) cmul;
+    final double Function(ComplexFloat z) cnorm;
+    /*Constructor
+     */
+    complex(DynamicLibrary dylib)
+        : cmul = dylib.lookup<NativeFunction<ComplexDouble Function(ComplexDouble, ComplexDouble)>>('cmul').asFunction(),
+          cnorm = dylib.lookup<NativeFunction<Float Function(ComplexFloat)>>('cnorm').asFunction();
+}
+/*Conversion helpers of complex number
+ */
+extension ComplexDoubleHelpers on ComplexDouble {
+    /*Set real and imaginary parts
+     */
+    void assign(double re, double im) {
+        this.re = re;
+        this.im = im;
+    }
+    /*Get real and imaginary parts as list
+     */
+    List<double> toList() => [re, im];
+}
+/*Conversion helpers of complex number
+ */
+extension ComplexFloatHelpers on ComplexFloat {
+    /*Set real and imaginary parts
+     */
+    void assign(double re, double im) {
+        this.re = re;
+        this.im = im;
+    }
+    /*Get real and imaginary parts as list
+     */
+    List<double> toList() => [re, im];
+}
+
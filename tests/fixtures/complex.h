@@ -0,0 +1,2 @@
+double _Complex cmul(double _Complex a, double _Complex b);
+float cnorm(float _Complex z);
@@ -0,0 +1,33 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class extent extends Struct {
+    @Int32() external int width;
+    @Int32() external int height;
+}
+extension extentDebug on extent {
+    /*Dump fields for debugging
+     */
+    String debugString() => 'extent(width: $width, height: $height)';
+}
+class image extends Struct {
+    external extent size;
+    external Pointer<Uint8> data;
+}
+extension imageDebug on image {
+    /*Dump fields for debugging
+     */
+    String debugString() => 'image(size: ${size.debugString()}, data: $data)';
+}
+/*Library class
+ */
+class debug {
+    /*Functions
+     */
+    final void Function(Pointer<image> image) draw;
+    /*Constructor
+     */
+    debug(DynamicLibrary dylib)
+        : draw = dylib.lookup<NativeFunction<Void Function(Pointer<image>)>>('draw').asFunction();
+}
+
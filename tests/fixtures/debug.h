@@ -0,0 +1,11 @@
+struct extent {
+    int width;
+    int height;
+};
+
+struct image {
+    struct extent size;
+    unsigned char *data;
+};
+
+void draw(struct image *image);
@@ -0,0 +1,58 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*Opaque types
+ */
+class examples_ctx extends Opaque {}
+/*Library class
+ */
+class examples {
+    /*Functions
+     */
+    /**
+     * Create context.
+     *
+     * @code
+     * struct examples_ctx *ctx = examples_new(NULL);
+     * examples_run(ctx, 42);
+     * printf("done\n");
+     * examples_free(ctx);
+     * @endcode
+     */
+    /*Usage example translated from C (`lib` is instance of `examples`):
+     ```dart
+     final ctx = lib.examples_new(nullptr);
+     lib.examples_run(ctx, 42);
+     // printf("done\n");
+     lib.examples_free(ctx);
+     ```
+     */
+    final Pointer<examples_ctx> Function(Pointer<Int8> name) examples_new;
+    /**
+     * Run context.
+     *
+     * ```
+     * int rc = examples_run(ctx, 1);
+     * if (rc != 0) {
+     *     examples_free(ctx);
+     * }
+     * ```
+     */
+    /*Usage example translated from C (`lib` is instance of `examples`):
+     ```dart
+     final rc = lib.examples_run(ctx, 1);
+     // if (rc != 0) {
+         lib.examples_free(ctx);
+     // }
+     ```
+     */
+    final int Function(Pointer<examples_ctx> ctx, int value) examples_run;
+    final void Function(Pointer<examples_ctx> ctx) examples_free;
+    /*Constructor
+     */
+    examples(DynamicLibrary dylib)
+        : examples_new = dylib.lookup<NativeFunction<Pointer<examples_ctx> Function(Pointer<Int8>)>>('examples_new').asFunction(),
+          examples_run = dylib.lookup<NativeFunction<Int32 Function(Pointer<examples_ctx>, Int32)>>('examples_run').asFunction(),
+          examples_free = dylib.lookup<NativeFunction<Void Function(Pointer<examples_ctx>)>>('examples_free').asFunction();
+}
+
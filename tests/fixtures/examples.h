@@ -0,0 +1,27 @@
+struct examples_ctx;
+
+/**
+ * Create context.
+ *
+ * @code
+ * struct examples_ctx *ctx = examples_new(NULL);
+ * examples_run(ctx, 42);
+ * printf("done\n");
+ * examples_free(ctx);
+ * @endcode
+ */
+struct examples_ctx *examples_new(const char *name);
+
+/**
+ * Run context.
+ *
+ * ```
+ * int rc = examples_run(ctx, 1);
+ * if (rc != 0) {
+ *     examples_free(ctx);
+ * }
+ * ```
+ */
+int examples_run(struct examples_ctx *ctx, int value);
+
+void examples_free(struct examples_ctx *ctx);
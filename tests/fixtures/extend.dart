@@ -0,0 +1,19 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+part of 'shapes.dart';
+
+class size extends Struct {
+    @Int32() external int w;
+    @Int32() external int h;
+}
+/*Library class
+ */
+class shapes_ext {
+    /*Functions
+     */
+    final int Function(Pointer<point> origin, Pointer<size> extent) area;
+    /*Constructor
+     */
+    shapes_ext(DynamicLibrary dylib)
+        : area = dylib.lookup<NativeFunction<Int32 Function(Pointer<point>, Pointer<size>)>>('area').asFunction();
+}
+
@@ -0,0 +1,13 @@
+struct point {
+    int x;
+    int y;
+};
+
+struct size {
+    int w;
+    int h;
+};
+
+int distance(struct point *a, struct point *b);
+
+int area(struct point *origin, struct size *extent);
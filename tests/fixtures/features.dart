@@ -0,0 +1,25 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class mode {
+    static const BASE = 0;
+    /*Only available with: FEATURE_X=1.
+     */
+    static const X = 1;
+}
+/*Library class
+ */
+class features {
+    /*Functions
+     */
+    final int Function() base_call;
+    /*Only available with: FEATURE_X=1.
+     */
+    final int Function(int value)? feature_call;
+    /*Constructor
+     */
+    features(DynamicLibrary dylib)
+        : base_call = dylib.lookup<NativeFunction<Int32 Function()>>('base_call').asFunction(),
+          feature_call = dylib.providesSymbol('feature_call') ? dylib.lookup<NativeFunction<Int32 Function(Int32)>>('feature_call').asFunction() : null;
+}
+
@@ -0,0 +1,12 @@
+enum mode {
+    MODE_BASE,
+#if FEATURE_X
+    MODE_X,
+#endif
+};
+
+int base_call(void);
+
+#if FEATURE_X
+int feature_call(int value);
+#endif
@@ -0,0 +1,29 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*Native function types
+ */
+/** Called on each event
+ */
+typedef event_cb = Void Function(Int32);
+/*Library class
+ */
+class functions {
+    /*Callbacks
+     */
+    final Pointer<NativeFunction<event_cb>> subscribe_cb;
+    /*Functions
+     */
+    final int Function(int a, int b) add;
+    final void Function() reset;
+    final int Function(int in_, int arg1) scale;
+    final void Function(Pointer<NativeFunction<event_cb>> cb) subscribe;
+    /*Constructor
+     */
+    functions(DynamicLibrary dylib, this.subscribe_cb)
+        : add = dylib.lookup<NativeFunction<Int32 Function(Int32, Int32)>>('add').asFunction(),
+          reset = dylib.lookup<NativeFunction<Void Function()>>('reset').asFunction(),
+          scale = dylib.lookup<NativeFunction<Int32 Function(Int32, Int32)>>('scale').asFunction(),
+          subscribe = dylib.lookup<NativeFunction<Void Function(Pointer<NativeFunction<event_cb>>)>>('subscribe').asFunction();
+}
+
@@ -0,0 +1,10 @@
+int add(int a, int b);
+
+void reset(void);
+
+int scale(int in, int);
+
+/** Called on each event */
+typedef void (*event_cb)(int code);
+
+void subscribe(event_cb cb);
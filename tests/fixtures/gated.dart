@@ -0,0 +1,37 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class GatedAudio_1 extends Struct {
+    @Int32() external int rate;
+}
+/*Library class
+ */
+class gated {
+    /*Functions
+     */
+    final int Function() core_init;
+    final void Function(Pointer<GatedAudio_1> config) core_config;
+    /*Optional features
+     */
+    final GatedAudio? audio;
+    /*Constructor
+     */
+    gated(DynamicLibrary dylib)
+        : core_init = dylib.lookup<NativeFunction<Int32 Function()>>('core_init').asFunction(),
+          core_config = dylib.lookup<NativeFunction<Void Function(Pointer<GatedAudio_1>)>>('core_config').asFunction(),
+          audio = dylib.providesSymbol('audio_play') && dylib.providesSymbol('audio_stop') ? GatedAudio(dylib) : null;
+}
+/*Library class
+ */
+class GatedAudio {
+    /*Functions
+     */
+    final int Function(int id) audio_play;
+    final void Function() audio_stop;
+    /*Constructor
+     */
+    GatedAudio(DynamicLibrary dylib)
+        : audio_play = dylib.lookup<NativeFunction<Int32 Function(Int32)>>('audio_play').asFunction(),
+          audio_stop = dylib.lookup<NativeFunction<Void Function()>>('audio_stop').asFunction();
+}
+
@@ -0,0 +1,8 @@
+struct GatedAudio {
+    int rate;
+};
+
+int core_init(void);
+void core_config(struct GatedAudio *config);
+int audio_play(int id);
+void audio_stop(void);
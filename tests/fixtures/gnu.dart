@@ -0,0 +1,25 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class level {
+    static const LOW = 0;
+    static const HIGH = 1;
+}
+/*Library class
+ */
+class gnu {
+    /*Functions
+     */
+    final int Function(int level) set_level;
+    final int Function(int value) twice;
+    final int Function(int value) widen;
+    final Pointer<Uint64> Function() count_ptr;
+    /*Constructor
+     */
+    gnu(DynamicLibrary dylib)
+        : set_level = dylib.lookup<NativeFunction<Int32 Function(Uint32)>>('set_level').asFunction(),
+          twice = dylib.lookup<NativeFunction<Int32 Function(Int32)>>('twice').asFunction(),
+          widen = dylib.lookup<NativeFunction<Int64 Function(Int32)>>('widen').asFunction(),
+          count_ptr = dylib.lookup<NativeFunction<Pointer<Uint64> Function()>>('count_ptr').asFunction();
+}
+
@@ -0,0 +1,13 @@
+typedef unsigned long size_t;
+
+enum level {
+    LEVEL_LOW,
+    LEVEL_HIGH,
+};
+
+__extension__ typedef long long wide_t;
+
+int set_level(enum level level);
+typeof(int) twice(typeof(int) value);
+wide_t widen(int value);
+size_t * _Nonnull count_ptr(void);
@@ -0,0 +1,26 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*Library class
+ */
+class groups {
+    /*Functions
+     */
+    final int Function() lib_init;
+    // ── Audio ──
+    final int Function() audio_open;
+    final void Function() audio_close;
+    // ── Video ──
+    final int Function() video_open;
+    // ── Misc ──
+    final int Function() misc_version;
+    /*Constructor
+     */
+    groups(DynamicLibrary dylib)
+        : lib_init = dylib.lookup<NativeFunction<Int32 Function()>>('lib_init').asFunction(),
+          audio_open = dylib.lookup<NativeFunction<Int32 Function()>>('audio_open').asFunction(),
+          audio_close = dylib.lookup<NativeFunction<Void Function()>>('audio_close').asFunction(),
+          video_open = dylib.lookup<NativeFunction<Int32 Function()>>('video_open').asFunction(),
+          misc_version = dylib.lookup<NativeFunction<Int32 Function()>>('misc_version').asFunction();
+}
+
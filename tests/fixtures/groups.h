@@ -0,0 +1,11 @@
+int lib_init(void);
+
+/* ---- Audio ---- */
+
+int audio_open(void);
+void audio_close(void);
+
+// ==== Video ====
+
+int video_open(void);
+int misc_version(void);
@@ -0,0 +1,18 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+import 'package:ffi/ffi.dart';
+import 'package:meta/meta.dart';
+import 'src/helpers.dart';
+
+/*Library class
+ */
+class imports {
+    /*Functions
+     */
+    final int Function() answer;
+    /*Constructor
+     */
+    imports(DynamicLibrary dylib)
+        : answer = dylib.lookup<NativeFunction<Int32 Function()>>('answer').asFunction();
+}
+
@@ -0,0 +1,32 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class point extends Struct {
+    @Int32() external int x;
+    @Int32() external int y;
+}
+class color {
+    static const RED = 0;
+    static const GREEN = 1;
+}
+/*Opaque types
+ */
+class handle extends Opaque {}
+class session_t extends Opaque {}
+/*Library class
+ */
+class incomplete {
+    /*Functions
+     */
+    final Pointer<handle> Function(Pointer<Int8> path) open_handle;
+    final Pointer<session_t> Function(Pointer<handle> handle) start;
+    final void Function(Pointer<session_t> session, Pointer<point> point) move_to;
+    // TODO(c4dart): could not bind `attach`: `struct handle` is incomplete type which can be passed by pointer only
+    /*Constructor
+     */
+    incomplete(DynamicLibrary dylib)
+        : open_handle = dylib.lookup<NativeFunction<Pointer<handle> Function(Pointer<Int8>)>>('open_handle').asFunction(),
+          start = dylib.lookup<NativeFunction<Pointer<session_t> Function(Pointer<handle>)>>('start').asFunction(),
+          move_to = dylib.lookup<NativeFunction<Void Function(Pointer<session_t>, Pointer<point>)>>('move_to').asFunction();
+}
+
@@ -0,0 +1,20 @@
+struct handle;
+typedef struct session session_t;
+struct point;
+enum color;
+enum unknown;
+
+struct handle *open_handle(const char *path);
+session_t *start(struct handle *handle);
+void move_to(session_t *session, const struct point *point);
+void attach(struct handle handle);
+
+struct point {
+    int x;
+    int y;
+};
+
+enum color {
+    COLOR_RED,
+    COLOR_GREEN,
+};
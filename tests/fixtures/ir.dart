@@ -0,0 +1,24 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class point extends Struct {
+    @Int32() external int x;
+    @Int32() external int y;
+}
+/*Color of point
+ */
+class color {
+    static const RED = 0;
+}
+/*Library class
+ */
+class points {
+    /*Functions
+     */
+    final int Function(Pointer<point> a, Pointer<point> b) distance;
+    /*Constructor
+     */
+    points(DynamicLibrary dylib)
+        : distance = dylib.lookup<NativeFunction<Int32 Function(Pointer<point>, Pointer<point>)>>('distance').asFunction();
+}
+
@@ -0,0 +1,51 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class sample extends Struct {
+    @Int8() external int tag;
+    @Double() external double value;
+    @Float() external double scale;
+    external Pointer<Int32> data;
+    @Array(2) external Array<Int16> dims;
+    @Uint32() external int flags;
+}
+extension sampleLayout on sample {
+    /*Check that layout of struct matches native one (asserted in debug mode only)
+     */
+    static void validateLayout(Allocator allocator) {
+        assert(sizeOf<sample>() == 40, 'Size of `sample` is ${sizeOf<sample>()} but 40 expected');
+        final bytes = allocator<Uint8>(sizeOf<sample>());
+        final value = bytes.cast<sample>().ref;
+        assert(_fieldOffset(bytes, sizeOf<sample>(), () => value.tag = -1) == 0, 'Offset of `sample.tag` differs from 0');
+        assert(_fieldOffset(bytes, sizeOf<sample>(), () => value.value = -1.0000000000000002) == 8, 'Offset of `sample.value` differs from 8');
+        assert(_fieldOffset(bytes, sizeOf<sample>(), () => value.scale = -1.0000001192092896) == 16, 'Offset of `sample.scale` differs from 16');
+        assert(_fieldOffset(bytes, sizeOf<sample>(), () => value.data = Pointer.fromAddress(-1)) == 24, 'Offset of `sample.data` differs from 24');
+        allocator.free(bytes);
+    }
+}
+/*Library class
+ */
+class layout {
+    /*Functions
+     */
+    final void Function(Pointer<sample> sample) process;
+    /*Constructor
+     */
+    layout(DynamicLibrary dylib)
+        : process = dylib.lookup<NativeFunction<Void Function(Pointer<sample>)>>('process').asFunction();
+}
+/*Find offset of struct field by writing it into zeroed bytes
+ */
+int _fieldOffset(Pointer<Uint8> bytes, int size, void Function() write) {
+    for (var i = 0; i < size; i++) {
+        bytes[i] = 0;
+    }
+    write();
+    for (var i = 0; i < size; i++) {
+        if (bytes[i] != 0) {
+            return i;
+        }
+    }
+    return -1;
+}
+
@@ -0,0 +1,10 @@
+struct sample {
+    char tag;
+    double value;
+    float scale;
+    int *data;
+    short dims[2];
+    unsigned flags : 3;
+};
+
+void process(struct sample *sample);
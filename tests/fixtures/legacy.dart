@@ -0,0 +1,20 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+// @dart=2.9
+import 'dart:ffi';
+
+class point extends Struct {
+    @Int32() int x;
+    @Int32() int y;
+}
+/*Library class
+ */
+class legacy {
+    /*Functions
+     */
+    final int Function(Pointer<point> a, Pointer<point> b) distance;
+    /*Constructor
+     */
+    legacy(DynamicLibrary dylib)
+        : distance = dylib.lookup<NativeFunction<Int32 Function(Pointer<point>, Pointer<point>)>>('distance').asFunction();
+}
+
@@ -0,0 +1,30 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class point extends Struct {
+    @Int32() external int x;
+    @Int32() external int y;
+}
+/*Library class
+ */
+class first {
+    /*Functions
+     */
+    final int Function(Pointer<point> a, Pointer<point> b) first_distance;
+    /*Constructor
+     */
+    first(DynamicLibrary dylib)
+        : first_distance = dylib.lookup<NativeFunction<Int32 Function(Pointer<point>, Pointer<point>)>>('first_distance').asFunction();
+}
+/*Library class
+ */
+class second {
+    /*Functions
+     */
+    final int Function(Pointer<point> a, Pointer<point> b) second_distance;
+    /*Constructor
+     */
+    second(DynamicLibrary dylib)
+        : second_distance = dylib.lookup<NativeFunction<Int32 Function(Pointer<point>, Pointer<point>)>>('second_distance').asFunction();
+}
+
@@ -0,0 +1,8 @@
+struct point {
+    int x;
+    int y;
+};
+
+int first_distance(struct point *a, struct point *b);
+
+int second_distance(struct point *a, struct point *b);
@@ -0,0 +1,16 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+// ignore_for_file: non_constant_identifier_names, camel_case_types
+import 'dart:ffi';
+
+/*Library class
+ */
+class lints {
+    /*Functions
+     */
+    final int Function() GetAnswer;
+    /*Constructor
+     */
+    lints(DynamicLibrary dylib)
+        : GetAnswer = dylib.lookup<NativeFunction<Int32 Function()>>('GetAnswer').asFunction();
+}
+
@@ -0,0 +1 @@
+int GetAnswer(void);
@@ -0,0 +1,19 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+const LIT_SIZE = 16;
+const LIT_DOUBLE = 32;
+const LIT_MASK = 4294967295;
+const LIT_HIGH = 1 << 31;
+/*Library class
+ */
+class literals {
+    /*Functions
+     */
+    final int Function() lit_size;
+    /*Constructor
+     */
+    literals(DynamicLibrary dylib)
+        : lit_size = dylib.lookup<NativeFunction<Int32 Function()>>('lit_size').asFunction();
+}
+
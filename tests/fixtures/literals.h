@@ -0,0 +1,15 @@
+struct lit_point {
+    int x;
+    int y;
+};
+
+#define LIT_ORIGIN ((struct lit_point){0, 0})
+#define LIT_TWO ({ int one = 1; one * 2; })
+#define LIT_INIT { 1, 2 }
+#define LIT_SIZE 16
+#define LIT_DOUBLE (LIT_SIZE * 2)
+#define LIT_MASK (~0u)
+#define LIT_WIDE_MASK (~0ul)
+#define LIT_HIGH (1u << 31)
+
+int lit_size(void);
@@ -0,0 +1,27 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class stat_info extends Struct {
+    @Int64() external int size;
+    @Uint32() external int mode;
+}
+/*Library class
+ */
+class files {
+    /*Functions
+     */
+    final int Function(Pointer<Int8> path) file_size;
+    /*Only available on: linux.
+     */
+    final int Function(Pointer<Int8> path)? file_inode;
+    /*Only available on: windows.
+     */
+    final int Function(Pointer<Int8> path)? file_attributes;
+    /*Constructor
+     */
+    files(DynamicLibrary dylib)
+        : file_size = dylib.lookup<NativeFunction<Int64 Function(Pointer<Int8>)>>('file_size').asFunction(),
+          file_inode = dylib.providesSymbol('file_inode') ? dylib.lookup<NativeFunction<Uint64 Function(Pointer<Int8>)>>('file_inode').asFunction() : null,
+          file_attributes = dylib.providesSymbol('file_attributes') ? dylib.lookup<NativeFunction<Uint32 Function(Pointer<Int8>)>>('file_attributes').asFunction() : null;
+}
+
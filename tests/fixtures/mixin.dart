@@ -0,0 +1,14 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*Library mixin
+ */
+mixin calc {
+    /*Library which functions is looked up in
+     */
+    DynamicLibrary get calc_dylib;
+    /*Functions
+     */
+    late final int Function(int a, int b) add = calc_dylib.lookup<NativeFunction<Int32 Function(Int32, Int32)>>('add').asFunction();
+}
+
@@ -0,0 +1,10 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*Library bindings
+ */
+/*Functions
+ */
+@Native<Int32 Function(Int32, Int32)>(symbol: 'add')
+external int add(int a, int b);
+
@@ -0,0 +1,34 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+import 'package:ffi/ffi.dart';
+
+/*Library class
+ */
+class nullable {
+    /*Functions
+     */
+    /*The `ctx` can be null.
+     */
+    final int Function(Pointer<Void> ctx, Pointer<Int32> out) process_raw;
+    /*The `opts` can be null.
+     */
+    final int Function(int id, Pointer<Void> opts) open_raw;
+    /*Constructor
+     */
+    nullable(DynamicLibrary dylib)
+        : process_raw = dylib.lookup<NativeFunction<Int32 Function(Pointer<Void>, Pointer<Int32>)>>('process').asFunction(),
+          open_raw = dylib.lookup<NativeFunction<Int32 Function(Int32, Pointer<Void>)>>('open').asFunction();
+    /*Wrappers
+     */
+    /*The `ctx` can be null.
+     */
+    int process(Pointer<Void>? ctx, Pointer<Int32> out) {
+        return process_raw(ctx ?? nullptr, out);
+    }
+    /*The `opts` can be null.
+     */
+    int open(int id, Pointer<Void>? opts) {
+        return open_raw(id, opts ?? nullptr);
+    }
+}
+
@@ -0,0 +1,3 @@
+/* c4dart: nullable(ctx) */
+int process(void *ctx, int *out);
+int open(int id, void *opts);
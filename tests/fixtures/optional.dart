@@ -0,0 +1,21 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+import 'package:ffi/ffi.dart';
+
+/*Library class
+ */
+class optional {
+    /*Functions
+     */
+    final int Function(int id, Pointer<Void> data, int flags) start_raw;
+    /*Constructor
+     */
+    optional(DynamicLibrary dylib)
+        : start_raw = dylib.lookup<NativeFunction<Int32 Function(Int32, Pointer<Void>, Int32)>>('start').asFunction();
+    /*Wrappers
+     */
+    int start(int id, {Pointer<Void>? data, int flags = 0}) {
+        return start_raw(id, data ?? nullptr, flags);
+    }
+}
+
@@ -0,0 +1 @@
+int start(int id, void *data, int flags);
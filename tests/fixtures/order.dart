@@ -0,0 +1,27 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class box extends Struct {
+    @Int32() external int w;
+}
+class mode {
+    static const FAST = 1;
+    static const SLOW = 0;
+}
+class vec extends Struct {
+    @Int32() external int x;
+}
+/*Library class
+ */
+class order {
+    /*Functions
+     */
+    final void Function(Pointer<box> b) area;
+    final int Function(Pointer<vec> v) zoom;
+    /*Constructor
+     */
+    order(DynamicLibrary dylib)
+        : area = dylib.lookup<NativeFunction<Void Function(Pointer<box>)>>('area').asFunction(),
+          zoom = dylib.lookup<NativeFunction<Int32 Function(Pointer<vec>)>>('zoom').asFunction();
+}
+
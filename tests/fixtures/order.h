@@ -0,0 +1,15 @@
+enum mode {
+    MODE_SLOW,
+    MODE_FAST,
+};
+
+struct vec {
+    int x;
+};
+
+struct box {
+    int w;
+};
+
+int zoom(struct vec *v);
+void area(struct box *b);
@@ -0,0 +1,47 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+import 'package:ffi/ffi.dart';
+
+/*Library class
+ */
+class owned {
+    /*Functions
+     */
+    final Pointer<Int8> Function(Pointer<Int8> s) dup_raw;
+    final Pointer<Int8> Function(Pointer<Int8> s) upper_raw;
+    final void Function(Pointer<Void> ptr) release;
+    /*Constructor
+     */
+    owned(DynamicLibrary dylib)
+        : dup_raw = dylib.lookup<NativeFunction<Pointer<Int8> Function(Pointer<Int8>)>>('str_dup').asFunction(),
+          upper_raw = dylib.lookup<NativeFunction<Pointer<Int8> Function(Pointer<Int8>)>>('str_upper').asFunction(),
+          release = dylib.lookup<NativeFunction<Void Function(Pointer<Void>)>>('str_release').asFunction();
+    /*Wrappers
+     */
+    String? dup(String s) {
+        final sPtr = s.toNativeUtf8();
+        try {
+            final result = dup_raw(sPtr.cast<Int8>());
+            try {
+                return result == nullptr ? null : result.cast<Utf8>().toDartString();
+            }
+            finally {
+                release(result.cast());
+            }
+        }
+        finally {
+            malloc.free(sPtr);
+        }
+    }
+    String? upper(String s) {
+        final sPtr = s.toNativeUtf8();
+        try {
+            final result = upper_raw(sPtr.cast<Int8>());
+            return result == nullptr ? null : result.cast<Utf8>().toDartString();
+        }
+        finally {
+            malloc.free(sPtr);
+        }
+    }
+}
+
@@ -0,0 +1,3 @@
+char *str_dup(const char *s);
+char *str_upper(const char *s);
+void str_release(void *ptr);
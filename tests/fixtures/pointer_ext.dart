@@ -0,0 +1,43 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class extent extends Struct {
+    @Int32() external int width;
+    @Int32() external int height;
+}
+extension extentPointerExt on Pointer<extent> {
+    /*Allocate struct using allocator
+     */
+    static Pointer<extent> allocate(Allocator allocator) => allocator<extent>();
+    int get width => ref.width;
+    set width(int value) => ref.width = value;
+    int get height => ref.height;
+    set height(int value) => ref.height = value;
+}
+class image extends Struct {
+    external extent size;
+    external Pointer<Uint8> data;
+    @Int32() external int ref;
+    @Array(4) external Array<Float> matrix;
+}
+extension imagePointerExt on Pointer<image> {
+    /*Allocate struct using allocator
+     */
+    static Pointer<image> allocate(Allocator allocator) => allocator<image>();
+    extent get size => ref.size;
+    Pointer<Uint8> get data => ref.data;
+    set data(Pointer<Uint8> value) => ref.data = value;
+    Array<Float> get matrix => ref.matrix;
+}
+/*Library class
+ */
+class pointer_ext {
+    /*Functions
+     */
+    final void Function(Pointer<image> image) draw;
+    /*Constructor
+     */
+    pointer_ext(DynamicLibrary dylib)
+        : draw = dylib.lookup<NativeFunction<Void Function(Pointer<image>)>>('draw').asFunction();
+}
+
@@ -0,0 +1,13 @@
+struct extent {
+    int width;
+    int height;
+};
+
+struct image {
+    struct extent size;
+    unsigned char *data;
+    int ref;
+    float matrix[4];
+};
+
+void draw(struct image *image);
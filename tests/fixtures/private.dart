@@ -0,0 +1,18 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*Library class
+ */
+class calc {
+    /*Functions
+     */
+    final int Function(int a, int b) _add;
+    /*Constructor
+     */
+    calc(DynamicLibrary dylib)
+        : _add = dylib.lookup<NativeFunction<Int32 Function(Int32, Int32)>>('add').asFunction();
+    /*Forwarding functions
+     */
+    int add(int a, int b) => _add(a, b);
+}
+
@@ -0,0 +1,27 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+import 'package:ffi/ffi.dart';
+
+/*Library class
+ */
+class redeclared {
+    /*Functions
+     */
+    final int Function(Pointer<Int8> s) _count;
+    /*Constructor
+     */
+    redeclared(DynamicLibrary dylib)
+        : _count = dylib.lookup<NativeFunction<Int32 Function(Pointer<Int8>)>>('count').asFunction();
+    /*Wrappers
+     */
+    int count(String s) {
+        final sPtr = s.toNativeUtf8();
+        try {
+            return _count(sPtr.cast<Int8>());
+        }
+        finally {
+            malloc.free(sPtr);
+        }
+    }
+}
+
@@ -0,0 +1,2 @@
+int count();
+int count(const char *s);
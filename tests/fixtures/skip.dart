@@ -0,0 +1,17 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*Library class
+ */
+class skip {
+    /*Functions
+     */
+    final int Function(Pointer<Int8> path) open_device;
+    // TODO(c4dart): could not bind `legacy_reset`: deprecated by vendor
+    // TODO(c4dart): could not bind `debug_dump`: excluded by configuration
+    /*Constructor
+     */
+    skip(DynamicLibrary dylib)
+        : open_device = dylib.lookup<NativeFunction<Int32 Function(Pointer<Int8>)>>('open_device').asFunction();
+}
+
@@ -0,0 +1,3 @@
+int open_device(const char *path);
+void legacy_reset(void);
+int debug_dump(int level);
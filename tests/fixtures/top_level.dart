@@ -0,0 +1,23 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*Native function types
+ */
+typedef event_cb = Void Function(Int32);
+/*Library bindings
+ */
+/*Callbacks
+ */
+late final Pointer<NativeFunction<event_cb>> subscribe_cb;
+/*Functions
+ */
+late final int Function(int a, int b) add;
+late final void Function(Pointer<NativeFunction<event_cb>> cb) subscribe;
+/*Initialize bindings
+ */
+void init(DynamicLibrary dylib, Pointer<NativeFunction<event_cb>> subscribe_cb_) {
+    subscribe_cb = subscribe_cb_;
+    add = dylib.lookup<NativeFunction<Int32 Function(Int32, Int32)>>('add').asFunction();
+    subscribe = dylib.lookup<NativeFunction<Void Function(Pointer<NativeFunction<event_cb>>)>>('subscribe').asFunction();
+}
+
@@ -0,0 +1,5 @@
+int add(int a, int b);
+
+typedef void (*event_cb)(int code);
+
+void subscribe(event_cb cb);
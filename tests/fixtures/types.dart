@@ -0,0 +1,55 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+class point extends Struct {
+    @Int32() external int x;
+    @Int32() external int y;
+}
+class color {
+    static const RED = 0;
+    static const GREEN = 2;
+}
+const String TYPES_VERSION = '1.2.3';
+const String TYPES_PATH = '/usr/lib';
+const String TYPES_GREETING = 'Hello, "\$USER"\n';
+const TYPES_SEP = 47;
+const TYPES_BASE = 0x10;
+const TYPES_NEXT = 17;
+const TYPES_VERSION_MAJOR = 1;
+const TYPES_VERSION_MINOR = 2;
+/*Library version
+ */
+class typesVersion {
+    static const major = 1;
+    static const minor = 2;
+    static const string = '1.2.3';
+}
+/*Function-like macros (cannot be bound directly):
+ TYPES_MAX(a, b)
+ */
+/*Library class
+ */
+class types {
+    /*Functions
+     */
+    final int Function(Pointer<point> a, Pointer<point> b) distance;
+    final int Function() types_version;
+    /*Constructor
+     */
+    types(DynamicLibrary dylib)
+        : distance = dylib.lookup<NativeFunction<Int32 Function(Pointer<point>, Pointer<point>)>>('distance').asFunction(),
+          types_version = dylib.lookup<NativeFunction<Int32 Function()>>('types_version').asFunction() {
+        _checkAbi();
+    }
+    /*Check that loaded library is compatible
+     */
+    void _checkAbi() {
+        if (sizeOf<point>() != 8) {
+            throw StateError('ABI mismatch: sizeOf<point>() is ${sizeOf<point>()} but ${8} expected');
+        }
+        if (types_version() != typesVersion.major) {
+            throw StateError('ABI mismatch: types_version() is ${types_version()} but ${typesVersion.major} expected');
+        }
+    }
+}
+
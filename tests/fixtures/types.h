@@ -0,0 +1,22 @@
+struct point {
+    int x;
+    int y;
+};
+
+enum color {
+    COLOR_RED,
+    COLOR_GREEN = 2,
+};
+
+int distance(struct point *a, struct point *b);
+int types_version(void);
+
+#define TYPES_VERSION "1.2.3"
+#define TYPES_PATH "/usr/" "lib"
+#define TYPES_GREETING "Hello, \"$USER\"\n"
+#define TYPES_SEP '/'
+#define TYPES_BASE 0x10
+#define TYPES_NEXT (TYPES_BASE + 1)
+#define TYPES_VERSION_MAJOR 1
+#define TYPES_VERSION_MINOR (TYPES_NEXT - 15)
+#define TYPES_MAX(a, b) ((a) > (b) ? (a) : (b))
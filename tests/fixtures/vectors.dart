@@ -0,0 +1,39 @@
+/* This file was generated using c4dart v0.1.0 tool and should not be modified manually. */
+import 'dart:ffi';
+
+/*Vector (SIMD) value of 16 bytes
+ 
+ The native struct is aligned to 16 bytes, so it should be allocated with same alignment.
+ */
+class Vector16 extends Struct {
+    @Array(16) external Array<Uint8> bytes;
+}
+/*The native struct is aligned to 16 bytes, so it should be allocated with same alignment.
+ */
+class particle extends Struct {
+    external Vector16 position;
+    @Int32() external int id;
+    @Array(12) external Array<Uint8> _padding0;
+}
+/*The native struct is aligned to 16 bytes, so it should be allocated with same alignment.
+ */
+class sample extends Struct {
+    @Int8() external int flag;
+    @Array(15) external Array<Uint8> _padding0;
+    external Vector16 value;
+}
+/*Library class
+ */
+class vectors {
+    /*Functions
+     */
+    final void Function(Pointer<particle> p, Pointer<Vector16> delta) move;
+    final void Function(Pointer<sample> s) push;
+    // TODO(c4dart): could not bind `sum`: vector types are not supported by dart:ffi
+    /*Constructor
+     */
+    vectors(DynamicLibrary dylib)
+        : move = dylib.lookup<NativeFunction<Void Function(Pointer<particle>, Pointer<Vector16>)>>('move').asFunction(),
+          push = dylib.lookup<NativeFunction<Void Function(Pointer<sample>)>>('push').asFunction();
+}
+
@@ -0,0 +1,15 @@
+typedef float v4f __attribute__((vector_size(16)));
+
+struct particle {
+    v4f position;
+    int id;
+};
+
+struct sample {
+    char flag;
+    v4f value;
+};
+
+void move(struct particle *p, v4f *delta);
+void push(struct sample *s);
+v4f sum(v4f a, v4f b);
@@ -0,0 +1,319 @@
+use std::path::Path;
+use regex::Regex;
+use c4dart::{Options, BindingShape, MemberVisibility, MemberOrder, check_golden};
+
+fn golden(name: &str, options: Options) {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures");
+    
+    if let Err(error) = check_golden(options,
+                                     &fixtures.join(format!("{}.h", name)),
+                                     &fixtures.join(format!("{}.dart", name))) {
+        panic!("{}", error);
+    }
+}
+
+fn options(class_name: &str) -> Options {
+    Options {
+        detect_isystem: false,
+        ..Options::new(class_name)
+    }
+}
+
+#[test]
+fn functions() {
+    golden("functions", options("functions"));
+}
+
+#[test]
+fn types() {
+    golden("types", Options {
+        macro_filter: Some(Regex::new("^TYPES_").unwrap()),
+        version_macros: vec![
+            ("major".into(), "TYPES_VERSION_MAJOR".into()),
+            ("minor".into(), "TYPES_VERSION_MINOR".into()),
+            ("string".into(), "TYPES_VERSION".into()),
+        ],
+        abi_structs: vec![Regex::new("^point$").unwrap()],
+        abi_version: Some(("types_version".into(), "major".into())),
+        ..options("types")
+    });
+}
+
+#[test]
+fn libraries() {
+    golden("libraries", Options {
+        function_filter: Some(Regex::new("^first_").unwrap()),
+        libraries: vec![("second".into(), Regex::new("^second_").unwrap())],
+        ..options("first")
+    });
+}
+
+#[test]
+fn extend() {
+    golden("extend", Options {
+        known_symbols: vec!["point".to_string(), "distance".to_string()].into_iter().collect(),
+        part_of: Some("shapes.dart".into()),
+        ..options("shapes_ext")
+    });
+}
+
+#[test]
+fn top_level() {
+    golden("top_level", Options {
+        binding_shape: BindingShape::TopLevel,
+        ..options("top_level")
+    });
+}
+
+#[test]
+fn native() {
+    golden("native", Options {
+        binding_shape: BindingShape::NativeAnnotations,
+        ..options("native")
+    });
+}
+
+#[test]
+fn mixin() {
+    golden("mixin", Options {
+        binding_shape: BindingShape::Mixin,
+        ..options("calc")
+    });
+}
+
+#[test]
+fn private() {
+    golden("private", Options {
+        member_visibility: MemberVisibility::Private,
+        ..options("calc")
+    });
+}
+
+#[test]
+fn redeclared() {
+    // the public name of private function is kept when declaration without prototype is replaced
+    golden("redeclared", Options {
+        member_visibility: MemberVisibility::Private,
+        wrappers: true,
+        ..options("redeclared")
+    });
+}
+
+#[test]
+fn optional() {
+    golden("optional", Options {
+        wrappers: true,
+        optional_params: vec![
+            (Regex::new(r"^start\.data$").unwrap(), None),
+            (Regex::new(r"^start\.flags$").unwrap(), Some("0".into())),
+        ],
+        ..options("optional")
+    });
+}
+
+#[test]
+fn buffers() {
+    golden("buffers", Options {
+        target: Some("x86_64-unknown-linux-gnu".into()),
+        wrappers: true,
+        ..options("buffers")
+    });
+}
+
+#[test]
+fn owned() {
+    // the free functions is called by bound names and missing ones is reported
+    golden("owned", Options {
+        wrappers: true,
+        names_match: Regex::new("^str_").unwrap(),
+        names_replace: "".into(),
+        owned_strings: vec![
+            (Regex::new("^str_dup$").unwrap(), "str_release".into()),
+            (Regex::new("^str_upper$").unwrap(), "str_free".into()),
+        ],
+        ..options("owned")
+    });
+}
+
+#[test]
+fn nullable() {
+    golden("nullable", Options {
+        wrappers: true,
+        nullable_params: vec![Regex::new(r"^open\.opts$").unwrap()],
+        ..options("nullable")
+    });
+}
+
+#[test]
+fn pointer_ext() {
+    golden("pointer_ext", Options {
+        pointer_extensions: true,
+        ..options("pointer_ext")
+    });
+}
+
+#[test]
+fn aligned() {
+    golden("aligned", Options {
+        pointer_extensions: true,
+        ..options("aligned")
+    });
+}
+
+#[test]
+fn layout() {
+    golden("layout", Options {
+        target: Some("x86_64-unknown-linux-gnu".into()),
+        layout_checks: true,
+        ..options("layout")
+    });
+}
+
+#[test]
+fn debug() {
+    golden("debug", Options {
+        debug_strings: true,
+        ..options("debug")
+    });
+}
+
+#[test]
+fn legacy() {
+    golden("legacy", Options {
+        null_safety: false,
+        ..options("legacy")
+    });
+}
+
+#[test]
+fn legacy_top_level() {
+    golden("legacy_top_level", Options {
+        null_safety: false,
+        binding_shape: BindingShape::TopLevel,
+        ..options("legacy_top_level")
+    });
+}
+
+#[test]
+fn imports() {
+    golden("imports", Options {
+        imports: vec![
+            "src/helpers.dart".into(),
+            "package:meta/meta.dart".into(),
+            "package:ffi/ffi.dart".into(),
+            "dart:ffi".into(),
+        ],
+        ..options("imports")
+    });
+}
+
+#[test]
+fn lints() {
+    golden("lints", Options {
+        ignore_lints: vec!["non_constant_identifier_names".into(), "camel_case_types".into()],
+        ..options("lints")
+    });
+}
+
+#[test]
+fn features() {
+    golden("features", Options {
+        define_sets: vec![vec!["FEATURE_X=0".into()], vec!["FEATURE_X=1".into()]],
+        ..options("features")
+    });
+}
+
+#[test]
+fn gated() {
+    golden("gated", Options {
+        features: vec![("audio".into(), Regex::new("^audio_").unwrap())],
+        ..options("gated")
+    });
+}
+
+#[test]
+fn groups() {
+    golden("groups", Options {
+        groups: vec![("Misc".into(), Regex::new("^misc_").unwrap())],
+        group_banners: true,
+        ..options("groups")
+    });
+}
+
+#[test]
+fn order() {
+    golden("order", Options {
+        member_order: MemberOrder::Alphabetical,
+        ..options("order")
+    });
+}
+
+#[test]
+fn aliases() {
+    golden("aliases", Options {
+        pointer_aliases: 2,
+        ..options("aliases")
+    });
+}
+
+#[test]
+fn literals() {
+    golden("literals", Options {
+        macro_filter: Some(Regex::new("^LIT_").unwrap()),
+        ..options("literals")
+    });
+}
+
+#[test]
+fn gnu() {
+    golden("gnu", Options {
+        target: Some("x86_64-unknown-linux-gnu".into()),
+        ..options("gnu")
+    });
+}
+
+#[test]
+fn skip() {
+    golden("skip", Options {
+        skip_functions: vec![("legacy_reset".into(), "deprecated by vendor".into()),
+                             ("debug_dump".into(), "excluded by configuration".into())],
+        ..options("skip")
+    });
+}
+
+#[test]
+fn incomplete() {
+    golden("incomplete", options("incomplete"));
+}
+
+#[test]
+fn atomic() {
+    golden("atomic", Options {
+        target: Some("x86_64-unknown-linux-gnu".into()),
+        ..options("atomic")
+    });
+}
+
+#[test]
+fn complex() {
+    golden("complex", Options {
+        complex_structs: true,
+        ..options("complex")
+    });
+}
+
+#[test]
+fn vectors() {
+    golden("vectors", Options {
+        vector_structs: true,
+        ..options("vectors")
+    });
+}
+
+#[test]
+fn examples() {
+    golden("examples", Options {
+        examples: true,
+        ..options("examples")
+    });
+}
@@ -0,0 +1,67 @@
+use std::{fs::read_to_string, path::Path};
+use c4dart::{Style, translate_ir, merge_ir};
+
+#[test]
+fn from_ir() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures");
+    let ir = read_to_string(fixtures.join("ir.json")).unwrap();
+    let expected = read_to_string(fixtures.join("ir.dart")).unwrap();
+
+    let mut actual = Vec::new();
+    translate_ir(Style::default(), &ir, &mut actual).unwrap();
+
+    assert_eq!(String::from_utf8(actual).unwrap(), expected);
+}
+
+#[test]
+fn invalid_ir() {
+    let mut output = Vec::new();
+
+    let error = translate_ir(Style::default(), r#"[{"struct": {"name": 1}}]"#, &mut output).unwrap_err();
+    assert!(error.to_string().contains("StructDef.name"), "{}", error);
+
+    let error = translate_ir(Style::default(), "[{]", &mut output).unwrap_err();
+    assert!(error.to_string().contains("line 1"), "{}", error);
+}
+
+#[test]
+fn merge_platforms() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures");
+    let inputs = ["linux", "windows"].iter()
+        .map(|platform| (platform.to_string(),
+                         read_to_string(fixtures.join(format!("merge_{}.json", platform))).unwrap()))
+        .collect::<Vec<_>>();
+    let expected = read_to_string(fixtures.join("merge.dart")).unwrap();
+
+    let (ir, diagnostics) = merge_ir(&inputs).unwrap();
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].name.as_deref(), Some("stat_info"));
+    assert_eq!(diagnostics[0].reason, "Field `size` differs: `@Int64() int` on linux but `@Int32() int` on windows");
+
+    let mut actual = Vec::new();
+    translate_ir(Style::default(), &ir, &mut actual).unwrap();
+
+    assert_eq!(String::from_utf8(actual).unwrap(), expected);
+}
+
+#[test]
+fn merge_legacy_platforms() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures");
+    // the opted out code has no null assertions on calls of platform-specific functions
+    let inputs = ["linux", "windows"].iter()
+        .map(|platform| (platform.to_string(),
+                         read_to_string(fixtures.join(format!("merge_{}.json", platform))).unwrap()
+                         .replace(r#""init_name": "","#,
+                                  r#""init_name": "", "legacy": true, "forwarders": [["fileSize", "file_size"], ["fileInode", "file_inode"]],"#)))
+        .collect::<Vec<_>>();
+
+    let (ir, _) = merge_ir(&inputs).unwrap();
+
+    let mut actual = Vec::new();
+    translate_ir(Style::default(), &ir, &mut actual).unwrap();
+    let actual = String::from_utf8(actual).unwrap();
+
+    assert!(actual.contains("int fileSize(Pointer<Int8> path) => file_size(path);"), "{}", actual);
+    assert!(actual.contains("int fileInode(Pointer<Int8> path) => file_inode(path);"), "{}", actual);
+}
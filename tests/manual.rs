@@ -0,0 +1,64 @@
+use c4dart::merge_manual_regions;
+
+#[test]
+fn regions_preserved() {
+    let previous = "\
+class foo {
+    final int Function() bar;
+    // c4dart:begin-manual foo
+    int get answer => 42;
+    // c4dart:end-manual
+}
+// c4dart:begin-manual file
+const extra = 1;
+// c4dart:end-manual
+";
+    let generated = "\
+class foo {
+    final int Function() bar;
+    final int Function() baz;
+    // c4dart:begin-manual foo
+    // c4dart:end-manual
+}
+// c4dart:begin-manual file
+// c4dart:end-manual
+";
+    let expected = "\
+class foo {
+    final int Function() bar;
+    final int Function() baz;
+    // c4dart:begin-manual foo
+    int get answer => 42;
+    // c4dart:end-manual
+}
+// c4dart:begin-manual file
+const extra = 1;
+// c4dart:end-manual
+";
+
+    assert_eq!(merge_manual_regions(previous, generated), expected);
+}
+
+#[test]
+fn orphan_regions_appended() {
+    let previous = "\
+class old {
+    // c4dart:begin-manual old
+    int get answer => 42;
+    // c4dart:end-manual
+}
+";
+    let generated = "\
+class new {
+}
+";
+    let expected = "\
+class new {
+}
+// c4dart:begin-manual old
+    int get answer => 42;
+// c4dart:end-manual
+";
+
+    assert_eq!(merge_manual_regions(previous, generated), expected);
+}
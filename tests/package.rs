@@ -0,0 +1,62 @@
+use std::fs::{read_to_string, remove_dir_all, write};
+use c4dart::{Requirements, package_name, package_bindings_path, write_package};
+
+#[test]
+fn package_names() {
+    assert_eq!(package_name("sqlite3"), "sqlite3");
+    assert_eq!(package_name("LibCurl"), "lib_curl");
+    assert_eq!(package_name("FooBAR2"), "foo_bar2");
+    assert_eq!(package_name("foo-bar"), "foo_bar");
+    assert_eq!(package_name("3d"), "lib_3d");
+}
+
+#[test]
+fn package_files() {
+    let dir = std::env::temp_dir().join(format!("c4dart-package-{}", std::process::id()));
+    let _ = remove_dir_all(&dir);
+
+    assert_eq!(package_bindings_path(&dir, "foo"), dir.join("lib/src/foo_bindings.dart"));
+
+    write_package(&dir, "foo", &Requirements::default()).unwrap();
+
+    let pubspec = read_to_string(dir.join("pubspec.yaml")).unwrap();
+    assert!(pubspec.starts_with("name: foo\n"));
+    assert!(pubspec.contains("\n  sdk: '>=2.12.0 <4.0.0'\n"));
+    assert!(pubspec.contains("\n  ffi: "));
+
+    let library = read_to_string(dir.join("lib/foo.dart")).unwrap();
+    assert!(library.ends_with("\nexport 'src/foo_bindings.dart';\n"));
+
+    // edited pubspec is kept
+    write(dir.join("pubspec.yaml"), "name: foo\nversion: 1.0.0\n").unwrap();
+    write_package(&dir, "foo", &Requirements::default()).unwrap();
+    assert_eq!(read_to_string(dir.join("pubspec.yaml")).unwrap(), "name: foo\nversion: 1.0.0\n");
+
+    remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn package_requirements() {
+    let dir = std::env::temp_dir().join(format!("c4dart-requirements-{}", std::process::id()));
+    let _ = remove_dir_all(&dir);
+
+    let requirements = Requirements {
+        sdk: (2, 19),
+        packages: vec!["meta".to_string(), "foo".to_string(), "json".to_string()].into_iter().collect(),
+        legacy: false,
+    };
+    write_package(&dir, "foo", &requirements).unwrap();
+
+    let pubspec = read_to_string(dir.join("pubspec.yaml")).unwrap();
+    assert!(pubspec.contains("\n  sdk: '>=2.19.0 <4.0.0'\n"));
+    assert!(pubspec.ends_with("\n  ffi: '>=1.0.0 <3.0.0'\n  json: any\n  meta: '>=1.3.0 <2.0.0'\n"));
+    remove_dir_all(&dir).unwrap();
+
+    // the opted out code is not supported by Dart 3
+    write_package(&dir, "foo", &Requirements { legacy: true, ..Requirements::default() }).unwrap();
+
+    let pubspec = read_to_string(dir.join("pubspec.yaml")).unwrap();
+    assert!(pubspec.contains("\n  sdk: '>=2.12.0 <3.0.0'\n"));
+    assert!(pubspec.ends_with("\n  ffi: '>=1.0.0 <3.0.0'\n"));
+    remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,19 @@
+use c4dart::split_response_file;
+
+#[test]
+fn plain_arguments() {
+    assert_eq!(split_response_file("-I include\n  -DFOO=1\t--target x86_64-pc-windows-msvc\n"),
+               ["-I", "include", "-DFOO=1", "--target", "x86_64-pc-windows-msvc"]);
+}
+
+#[test]
+fn quoted_arguments() {
+    assert_eq!(split_response_file(r#"-I "Program Files/sdk" -D'NAME="value"' """#),
+               ["-I", "Program Files/sdk", "-DNAME=\"value\"", ""]);
+}
+
+#[test]
+fn windows_paths() {
+    assert_eq!(split_response_file(r#"-I C:\sdk\include -I "C:\Program Files\sdk" a\ b"#),
+               ["-I", r"C:\sdk\include", "-I", r"C:\Program Files\sdk", "a b"]);
+}